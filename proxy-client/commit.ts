@@ -168,6 +168,8 @@ export interface Commit {
   branches: string[];
   diff: Diff;
   header: CommitHeader;
+  // Headers of the parent commits. Empty for the root commit.
+  parents: CommitHeader[];
   stats: CommitStats;
 }
 
@@ -175,6 +177,7 @@ export const commitSchema: zod.Schema<Commit> = zod.object({
   branches: zod.array(zod.string()),
   diff: diffSchema,
   header: commitHeaderSchema,
+  parents: zod.array(commitHeaderSchema),
   stats: zod.object({
     additions: zod.number(),
     deletions: zod.number(),
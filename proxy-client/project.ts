@@ -42,6 +42,7 @@ const projectSchema: zod.Schema<Project> = zod.object({
     branches: zod.number(),
     commits: zod.number(),
     contributors: zod.number(),
+    approximate: zod.boolean(),
   }),
   seed: zod.string().url().nullable(),
 });
@@ -60,6 +61,9 @@ export interface Stats {
   branches: number;
   commits: number;
   contributors: number;
+  // If true, `commits` and `contributors` only take part of the history
+  // into account and are lower bounds.
+  approximate: boolean;
 }
 
 interface NewRepo {
@@ -138,6 +138,23 @@ interface TreeGetParams {
   prefix: string;
 }
 
+// The name of a branch or tag. Refs of a remote peer also carry the ID of
+// that peer.
+export type ScopedRef = string | { name: string; remote: string };
+
+const scopedRefSchema: zod.Schema<ScopedRef> = zod.union([
+  zod.string(),
+  zod.object({
+    name: zod.string(),
+    remote: zod.string(),
+  }),
+]);
+
+// Returns the name of a branch or tag regardless of the peer it belongs to.
+export function scopedRefName(ref: ScopedRef): string {
+  return typeof ref === "string" ? ref : ref.name;
+}
+
 interface RefsGetParams {
   projectUrn: string;
   peerId?: string;
@@ -184,7 +201,7 @@ export class Client {
   public async branchesGet(
     params: RefsGetParams,
     options?: RequestOptions
-  ): Promise<string[]> {
+  ): Promise<ScopedRef[]> {
     return this.fetcher.fetchOk(
       {
         method: "GET",
@@ -194,7 +211,7 @@ export class Client {
         },
         options,
       },
-      zod.array(zod.string())
+      zod.array(scopedRefSchema)
     );
   }
 
@@ -220,7 +237,7 @@ export class Client {
   public async tagsGet(
     params: RefsGetParams,
     options?: RequestOptions
-  ): Promise<string[]> {
+  ): Promise<ScopedRef[]> {
     return this.fetcher.fetchOk(
       {
         method: "GET",
@@ -230,7 +247,7 @@ export class Client {
         },
         options,
       },
-      zod.array(zod.string())
+      zod.array(scopedRefSchema)
     );
   }
 
@@ -19,7 +19,7 @@ import type {
   Stats,
   Tag,
 } from "proxy-client/source";
-import { RevisionType } from "proxy-client/source";
+import { RevisionType, scopedRefName } from "proxy-client/source";
 
 export type {
   Blob,
@@ -155,22 +155,22 @@ export async function fetchRevisions(
   peerId?: string,
   options?: proxy.RequestOptions
 ): Promise<Revisions> {
-  const [branchNames, tagNames] = await Promise.all([
+  const [branchRefs, tagRefs] = await Promise.all([
     proxy.client.source.branchesGet({ projectUrn, peerId }, options),
     proxy.client.source.tagsGet({ projectUrn, peerId }, options),
   ]);
 
-  const branches = branchNames.map(
-    (name): Branch => ({
+  const branches = branchRefs.map(
+    (ref): Branch => ({
       type: RevisionType.Branch,
-      name,
+      name: scopedRefName(ref),
     })
   );
 
-  const tags = tagNames.map(
-    (name): Tag => ({
+  const tags = tagRefs.map(
+    (ref): Tag => ({
       type: RevisionType.Tag,
-      name,
+      name: scopedRefName(ref),
     })
   );
   return { branches, tags };
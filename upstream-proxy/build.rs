@@ -0,0 +1,74 @@
+// Copyright © 2022 The Radicle Upstream Contributors
+//
+// This file is part of radicle-upstream, distributed under the GPLv3
+// with Radicle Linking Exception. For full terms see the included
+// LICENSE file.
+
+use std::{path::Path, process::Command};
+
+fn main() {
+    let hash = Command::new("git")
+        .arg("rev-parse")
+        .arg("--short")
+        .arg("HEAD")
+        .output()
+        .ok()
+        .and_then(|output| {
+            if output.status.success() {
+                String::from_utf8(output.stdout).ok()
+            } else {
+                None
+            }
+        })
+        .unwrap_or_else(|| String::from("unknown"));
+
+    println!("cargo:rustc-env=GIT_HEAD={}", hash.trim());
+
+    // New commits move the branch `HEAD` points to, not `HEAD` itself.
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    if let Some(branch) = std::fs::read_to_string("../.git/HEAD")
+        .ok()
+        .and_then(|head| {
+            head.strip_prefix("ref: ")
+                .map(|branch| branch.trim().to_string())
+        })
+    {
+        println!("cargo:rerun-if-changed=../.git/{}", branch);
+    }
+
+    // The lock file of the workspace is not tracked by git, but cargo writes it before any build
+    // script runs. It is only missing if the crate is built outside of the workspace.
+    println!("cargo:rerun-if-changed=../Cargo.lock");
+    let librad_version = std::env::var("CARGO_MANIFEST_DIR")
+        .ok()
+        .and_then(|dir| std::fs::read_to_string(Path::new(&dir).join("../Cargo.lock")).ok())
+        .and_then(|lock| locked_version(&lock, "librad"))
+        .unwrap_or_else(|| String::from("unknown"));
+
+    println!("cargo:rustc-env=LIBRAD_VERSION={}", librad_version);
+}
+
+/// Finds the version of the package `name` in the contents of a `Cargo.lock` file. For git
+/// dependencies the commit the package is pinned to is appended, e.g. `0.1.0+997c1bb`.
+fn locked_version(lock: &str, name: &str) -> Option<String> {
+    let package = lock.split("[[package]]").find(|package| {
+        package
+            .lines()
+            .any(|line| line == format!("name = \"{}\"", name))
+    })?;
+    let field = |key: &str| {
+        package.lines().find_map(|line| {
+            line.strip_prefix(&format!("{} = \"", key))
+                .and_then(|value| value.strip_suffix('"'))
+        })
+    };
+
+    let version = field("version")?;
+    match field("source")
+        .filter(|source| source.starts_with("git+"))
+        .and_then(|source| source.rsplit_once('#'))
+    {
+        Some((_, commit)) => Some(format!("{}+{}", version, &commit[..commit.len().min(7)])),
+        None => Some(version.to_string()),
+    }
+}
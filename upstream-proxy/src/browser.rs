@@ -7,13 +7,18 @@
 use std::convert::TryFrom as _;
 
 use librad::git::types::{Reference, Single};
-use radicle_source::{error, surf::vcs::git};
+use link_crypto::PeerId;
+use radicle_source::{error, surf::vcs::git, Revision};
+use tokio_util::sync::CancellationToken;
 
 use crate::error::Error;
 
 /// Provide a repo [`git::Browser`] where the `Browser` is initialised with the provided
 /// `reference`.
 ///
+/// If the reference has a `remote` the browser operates on that peer's view of the project.
+/// Pass a peer id to [`crate::daemon::state::get_branch`] to obtain such a reference.
+///
 /// See [`crate::daemon::state::find_default_branch`] and [`crate::daemon::state::get_branch`] for
 /// obtaining a [`Reference`].
 ///
@@ -39,18 +44,135 @@ where
     )
     .map_err(error::Error::from)?;
 
-    let branch = match reference.remote {
+    let branch = branch(&reference);
+    let monorepo = crate::daemon::state::monorepo(peer.librad_peer());
+    let repo = git::Repository::new(monorepo).map_err(error::Error::from)?;
+    let mut browser =
+        git::Browser::new_with_namespace(&repo, &namespace, branch).map_err(error::Error::from)?;
+
+    Ok(callback(&mut browser)?)
+}
+
+/// Like [`using`], but instead of a [`git::Browser`] `callback` is passed the monorepo and the
+/// commit `revision` resolves to, see [`crate::source::resolve_revision`]. If `revision` is
+/// `None` the commit `reference` points to is used.
+///
+/// Setting up a [`git::Browser`] walks the complete history of the reference. Operations that
+/// only need part of the history should use this and walk it with
+/// [`crate::source::history`].
+///
+/// # Errors
+///   * If we could not open the monorepo.
+///   * If `revision` could not be resolved.
+///   * If the callback provided returned an error.
+pub fn using_commit<T, F>(
+    peer: &crate::peer::Peer,
+    reference: Reference<Single>,
+    revision: Option<Revision<PeerId>>,
+    callback: F,
+) -> Result<T, Error>
+where
+    F: FnOnce(&git2::Repository, git2::Oid) -> Result<T, Error>,
+{
+    let repo = monorepo(peer)?;
+    let head = crate::source::resolve_revision(&repo, &reference, revision)?;
+
+    callback(&repo, head)
+}
+
+/// Opens the monorepo of `peer` for operations that work on git objects directly.
+///
+/// # Errors
+///   * If we could not open the monorepo.
+pub fn monorepo(peer: &crate::peer::Peer) -> Result<git2::Repository, Error> {
+    let monorepo = crate::daemon::state::monorepo(peer.librad_peer());
+    Ok(git2::Repository::open(monorepo)
+        .map_err(|err| error::Error::from(git::error::Error::from(err)))?)
+}
+
+/// Like [`using_commit`], but runs `callback` on a blocking thread and also passes it
+/// `reference` and a token that is cancelled as soon as the returned future is dropped, e.g.
+/// because the client of an HTTP request disconnected. Long walks should check the token while
+/// they walk and give up with [`Error::Cancelled`].
+///
+/// Anything that has to be held until the walk is done, like a
+/// [`crate::http::HistoryWalkPermit`], should be moved into `callback`, the walk outlives the
+/// returned future until it checks the token.
+///
+/// # Errors
+///   * See [`using_commit`].
+///   * If the blocking thread panicked.
+pub async fn using_cancellable<T, F>(
+    peer: &crate::peer::Peer,
+    reference: Reference<Single>,
+    revision: Option<Revision<PeerId>>,
+    callback: F,
+) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce(
+            &git2::Repository,
+            &Reference<Single>,
+            git2::Oid,
+            &CancellationToken,
+        ) -> Result<T, Error>
+        + Send
+        + 'static,
+{
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
+    let peer = peer.clone();
+    tokio::task::spawn_blocking(move || {
+        using_commit(&peer, reference.clone(), revision, |repo, head| {
+            callback(repo, &reference, head, &cancel)
+        })
+    })
+    .await
+    .map_err(|err| Error::Other(err.into()))?
+}
+
+/// The [`git::Branch`] `reference` points to. Remote references are resolved in the ref
+/// namespace of the remote peer.
+fn branch(reference: &Reference<Single>) -> git::Branch {
+    match reference.remote {
         None => git::Branch::local(reference.name.as_str()),
         Some(peer) => git::Branch::remote(
             &format!("heads/{}", reference.name.as_str()),
             &peer.to_string(),
         ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use librad::{
+        git::{types::Namespace, Urn},
+        git_ext::RefLike,
+        PeerId,
     };
 
-    let monorepo = crate::daemon::state::monorepo(peer.librad_peer());
-    let repo = git::Repository::new(monorepo).map_err(error::Error::from)?;
-    let mut browser =
-        git::Browser::new_with_namespace(&repo, &namespace, branch).map_err(error::Error::from)?;
+    fn reference(remote: Option<PeerId>) -> Reference<Single> {
+        Reference::head(
+            Namespace::from(Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()))),
+            remote,
+            RefLike::try_from("master").unwrap(),
+        )
+    }
 
-    Ok(callback(&mut browser)?)
+    #[test]
+    fn branch_local() {
+        assert_eq!(branch(&reference(None)), git::Branch::local("master"));
+    }
+
+    #[test]
+    fn branch_remote() {
+        let remote = PeerId::from(link_crypto::SecretKey::new());
+        assert_eq!(
+            branch(&reference(Some(remote))),
+            git::Branch::remote("heads/master", &remote.to_string())
+        );
+    }
 }
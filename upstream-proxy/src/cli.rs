@@ -19,10 +19,71 @@ pub struct Args {
     )]
     pub http_listen: std::net::SocketAddr,
 
+    /// Origin that is allowed to make cross-origin requests to the HTTP API. Can be specified
+    /// multiple times. If not provided, requests from any origin are allowed.
+    #[clap(
+        long = "http-allowed-origin",
+        env = "RADICLE_PROXY_HTTP_ALLOWED_ORIGINS",
+        use_value_delimiter = true
+    )]
+    pub http_allowed_origins: Option<Vec<http::HeaderValue>>,
+
+    /// Disallow credentials in cross-origin requests to the HTTP API.
+    #[clap(long)]
+    pub http_disallow_credentials: bool,
+
+    /// Serve the HTTP API read-only. All requests that may modify state, except for unsealing
+    /// the keystore, are rejected.
+    #[clap(long = "readonly")]
+    pub read_only: bool,
+
+    /// Serve HTTP endpoints for debugging repositories, e.g. listing the objects of a project.
+    #[clap(long)]
+    pub http_debug_endpoints: bool,
+
+    /// Program and arguments, separated by commas, that are run in every working copy checked out
+    /// through the HTTP API, e.g. `--post-checkout npm,install`.
+    #[clap(long, use_value_delimiter = true)]
+    pub post_checkout: Option<Vec<String>>,
+
+    /// Maximum size in bytes of JSON request bodies accepted by the HTTP API.
+    #[clap(long, default_value_t = crate::http::DEFAULT_MAX_JSON_BODY_BYTES)]
+    pub http_max_json_body_bytes: u64,
+
+    /// Maximum size in bytes of git bundles uploaded to the HTTP API.
+    #[clap(long, default_value_t = crate::http::DEFAULT_MAX_BUNDLE_BODY_BYTES)]
+    pub http_max_bundle_body_bytes: u64,
+
+    /// Maximum number of HTTP requests walking project histories, e.g. listing commits, at the
+    /// same time. Must be at least 1.
+    #[clap(long, default_value_t = crate::http::DEFAULT_MAX_CONCURRENT_HISTORY_WALKS)]
+    pub http_max_concurrent_history_walks: std::num::NonZeroUsize,
+
+    /// Seconds an HTTP request waits for other history walks to finish before it fails with
+    /// `503 Service Unavailable`.
+    #[clap(long, default_value_t = crate::http::DEFAULT_HISTORY_WALK_TIMEOUT.as_secs())]
+    pub http_history_walk_timeout_secs: u64,
+
     /// Run the peer on a specified address:port
     #[clap(long, env = "RADICLE_PROXY_PEER_LISTEN", default_value = "0.0.0.0:0")]
     pub peer_listen: std::net::SocketAddr,
 
+    /// Maximum number of peers to actively gossip with.
+    #[clap(long)]
+    pub gossip_max_active: Option<usize>,
+
+    /// Maximum number of known peers that are not actively gossiped with.
+    #[clap(long)]
+    pub gossip_max_passive: Option<usize>,
+
+    /// Interval at which known peers are shuffled with other peers in seconds.
+    #[clap(long)]
+    pub gossip_shuffle_interval: Option<u64>,
+
+    /// Interval at which known peers are promoted to active gossip peers in seconds.
+    #[clap(long)]
+    pub gossip_promote_interval: Option<u64>,
+
     /// Passphrase to unlock the keystore. If not provided the keystore must be unlocked via the
     /// HTTP API.
     #[clap(long, env = "RADICLE_PROXY_KEY_PASSPHRASE")]
@@ -36,6 +97,16 @@ pub struct Args {
     #[clap(long)]
     pub dev_log: bool,
 
+    /// Format of the log output. Defaults to the format named by the `TRACING_FMT` environment
+    /// variable, which is ignored if it names no known format, and then to `pretty` with
+    /// `--dev-log` and to `full`.
+    #[clap(long, arg_enum)]
+    pub log_format: Option<crate::logging::LogFormat>,
+
+    /// Minimum level of the log records. Ignored if `RUST_LOG` is set.
+    #[clap(long, env = "RADICLE_PROXY_LOG_LEVEL", default_value = "info")]
+    pub log_level: tracing_subscriber::filter::LevelFilter,
+
     /// URL of a seed to fetch identities via Git over HTTP. Can be specified multiple times.
     #[clap(
         long,
@@ -56,3 +127,25 @@ impl Args {
         <Self as clap::Parser>::parse()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn history_walks_must_be_allowed() {
+        let parse = |max: &str| {
+            <Args as clap::Parser>::try_parse_from([
+                "upstream-proxy",
+                "--http-max-concurrent-history-walks",
+                max,
+            ])
+        };
+
+        assert_eq!(
+            parse("2").unwrap().http_max_concurrent_history_walks.get(),
+            2
+        );
+        assert!(parse("0").is_err());
+    }
+}
@@ -5,7 +5,10 @@
 
 //! Crate configuration.
 
-use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
 
 use futures::stream::BoxStream;
 
@@ -17,27 +20,102 @@ lazy_static::lazy_static! {
         SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 0));
 }
 
+/// Errors when building a [`net::peer::Config`].
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A gossip parameter is out of bounds.
+    #[error("invalid gossip parameter `{name}`: {reason}")]
+    InvalidGossipParam {
+        /// Name of the offending parameter.
+        name: &'static str,
+        /// Why the value was rejected.
+        reason: String,
+    },
+}
+
+/// Tuning of the gossip membership protocol. Parameters that are not set fall back to the
+/// defaults of [`net::protocol::membership::Params`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GossipConfig {
+    /// Maximum number of peers we actively gossip with, i.e. the fanout.
+    pub max_active: Option<usize>,
+    /// Maximum number of peers we know about but don't actively gossip with.
+    pub max_passive: Option<usize>,
+    /// Interval at which the passive view is shuffled with other peers.
+    pub shuffle_interval: Option<Duration>,
+    /// Interval at which passive peers are promoted to the active view.
+    pub promote_interval: Option<Duration>,
+}
+
+impl GossipConfig {
+    /// Builds the membership parameters, checking that they are within bounds.
+    fn params(self) -> Result<net::protocol::membership::Params, Error> {
+        let defaults = net::protocol::membership::Params::default();
+        let params = net::protocol::membership::Params {
+            max_active: self.max_active.unwrap_or(defaults.max_active),
+            max_passive: self.max_passive.unwrap_or(defaults.max_passive),
+            shuffle_interval: self.shuffle_interval.unwrap_or(defaults.shuffle_interval),
+            promote_interval: self.promote_interval.unwrap_or(defaults.promote_interval),
+            ..defaults
+        };
+
+        if params.max_active == 0 {
+            return Err(Error::InvalidGossipParam {
+                name: "max_active",
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        if params.max_passive < params.max_active {
+            return Err(Error::InvalidGossipParam {
+                name: "max_passive",
+                reason: format!("must be at least max_active ({})", params.max_active),
+            });
+        }
+        for (name, interval) in [
+            ("shuffle_interval", params.shuffle_interval),
+            ("promote_interval", params.promote_interval),
+        ] {
+            if interval.is_zero() {
+                return Err(Error::InvalidGossipParam {
+                    name,
+                    reason: "must be greater than zero".to_string(),
+                });
+            }
+        }
+
+        Ok(params)
+    }
+}
+
 /// Configure a [`net::peer::Config`].
-#[must_use]
-pub fn configure<S>(paths: paths::Paths, signer: S, listen_addr: SocketAddr) -> net::peer::Config<S>
+///
+/// # Errors
+///
+///   * A parameter of `gossip` is out of bounds.
+pub fn configure<S>(
+    paths: paths::Paths,
+    signer: S,
+    listen_addr: SocketAddr,
+    gossip: GossipConfig,
+) -> Result<net::peer::Config<S>, Error>
 where
     S: Signer + Clone + Send + Sync + 'static,
     S::Error: std::error::Error + Send + Sync + 'static,
 {
-    net::peer::Config {
+    Ok(net::peer::Config {
         signer,
         protocol: net::protocol::Config {
             paths,
             listen_addr,
             advertised_addrs: None,
-            membership: net::protocol::membership::Params::default(),
+            membership: gossip.params()?,
             network: net::Network::default(),
             replication: net::replication::Config::default(),
             rate_limits: net::protocol::Quota::default(),
             request_pull: net::protocol::config::DenyAll,
         },
         storage: net::peer::config::Storage::default(),
-    }
+    })
 }
 
 /// Discovery that never provides a boostrap peer
@@ -52,3 +130,60 @@ impl discovery::Discovery for NoDiscovery {
         Box::pin(futures::stream::pending())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn gossip_defaults() {
+        let defaults = net::protocol::membership::Params::default();
+        let params = GossipConfig::default().params().unwrap();
+        assert_eq!(params.max_active, defaults.max_active);
+        assert_eq!(params.max_passive, defaults.max_passive);
+        assert_eq!(params.shuffle_interval, defaults.shuffle_interval);
+        assert_eq!(params.promote_interval, defaults.promote_interval);
+    }
+
+    #[test]
+    fn gossip_custom() {
+        let params = GossipConfig {
+            max_active: Some(2),
+            max_passive: Some(4),
+            shuffle_interval: Some(Duration::from_secs(120)),
+            promote_interval: None,
+        }
+        .params()
+        .unwrap();
+        assert_eq!(params.max_active, 2);
+        assert_eq!(params.max_passive, 4);
+        assert_eq!(params.shuffle_interval, Duration::from_secs(120));
+    }
+
+    #[test]
+    fn gossip_out_of_bounds() {
+        let invalid = [
+            GossipConfig {
+                max_active: Some(0),
+                ..GossipConfig::default()
+            },
+            GossipConfig {
+                max_active: Some(4),
+                max_passive: Some(2),
+                ..GossipConfig::default()
+            },
+            GossipConfig {
+                promote_interval: Some(Duration::ZERO),
+                ..GossipConfig::default()
+            },
+        ];
+        for config in invalid {
+            assert!(
+                matches!(config.params(), Err(Error::InvalidGossipParam { .. })),
+                "expected {:?} to be rejected",
+                config
+            );
+        }
+    }
+}
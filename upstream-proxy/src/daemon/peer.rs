@@ -37,6 +37,8 @@ pub use run_state::{config as run_config, Config as RunConfig, Event, Status, Wa
 mod subroutines;
 use subroutines::Subroutines;
 
+pub mod request_history;
+
 mod waiting_room;
 
 /// Upper bound of messages stored in receiver channels.
@@ -203,6 +205,7 @@ where
                 subscriber,
                 control_receiver,
             )
+            .await
             .run()
             .fuse()
             .map_err(Error::Join);
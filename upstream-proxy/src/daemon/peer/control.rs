@@ -31,6 +31,8 @@ pub enum Request {
     ),
     /// List all project searches.
     ListSearches(oneshot::Sender<Vec<request::SomeRequest<SystemTime>>>),
+    /// Request the metrics of project searches.
+    SearchMetrics(oneshot::Sender<waiting_room::Metrics>),
     /// Initiate a search for a project on the network.
     StartSearch(
         Urn,
@@ -55,6 +57,11 @@ pub enum Response {
         oneshot::Sender<Vec<request::SomeRequest<SystemTime>>>,
         Vec<request::SomeRequest<SystemTime>>,
     ),
+    /// Response to a project search metrics request.
+    SearchMetrics(
+        oneshot::Sender<waiting_room::Metrics>,
+        waiting_room::Metrics,
+    ),
     /// Response to a start project search request.
     StartSearch(
         oneshot::Sender<waiting_room::Created<SystemTime>>,
@@ -132,6 +139,18 @@ impl Control {
         receiver.await.expect("receiver is gone")
     }
 
+    /// Get the metrics of project requests.
+    pub async fn get_project_request_metrics(&mut self) -> waiting_room::Metrics {
+        let (sender, receiver) = oneshot::channel::<waiting_room::Metrics>();
+
+        self.sender
+            .send(Request::SearchMetrics(sender))
+            .await
+            .expect("peer is gone");
+
+        receiver.await.expect("receiver is gone")
+    }
+
     /// Initiate a new request for the `urn`.
     pub async fn request_project(
         &mut self,
@@ -12,7 +12,7 @@ use librad::{
         peer::Peer,
         protocol::gossip::{Payload, Rev},
     },
-    PeerId, Signer,
+    Signer,
 };
 
 /// Announce a new rev for the `urn`.
@@ -30,16 +30,15 @@ where
     }
 }
 
-/// Emit a [`Payload`] request for the given `urn`.
-pub fn query<S>(peer: &Peer<S>, urn: &Urn, origin: Option<PeerId>)
+/// Emit a [`Payload`] request, e.g. one built by
+/// [`crate::daemon::request::SomeRequest::to_gossip`].
+pub fn query<S>(peer: &Peer<S>, payload: Payload)
 where
     S: Clone + Signer,
 {
-    match peer.query(Payload {
-        urn: urn.clone(),
-        rev: None,
-        origin,
-    }) {
+    let urn = payload.urn.clone();
+    let origin = payload.origin;
+    match peer.query(payload) {
         Ok(()) => tracing::trace!(%urn, ?origin, "successfully queried URN"),
         Err(_payload) => tracing::warn!(%urn, "failed to query URN"),
     };
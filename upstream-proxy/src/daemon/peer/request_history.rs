@@ -0,0 +1,155 @@
+// Copyright © 2022 The Radicle Upstream Contributors
+//
+// This file is part of radicle-upstream, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! Append-only log of the outcomes of requests that left the
+//! [`crate::daemon::request::waiting_room::WaitingRoom`].
+
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use librad::{git::Urn, PeerId};
+
+use crate::daemon::request::{Attempts, Millis, SomeRequest};
+
+/// Name for the bucket used in [`kv::Store`].
+const BUCKET_NAME: &str = "request_history";
+
+/// The terminal state a request ended up in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FinalState {
+    /// The identity was cloned.
+    Cloned,
+    /// The request was cancelled.
+    Cancelled,
+    /// The request made too many query or clone attempts.
+    TimedOut,
+}
+
+/// The outcome of a single request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    /// The identity that was requested.
+    pub urn: Urn,
+    /// The state the request ended up in.
+    pub final_state: FinalState,
+    /// The number of attempts the request made.
+    pub attempts: Attempts,
+    /// When the request was created, if it is known.
+    pub started_at: Option<Millis<SystemTime>>,
+    /// When the request reached its final state.
+    pub ended_at: Millis<SystemTime>,
+    /// The peer the identity was cloned from.
+    pub served_by: Option<PeerId>,
+}
+
+impl Entry {
+    /// Create the history entry for `request` if it is in a terminal state.
+    #[must_use]
+    pub fn terminal(urn: &Urn, request: &SomeRequest<SystemTime>) -> Option<Self> {
+        let (final_state, served_by) = match request {
+            SomeRequest::Cloned(request) => (FinalState::Cloned, Some(request.remote_peer)),
+            SomeRequest::Cancelled(_) => (FinalState::Cancelled, None),
+            SomeRequest::TimedOut(_) => (FinalState::TimedOut, None),
+            SomeRequest::Created(_)
+            | SomeRequest::Requested(_)
+            | SomeRequest::Found(_)
+            | SomeRequest::Cloning(_) => return None,
+        };
+
+        Some(Self {
+            urn: Urn {
+                path: None,
+                ..urn.clone()
+            },
+            final_state,
+            attempts: *request.attempts(),
+            started_at: request.started_at().copied().map(Millis),
+            ended_at: Millis(*request.timestamp()),
+            served_by,
+        })
+    }
+}
+
+/// Append `entry` to the history.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+/// * if the storage of the entry fails
+pub fn append(store: &kv::Store, entry: Entry) -> Result<(), kv::Error> {
+    let bucket = store.bucket::<kv::Integer, kv::Json<Entry>>(Some(BUCKET_NAME))?;
+    bucket.set(store.generate_id()?, kv::Json(entry))
+}
+
+/// List all entries of the history, oldest first.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+/// * if an entry can't be read
+pub fn list(store: &kv::Store) -> Result<Vec<Entry>, kv::Error> {
+    let bucket = store.bucket::<kv::Integer, kv::Json<Entry>>(Some(BUCKET_NAME))?;
+    bucket
+        .iter()
+        .map(|item| Ok(item?.value::<kv::Json<Entry>>()?.0))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use pretty_assertions::assert_eq;
+
+    use librad::{git::Urn, PeerId};
+
+    use crate::daemon::request::{waiting_room, Millis};
+
+    use super::{append, list, Entry, FinalState};
+
+    #[test]
+    fn cloned_request_is_recorded() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let store = kv::Store::new(kv::Config::new(tmp_dir.path()))?;
+        let mut waiting_room: waiting_room::WaitingRoom<SystemTime, Duration> =
+            waiting_room::WaitingRoom::new(waiting_room::Config::default());
+        let urn = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        let peer = PeerId::from(link_crypto::SecretKey::new());
+        let started_at = SystemTime::UNIX_EPOCH + Duration::from_millis(1_000);
+        let ended_at = started_at + Duration::from_millis(500);
+
+        let _request = waiting_room.request(&urn, started_at);
+        waiting_room.queried(&urn, started_at)?;
+        waiting_room.found(&urn, peer, started_at)?;
+        assert_eq!(
+            Entry::terminal(&urn, waiting_room.get(&urn).expect("missing request")),
+            None
+        );
+        waiting_room.cloning(&urn, peer, started_at)?;
+        waiting_room.cloned(&urn, peer, ended_at)?;
+
+        let entry = Entry::terminal(&urn, waiting_room.get(&urn).expect("missing request"))
+            .expect("cloned request has no history entry");
+        append(&store, entry.clone())?;
+
+        let history = list(&store)?;
+        assert_eq!(history, vec![entry]);
+        assert_eq!(history[0].final_state, FinalState::Cloned);
+        assert_eq!(history[0].served_by, Some(peer));
+        assert_eq!(history[0].started_at, Some(Millis(started_at)));
+        assert_eq!(history[0].ended_at, Millis(ended_at));
+
+        let json = serde_json::to_value(&history[0])?;
+        assert_eq!(json["finalState"], "cloned");
+        assert_eq!(json["servedBy"], serde_json::to_value(peer)?);
+        assert_eq!(json["startedAt"], 1_000);
+        assert_eq!(json["endedAt"], 1_500);
+
+        Ok(())
+    }
+}
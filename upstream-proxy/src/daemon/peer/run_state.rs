@@ -160,14 +160,17 @@ pub struct RunState {
 }
 
 impl RunState {
-    /// Creates a new `RunState` initialising it with the provided `config` and
-    /// `waiting_room`.
-    pub fn new(waiting_room: WaitingRoom<SystemTime, Duration>) -> Self {
+    /// Creates a new `RunState` initialising it with the provided `waiting_room`,
+    /// evicting terminal requests after `terminal_retention`.
+    pub fn new(
+        waiting_room: WaitingRoom<SystemTime, Duration>,
+        terminal_retention: Duration,
+    ) -> Self {
         Self {
             listen_addrs: vec![],
             stats: downstream::Stats::default(),
             status: Status::Stopped,
-            waiting_room: RunningWaitingRoom::new(waiting_room),
+            waiting_room: RunningWaitingRoom::new(waiting_room, terminal_retention),
         }
     }
 
@@ -222,6 +225,11 @@ impl RunState {
                         .collect::<Vec<_>>(),
                 )),
             )],
+            input::Control::RequestMetrics(sender) => {
+                vec![Command::Control(command::Control::Respond(
+                    control::Response::SearchMetrics(sender, self.waiting_room.metrics()),
+                ))]
+            },
             input::Control::ListenAddrs(sender) => {
                 vec![Command::Control(command::Control::Respond(
                     control::Response::ListenAddrs(sender, self.listen_addrs.clone()),
@@ -284,10 +292,9 @@ impl RunState {
             (Status::Online { .. }, input::Request::Tick) => {
                 self.waiting_room.tick(SystemTime::now())
             },
-            (_, input::Request::Cloning(urn, remote_peer)) => {
-                self.waiting_room
-                    .cloning(&urn, remote_peer, SystemTime::now())
-            },
+            // The request was marked as cloning when the clone was started by
+            // the tick, see [`RunningWaitingRoom::tick`].
+            (_, input::Request::Cloning(..)) => Vec::new(),
             (_, input::Request::Cloned(urn, remote_peer)) => {
                 self.waiting_room
                     .cloned(&urn, remote_peer, SystemTime::now())
@@ -5,9 +5,12 @@
 
 use std::time::{Duration, SystemTime};
 
-use librad::{git::Urn, PeerId};
+use librad::{git::Urn, net::protocol::gossip::Payload, PeerId};
 
-use crate::daemon::{peer::control, request::waiting_room::WaitingRoom};
+use crate::daemon::{
+    peer::{control, request_history},
+    request::waiting_room::WaitingRoom,
+};
 
 /// Instructions to issue side-effectful operations which are the results from
 /// state transitions.
@@ -21,6 +24,8 @@ pub enum Command {
     Include(Urn),
     /// Tell the subroutine to persist the [`WaitingRoom`].
     PersistWaitingRoom(WaitingRoom<SystemTime, Duration>),
+    /// Tell the subroutine to append the outcome of a finished request to the history.
+    RecordRequestHistory(request_history::Entry),
     /// Fulfill request commands.
     Request(Request),
     Stats,
@@ -41,9 +46,9 @@ pub enum Request {
     /// Tell the subroutine to attempt a clone from the given [`Urn`] and
     /// [`PeerId`].
     Clone(Urn, PeerId),
-    /// Tell the subroutine that we should query for the given [`Urn`] on the
-    /// network.
-    Query(Urn),
+    /// Tell the subroutine that we should query the network with the given
+    /// [`Payload`], see [`crate::daemon::request::SomeRequest::to_gossip`].
+    Query(Payload),
     /// The request for [`Urn`] timed out.
     TimedOut(Urn),
 }
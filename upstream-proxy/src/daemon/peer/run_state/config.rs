@@ -7,6 +7,8 @@
 
 use std::time::Duration;
 
+use librad::git::Urn;
+
 /// Default time to wait between announcement subroutine runs.
 const DEFAULT_ANNOUNCE_INTERVAL: Duration = std::time::Duration::from_secs(1);
 
@@ -15,6 +17,9 @@ const DEFAULT_STATS_INTERVAL: Duration = Duration::from_millis(1000);
 /// Default period at which we query the waiting room.
 const DEFAULT_WAITING_ROOM_INTERVAL: Duration = Duration::from_millis(500);
 
+/// Default period for which requests in a terminal state are kept in the waiting room.
+const DEFAULT_TERMINAL_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 /// Default period to consider until a query has timed out.
 pub const DEFAULT_WAITING_ROOM_TIMEOUT: Duration = Duration::from_secs(10);
 
@@ -66,12 +71,25 @@ pub struct WaitingRoom {
     /// Interval at which to query the
     /// [`crate::daemon::request::waiting_room::WaitingRoom`] for ready requests.
     pub interval: Duration,
+    /// Period after which a clone of a requested project is abandoned, so a single
+    /// unreachable peer doesn't stall the request. Clones of large projects can take a long
+    /// time, so by default they are never abandoned.
+    pub clone_timeout: Option<Duration>,
+    /// Identities to request from the network on startup, unless a request for them is
+    /// already in the [`crate::daemon::request::waiting_room::WaitingRoom`].
+    pub startup_requests: Vec<Urn>,
+    /// Period after which requests that were cloned, cancelled or timed out are evicted
+    /// from the [`crate::daemon::request::waiting_room::WaitingRoom`].
+    pub terminal_retention: Duration,
 }
 
 impl Default for WaitingRoom {
     fn default() -> Self {
         Self {
             interval: DEFAULT_WAITING_ROOM_INTERVAL,
+            clone_timeout: None,
+            startup_requests: Vec::new(),
+            terminal_retention: DEFAULT_TERMINAL_RETENTION,
         }
     }
 }
@@ -61,6 +61,8 @@ pub enum Control {
     ),
     /// Request the list of project searches.
     ListRequests(oneshot::Sender<Vec<SomeRequest<SystemTime>>>),
+    /// Request the metrics of project searches.
+    RequestMetrics(oneshot::Sender<waiting_room::Metrics>),
 }
 
 /// Request event for projects requested from the network.
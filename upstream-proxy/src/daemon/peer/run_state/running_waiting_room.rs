@@ -10,11 +10,15 @@ use std::{
     time::{Duration, SystemTime},
 };
 
-use crate::daemon::request::SomeRequest;
+use crate::daemon::{
+    peer::request_history,
+    request::{RequestState, SomeRequest},
+};
 
 use super::{
-    command, control, waiting_room::Error as WaitingRoomError, Command, Event as RunStateEvent,
-    WaitingRoom,
+    command, control,
+    waiting_room::{self, Error as WaitingRoomError},
+    Command, Event as RunStateEvent, WaitingRoom,
 };
 use tokio::sync::oneshot::Sender;
 
@@ -85,11 +89,19 @@ pub enum Event {
 /// the values  returned by `WaitingRoom` methods into `Vec<Command>`.
 pub(super) struct RunningWaitingRoom {
     waiting_room: WaitingRoom<SystemTime, Duration>,
+    /// How long requests in a terminal state are kept before they are evicted.
+    terminal_retention: Duration,
 }
 
 impl RunningWaitingRoom {
-    pub const fn new(waiting_room: WaitingRoom<SystemTime, Duration>) -> Self {
-        Self { waiting_room }
+    pub const fn new(
+        waiting_room: WaitingRoom<SystemTime, Duration>,
+        terminal_retention: Duration,
+    ) -> Self {
+        Self {
+            waiting_room,
+            terminal_retention,
+        }
     }
 
     pub fn cancel(
@@ -103,20 +115,23 @@ impl RunningWaitingRoom {
             Ok(()) => {
                 let request = self.waiting_room.remove(&urn);
                 let state_after = self.waiting_room.requests();
+                let history = Self::history_entry(&state_before, &urn, request.as_ref());
                 let transition = WaitingRoomTransition {
                     timestamp,
                     state_before,
                     state_after,
                     event: Event::Canceled { urn },
                 };
-                vec![
+                let mut commands = vec![
                     Command::Control(command::Control::Respond(control::Response::CancelSearch(
                         sender,
                         Ok(request),
                     ))),
                     Command::PersistWaitingRoom(self.waiting_room.clone()),
                     Command::EmitEvent(transition.into()),
-                ]
+                ];
+                commands.extend(history.map(Command::RecordRequestHistory));
+                commands
             },
             Err(e) => vec![
                 Command::Control(command::Control::Respond(control::Response::CancelSearch(
@@ -168,6 +183,11 @@ impl RunningWaitingRoom {
         self.waiting_room.get(urn)
     }
 
+    /// Return the current [`waiting_room::Metrics`] of the `WaitingRoom`.
+    pub fn metrics(&self) -> waiting_room::Metrics {
+        self.waiting_room.metrics()
+    }
+
     /// Return the list of all `Urn`/`SomeRequest` pairs in the `WaitingRoom`.
     pub fn iter(&self) -> impl Iterator<Item = (Urn, &SomeRequest<SystemTime>)> {
         self.waiting_room.iter()
@@ -187,36 +207,102 @@ impl RunningWaitingRoom {
     }
 
     /// Issue "query" and "clone" requests for requests that are next in the
-    /// queue.
+    /// queue, and evict terminal requests that are past their retention.
+    ///
+    /// Clones are started with [`WaitingRoom::process`], which marks the
+    /// requests as cloning right away so the next tick doesn't start them
+    /// again. Requests that ran out of clone attempts time out instead.
+    ///
+    /// Evicted requests are not recorded in the request history again, since
+    /// their entry was recorded when they reached their terminal state.
     pub fn tick(&mut self, timestamp: SystemTime) -> Vec<Command> {
-        let mut cmds = Vec::with_capacity(2);
+        let mut cmds = Vec::with_capacity(3);
 
-        if let Some(urn) = self.waiting_room.next_query(timestamp) {
-            cmds.push(Command::Request(command::Request::Query(urn)));
+        let evicted = self
+            .waiting_room
+            .evict_terminal(self.terminal_retention, timestamp);
+        if !evicted.is_empty() {
+            for (urn, request) in &evicted {
+                tracing::debug!(%urn, state = %RequestState::from(request), "Evicted request");
+            }
+            cmds.push(Command::PersistWaitingRoom(self.waiting_room.clone()));
         }
-        if let Some((urn, remote_peer)) = self.waiting_room.next_clone() {
-            cmds.push(Command::Request(command::Request::Clone(urn, remote_peer)));
+
+        if let Some(request) = self
+            .waiting_room
+            .next_query(timestamp)
+            .and_then(|urn| self.waiting_room.get(&urn))
+        {
+            cmds.push(Command::Request(command::Request::Query(
+                request.to_gossip(),
+            )));
         }
+        cmds.extend(self.process(timestamp));
 
         cmds
     }
 
-    pub fn cloning(
-        &mut self,
-        urn: &Urn,
-        remote_peer: PeerId,
-        timestamp: SystemTime,
-    ) -> Vec<Command> {
-        Self::simple_command_helper(
-            &mut self.waiting_room,
-            urn,
-            Event::Cloning {
-                urn: urn.clone(),
-                peer: remote_peer,
-            },
-            timestamp,
-            |w| w.cloning(urn, remote_peer, timestamp),
-        )
+    /// Start a clone for every found request that has a peer left to clone
+    /// from, see [`WaitingRoom::process`].
+    fn process(&mut self, timestamp: SystemTime) -> Vec<Command> {
+        let found = self
+            .waiting_room
+            .iter()
+            .filter(|(_, request)| RequestState::from(*request) == RequestState::Found)
+            .map(|(urn, _)| urn)
+            .collect::<Vec<_>>();
+        if found.is_empty() {
+            return Vec::new();
+        }
+
+        let state_before = self.waiting_room.requests();
+        let clones = self.waiting_room.process(timestamp);
+        let state_after = self.waiting_room.requests();
+
+        let mut cmds = vec![Command::PersistWaitingRoom(self.waiting_room.clone())];
+        for urn in found {
+            let request = self.waiting_room.get(&urn);
+            cmds.extend(
+                Self::history_entry(&state_before, &urn, request)
+                    .map(Command::RecordRequestHistory),
+            );
+            if let Some(SomeRequest::TimedOut(request)) = request {
+                let attempts = match WaitingRoomError::from(request.clone()) {
+                    WaitingRoomError::TimeOut { attempts, .. } => attempts,
+                    _ => None,
+                };
+                cmds.push(Command::EmitEvent(
+                    WaitingRoomTransition {
+                        timestamp,
+                        state_before: state_before.clone(),
+                        state_after: state_after.clone(),
+                        event: Event::TimedOut {
+                            urn: urn.clone(),
+                            attempts,
+                        },
+                    }
+                    .into(),
+                ));
+                cmds.push(Command::Request(command::Request::TimedOut(urn)));
+            }
+        }
+        for (urn, remote_peer) in clones {
+            cmds.push(Command::EmitEvent(
+                WaitingRoomTransition {
+                    timestamp,
+                    state_before: state_before.clone(),
+                    state_after: state_after.clone(),
+                    event: Event::Cloning {
+                        urn: urn.clone(),
+                        peer: remote_peer,
+                    },
+                }
+                .into(),
+            ));
+            cmds.push(Command::Request(command::Request::Clone(urn, remote_peer)));
+        }
+
+        cmds
     }
 
     pub fn cloned(
@@ -267,6 +353,23 @@ impl RunningWaitingRoom {
         )
     }
 
+    /// The history entry for `request` if the request for `urn` just reached a terminal state.
+    fn history_entry(
+        state_before: &HashMap<Revision, SomeRequest<SystemTime>>,
+        urn: &Urn,
+        request: Option<&SomeRequest<SystemTime>>,
+    ) -> Option<request_history::Entry> {
+        let already_terminal = state_before
+            .get(&urn.id)
+            .and_then(|before| request_history::Entry::terminal(urn, before))
+            .is_some();
+        if already_terminal {
+            return None;
+        }
+
+        request.and_then(|request| request_history::Entry::terminal(urn, request))
+    }
+
     fn simple_command_helper<F>(
         waiting_room: &mut WaitingRoom<SystemTime, Duration>,
         urn: &Urn,
@@ -280,8 +383,12 @@ impl RunningWaitingRoom {
         let state_before = waiting_room.requests();
         let result = f(waiting_room);
         let state_after = waiting_room.requests();
-        let mut commands = Vec::with_capacity(4);
+        let mut commands = Vec::with_capacity(5);
         commands.push(Command::PersistWaitingRoom(waiting_room.clone()));
+        commands.extend(
+            Self::history_entry(&state_before, urn, waiting_room.get(urn))
+                .map(Command::RecordRequestHistory),
+        );
         match result {
             Ok(()) => {
                 commands.push(Command::EmitEvent(
@@ -327,3 +434,88 @@ pub struct WaitingRoomTransition<T> {
     pub state_after: HashMap<Revision, SomeRequest<T>>,
     pub event: Event,
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use pretty_assertions::assert_eq;
+
+    use librad::{git::Urn, PeerId};
+
+    use crate::daemon::request::{waiting_room, Clones};
+
+    use super::{command, request_history, Command, RunningWaitingRoom, WaitingRoom};
+
+    fn history(commands: &[Command]) -> Vec<&request_history::Entry> {
+        commands
+            .iter()
+            .filter_map(|cmd| match cmd {
+                Command::RecordRequestHistory(entry) => Some(entry),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn clones(commands: &[Command]) -> Vec<(Urn, PeerId)> {
+        commands
+            .iter()
+            .filter_map(|cmd| match cmd {
+                Command::Request(command::Request::Clone(urn, peer)) => Some((urn.clone(), *peer)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tick_records_requests_that_ran_out_of_clones() {
+        let mut waiting_room = WaitingRoom::new(waiting_room::Config {
+            max_clones: Clones::Max(1),
+            ..waiting_room::Config::default()
+        });
+        let urn = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        let first = PeerId::from(link_crypto::SecretKey::new());
+        let second = PeerId::from(link_crypto::SecretKey::new());
+        let timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+        let _request = waiting_room.request(&urn, timestamp);
+        waiting_room.queried(&urn, timestamp).unwrap();
+        waiting_room.found(&urn, first, timestamp).unwrap();
+        waiting_room.found(&urn, second, timestamp).unwrap();
+        let mut running = RunningWaitingRoom::new(waiting_room, Duration::from_secs(60));
+
+        let commands = running.tick(timestamp);
+        let started = clones(&commands);
+        assert_eq!(started, vec![(urn.clone(), first.min(second))]);
+        assert!(history(&commands).is_empty());
+        // The request is cloning now, so the next tick doesn't start it again.
+        assert!(clones(&running.tick(timestamp)).is_empty());
+
+        running.cloning_failed(
+            &urn,
+            started[0].1,
+            timestamp,
+            Box::new(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "peer unreachable",
+            )),
+        );
+        // Cloning from the other peer would exceed the clone attempts.
+        let commands = running.tick(timestamp);
+        assert!(clones(&commands).is_empty());
+        let entries = history(&commands);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].urn, urn);
+        assert_eq!(
+            entries[0].final_state,
+            request_history::FinalState::TimedOut
+        );
+        assert_eq!(entries[0].served_by, None);
+        assert!(commands.iter().any(|cmd| matches!(
+            cmd,
+            Command::Request(command::Request::TimedOut(timed_out)) if *timed_out == urn
+        )));
+
+        // The request already reached its terminal state and isn't recorded again.
+        assert!(history(&running.tick(timestamp)).is_empty());
+    }
+}
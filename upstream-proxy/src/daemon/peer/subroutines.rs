@@ -32,7 +32,7 @@ use crate::daemon::{
 };
 
 use super::{
-    announcement, control, gossip, include,
+    announcement, control, gossip, include, request_history,
     run_state::{command, config, input, Command, Config as RunConfig, Event, Input, RunState},
     waiting_room, RECEIVER_CAPACITY,
 };
@@ -49,6 +49,8 @@ pub struct Subroutines<S> {
     peer: net::peer::Peer<S>,
     /// [`kv::Store`] for suborutine task fulfillment.
     store: kv::Store,
+    /// Period after which a clone subroutine is abandoned, if any.
+    clone_timeout: Option<Duration>,
 
     /// Main peer state machine.
     run_state: RunState,
@@ -66,7 +68,10 @@ where
     S: Clone + Signer,
 {
     /// Constructs a new subroutines manager.
-    pub fn new(
+    ///
+    /// The startup requests of `run_config` are added to the persisted waiting room, except for
+    /// the identities that are already present in the monorepo.
+    pub async fn new(
         peer: net::peer::Peer<S>,
         mut listen_addrs: watch::Receiver<Vec<SocketAddr>>,
         store: kv::Store,
@@ -80,7 +85,7 @@ where
         } else {
             Some(interval(run_config.announce.interval))
         };
-        let waiting_room = match waiting_room::load(&store) {
+        let mut waiting_room = match waiting_room::load(&store) {
             Err(err) => {
                 tracing::warn!(?err, "Failed to load waiting room");
                 WaitingRoom::new(request::waiting_room::Config {
@@ -94,11 +99,19 @@ where
             }),
             Ok(Some(room)) => room,
         };
+        let created = waiting_room.enqueue_many(
+            missing_identities(&peer, &run_config.waiting_room.startup_requests).await,
+            SystemTime::now(),
+        );
+        if created > 0 {
+            tracing::info!(created, "Enqueued startup requests");
+            persist_waiting_room(waiting_room.clone(), store.clone()).await;
+        }
         let mut waiting_room_timer = interval(run_config.waiting_room.interval);
         let (input_sender, mut external_inputs) = mpsc::channel::<Input>(RECEIVER_CAPACITY);
         let mut stats_timer = interval(run_config.stats.interval);
 
-        let run_state = RunState::new(waiting_room);
+        let run_state = RunState::new(waiting_room, run_config.waiting_room.terminal_retention);
 
         let inputs = {
             let mut coalesced = SelectAll::new();
@@ -173,6 +186,9 @@ where
                     control::Request::ListSearches(sender) => {
                         Input::Control(input::Control::ListRequests(sender))
                     },
+                    control::Request::SearchMetrics(sender) => {
+                        Input::Control(input::Control::RequestMetrics(sender))
+                    },
                     control::Request::StartSearch(urn, time, sender) => {
                         Input::Control(input::Control::CreateRequest(urn, time, sender))
                     },
@@ -197,6 +213,7 @@ where
 
             peer,
             store,
+            clone_timeout: run_config.waiting_room.clone_timeout,
             run_state,
 
             subscriber,
@@ -221,12 +238,16 @@ where
             Command::PersistWaitingRoom(waiting_room) => {
                 tokio::spawn(persist_waiting_room(waiting_room, self.store.clone()))
             },
-            Command::Request(command::Request::Query(urn)) => {
-                tokio::spawn(query(urn, self.peer.clone(), self.input_sender.clone()))
+            Command::RecordRequestHistory(entry) => {
+                tokio::spawn(record_request_history(entry, self.store.clone()))
+            },
+            Command::Request(command::Request::Query(payload)) => {
+                tokio::spawn(query(payload, self.peer.clone(), self.input_sender.clone()))
             },
             Command::Request(command::Request::Clone(urn, remote_peer)) => tokio::spawn(clone(
                 urn,
                 remote_peer,
+                self.clone_timeout,
                 self.peer.clone(),
                 self.input_sender.clone(),
             )),
@@ -332,6 +353,7 @@ async fn control_respond(cmd: control::Response) {
         control::Response::CancelSearch(sender, request) => sender.send(request).ok(),
         control::Response::ListenAddrs(sender, addrs) => sender.send(addrs).ok(),
         control::Response::ListSearches(sender, requests) => sender.send(requests).ok(),
+        control::Response::SearchMetrics(sender, metrics) => sender.send(metrics).ok(),
         control::Response::StartSearch(sender, request) => sender.send(request).ok(),
     };
 }
@@ -356,12 +378,44 @@ async fn persist_waiting_room(waiting_room: WaitingRoom<SystemTime, Duration>, s
     }
 }
 
-/// Send a query on the network for the given urn.
-async fn query<S>(urn: Urn, peer: net::peer::Peer<S>, sender: mpsc::Sender<Input>)
+/// The `urns` that are not present in the monorepo of `peer` yet. URNs that can't be checked are
+/// kept, so they are requested anyway.
+async fn missing_identities<S>(peer: &net::peer::Peer<S>, urns: &[Urn]) -> Vec<Urn>
 where
     S: Clone + Signer,
 {
-    gossip::query(&peer, &urn, None);
+    let mut missing = Vec::with_capacity(urns.len());
+    for urn in urns {
+        match state::project_exists(peer, urn.clone()).await {
+            Ok(true) => tracing::debug!(%urn, "Skipping startup request for local identity"),
+            Ok(false) => missing.push(urn.clone()),
+            Err(err) => {
+                tracing::warn!(%urn, ?err, "Failed to check for local identity");
+                missing.push(urn.clone());
+            },
+        }
+    }
+    missing
+}
+
+#[allow(clippy::unused_async)]
+async fn record_request_history(entry: request_history::Entry, store: kv::Store) {
+    match request_history::append(&store, entry) {
+        Ok(()) => tracing::debug!("Successfully recorded the request history"),
+        Err(err) => tracing::warn!(?err, "Error while recording the request history"),
+    }
+}
+
+/// Send a query on the network with the given payload.
+async fn query<S>(
+    payload: net::protocol::gossip::Payload,
+    peer: net::peer::Peer<S>,
+    sender: mpsc::Sender<Input>,
+) where
+    S: Clone + Signer,
+{
+    let urn = payload.urn.clone();
+    gossip::query(&peer, payload);
     sender
         .send(Input::Request(input::Request::Queried(urn)))
         .await
@@ -369,10 +423,11 @@ where
 }
 
 /// Run a clone for the given `url`. On completion report back with the success
-/// or failure.
+/// or failure. The clone fails if it doesn't complete within `timeout`.
 async fn clone<S>(
     urn: Urn,
     remote_peer: PeerId,
+    timeout: Option<Duration>,
     peer: net::peer::Peer<S>,
     sender: mpsc::Sender<Input>,
 ) where
@@ -386,7 +441,7 @@ async fn clone<S>(
         .await
         .ok();
 
-    match state::clone_project(&peer, urn.clone(), remote_peer, None).await {
+    match state::clone_project_with_timeout(&peer, urn.clone(), remote_peer, None, timeout).await {
         Ok(_urn) => {
             sender
                 .send(Input::Request(input::Request::Cloned(
@@ -415,3 +470,36 @@ async fn clone<S>(
         },
     }
 }
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use librad::git::Urn;
+
+    use super::missing_identities;
+
+    #[tokio::test]
+    async fn startup_requests_skip_local_identities() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+        let owner = crate::daemon::state::init_owner(
+            peer,
+            crate::identity::Metadata {
+                handle: "alice".to_string(),
+                ethereum: None,
+            },
+        )
+        .await
+        .unwrap();
+        let local = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+        let remote = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+
+        assert_eq!(
+            missing_identities(peer, &[local, remote.clone()]).await,
+            vec![remote]
+        );
+    }
+}
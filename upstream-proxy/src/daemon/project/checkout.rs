@@ -9,6 +9,8 @@ use std::{
     path::{self, PathBuf},
 };
 
+use serde::{Deserialize, Serialize};
+
 use librad::{
     git::{
         include,
@@ -30,6 +32,33 @@ pub enum Error {
     #[error("the path provided '{0}' already exists")]
     AlreadExists(PathBuf),
 
+    /// The project name or the owner handle can't be used as a directory name, because the
+    /// working copy would end up outside of the path provided.
+    #[error("'{0}' is not a valid directory name")]
+    InvalidDirectoryName(String),
+
+    /// The branch to check out doesn't exist in the monorepo.
+    #[error("the branch '{0}' does not exist")]
+    BranchNotFound(String),
+
+    /// The include file can't be written.
+    #[error("the include file '{0}' is not writable")]
+    IncludeNotWritable(PathBuf),
+
+    /// The post-checkout command failed. The working copy was created nonetheless.
+    #[error(
+        "the working copy '{}' was created, but the post-checkout command '{command}' failed: {failure}",
+        path.display()
+    )]
+    PostCheckout {
+        /// The working copy the command was run in.
+        path: PathBuf,
+        /// The command as it was given.
+        command: String,
+        /// How the command failed.
+        failure: PostCheckoutFailure,
+    },
+
     /// Git error when checking out the project.
     #[error(transparent)]
     Git(#[from] git2::Error),
@@ -51,6 +80,35 @@ pub enum Error {
     Transport(#[from] librad::git::local::transport::Error),
 }
 
+/// How the post-checkout command of a [`Checkout`] failed, see [`Error::PostCheckout`].
+#[derive(Debug, thiserror::Error)]
+pub enum PostCheckoutFailure {
+    /// The command could not be started.
+    #[error("{0}")]
+    Spawn(io::Error),
+
+    /// The command exited unsuccessfully.
+    #[error("it exited with {0}")]
+    Status(std::process::ExitStatus),
+}
+
+/// How the directory of the working copy is derived from the path provided to [`Checkout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PathLayout {
+    /// The working copy is placed in `<path>/<name>`, or in `<path>` itself if it already ends
+    /// in the project name.
+    Flat,
+    /// The working copy is placed in `<path>/<owner>/<name>`.
+    NamespacedByOwner,
+}
+
+impl Default for PathLayout {
+    fn default() -> Self {
+        Self::Flat
+    }
+}
+
 /// The data necessary for checking out a project.
 pub struct Checkout<P>
 where
@@ -64,6 +122,13 @@ where
     pub default_branch: OneLevel,
     /// The path on the filesystem where we're going to checkout to.
     pub path: P,
+    /// How the directory of the working copy is derived from `path`.
+    pub layout: PathLayout,
+    /// The handle of the peer whose copy of the project is checked out, used by
+    /// [`PathLayout::NamespacedByOwner`].
+    pub owner: String,
+    /// Program and arguments that are run in the working copy after it was checked out.
+    pub post_checkout: Option<Vec<String>>,
     /// Absolute path of the include file that will be set in the working copy
     /// config.
     pub include_path: PathBuf,
@@ -229,6 +294,84 @@ impl<P> Checkout<P>
 where
     P: AsRef<path::Path>,
 {
+    /// The directory the working copy is placed in according to the `layout`.
+    ///
+    /// # Errors
+    ///  * If the project name or the owner handle isn't a single path component, which would place
+    ///    the working copy outside of `path`.
+    pub fn project_path(&self) -> Result<PathBuf, Error> {
+        let path = self.path.as_ref();
+        let name = directory_name(&self.name)?;
+        match self.layout {
+            // Check if the path provided ends in the 'directory_name' provided. If not we
+            // create the full path to that name.
+            PathLayout::Flat => {
+                Ok(path
+                    .components()
+                    .next_back()
+                    .map_or(path.join(name), |destination| {
+                        let destination: &ffi::OsStr = destination.as_ref();
+                        if destination == name {
+                            path.to_path_buf()
+                        } else {
+                            path.join(name)
+                        }
+                    }))
+            },
+            PathLayout::NamespacedByOwner => Ok(path.join(directory_name(&self.owner)?).join(name)),
+        }
+    }
+
+    /// Checks whether [`Checkout::run`] is able to create the working copy, without cloning
+    /// anything. The checks are that
+    ///
+    ///  * the directory of the working copy is empty or doesn't exist,
+    ///  * the default branch of `peer_id`, or of the local peer if it's `None`, exists in the
+    ///    `monorepo`,
+    ///  * the include file can be written.
+    ///
+    /// # Errors
+    ///  * If any of the checks fails.
+    ///  * If the `monorepo` can't be read.
+    pub fn validate(
+        &self,
+        monorepo: &git2::Repository,
+        peer_id: Option<PeerId>,
+    ) -> Result<(), Error> {
+        let project_path = self.project_path()?;
+        crate::daemon::project::ensure_directory(&project_path)?
+            .ok_or_else(|| Error::AlreadExists(project_path.clone()))?;
+
+        let branch = match peer_id {
+            None => format!("refs/heads/{}", self.default_branch),
+            Some(peer_id) => format!("refs/remotes/{}/heads/{}", peer_id, self.default_branch),
+        };
+        let reference = format!("refs/namespaces/{}/{}", self.urn.encode_id(), branch);
+        match monorepo.find_reference(&reference) {
+            Ok(_) => {},
+            Err(err) if err.code() == git2::ErrorCode::NotFound => {
+                return Err(Error::BranchNotFound(self.default_branch.to_string()))
+            },
+            Err(err) => return Err(err.into()),
+        }
+
+        // The include file is overwritten, so either it or the directory it is created in must
+        // be writable.
+        let writable = |path: &path::Path| {
+            std::fs::metadata(path).map_or(false, |metadata| !metadata.permissions().readonly())
+        };
+        let include_writable = if self.include_path.exists() {
+            writable(&self.include_path)
+        } else {
+            self.include_path.parent().map_or(false, writable)
+        };
+        if !include_writable {
+            return Err(Error::IncludeNotWritable(self.include_path.clone()));
+        }
+
+        Ok(())
+    }
+
     /// Based off of the `Ownership`, clone the project using the provided
     /// inputs.
     ///
@@ -279,27 +422,17 @@ where
     ///  * If the project cloning fails.
     ///  * If we cannot set the upstream branch for the `rad` remote.
     ///  * If we cannot set the include path for the working copy.
+    ///  * If the `post_checkout` command can't be run or exits unsuccessfully.
     pub fn run<F>(self, open_storage: F, ownership: Ownership) -> Result<PathBuf, Error>
     where
         F: CanOpenStorage + Clone + 'static,
     {
-        // Check if the path provided ends in the 'directory_name' provided. If not we
-        // create the full path to that name.
-        let path = &self.path.as_ref();
-        let project_path: PathBuf =
-            path.components()
-                .next_back()
-                .map_or(path.join(&self.name), |destination| {
-                    let destination: &ffi::OsStr = destination.as_ref();
-                    let name: &ffi::OsStr = self.name.as_ref();
-                    if destination == name {
-                        path.to_path_buf()
-                    } else {
-                        path.join(name)
-                    }
-                });
+        let project_path = self.project_path()?;
         crate::daemon::project::ensure_directory(&project_path)?
             .ok_or_else(|| Error::AlreadExists(project_path.clone()))?;
+        if let Some(parent) = project_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
 
         // Clone the repository
         let (repo, rad) =
@@ -311,6 +444,184 @@ where
         repo.set_head(Qualified::from(self.default_branch).as_str())?;
         repo.checkout_head(None)?;
 
+        if let Some(command) = &self.post_checkout {
+            post_checkout(command, &project_path)?;
+        }
+
         Ok(project_path)
     }
 }
+
+/// Runs the program and arguments in `command` in the working copy at `path`. The command
+/// inherits the environment of the process. Does nothing if `command` is empty.
+fn post_checkout(command: &[String], path: &path::Path) -> Result<(), Error> {
+    let (program, args) = match command.split_first() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+
+    let failed = |failure| Error::PostCheckout {
+        path: path.to_path_buf(),
+        command: command.join(" "),
+        failure,
+    };
+    let status = std::process::Command::new(program)
+        .args(args)
+        .current_dir(path)
+        .status()
+        .map_err(|err| failed(PostCheckoutFailure::Spawn(err)))?;
+    if !status.success() {
+        return Err(failed(PostCheckoutFailure::Status(status)));
+    }
+
+    Ok(())
+}
+
+/// `name` as a single directory name, so that joining it to a path stays below that path.
+///
+/// # Errors
+///
+/// [`Error::InvalidDirectoryName`] if `name` is empty, `.`, `..` or has several components.
+pub fn directory_name(name: &str) -> Result<&ffi::OsStr, Error> {
+    let mut components = path::Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(path::Component::Normal(component)), None) => Ok(component),
+        _ => Err(Error::InvalidDirectoryName(name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn checkout(path: &str, name: &str, layout: PathLayout, owner: &str) -> Checkout<PathBuf> {
+        Checkout {
+            urn: Urn::new(git_ext::Oid::from(git2::Oid::zero())),
+            name: name.to_string(),
+            default_branch: OneLevel::from(reflike!("main")),
+            path: PathBuf::from(path),
+            layout,
+            owner: owner.to_string(),
+            post_checkout: None,
+            include_path: PathBuf::from("/include"),
+        }
+    }
+
+    /// Creates a monorepo in `dir` with the branch `main` of the project `urn`.
+    fn monorepo(dir: &path::Path, urn: &Urn) -> git2::Repository {
+        let repo = git2::Repository::init_bare(dir).unwrap();
+        let oid = {
+            let tree = repo
+                .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+                .unwrap();
+            let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+            repo.commit(None, &signature, &signature, "Initial commit", &tree, &[])
+                .unwrap()
+        };
+        repo.reference(
+            &format!("refs/namespaces/{}/refs/heads/main", urn.encode_id()),
+            oid,
+            false,
+            "create main",
+        )
+        .unwrap();
+        repo
+    }
+
+    #[test]
+    fn validate_checkout() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let code = temp_dir.path().join("code");
+        let mut checkout = checkout(code.to_str().unwrap(), "alpha", PathLayout::Flat, "alice");
+        checkout.include_path = temp_dir.path().join("alpha.inc");
+        let monorepo = monorepo(&temp_dir.path().join("monorepo"), &checkout.urn);
+
+        checkout.validate(&monorepo, None).unwrap();
+        assert!(!code.exists());
+
+        let peer_id = PeerId::from(link_crypto::SecretKey::new());
+        assert!(matches!(
+            checkout.validate(&monorepo, Some(peer_id)),
+            Err(Error::BranchNotFound(_))
+        ));
+
+        std::fs::create_dir_all(code.join("alpha")).unwrap();
+        std::fs::write(code.join("alpha").join("README.md"), "alpha").unwrap();
+        assert!(matches!(
+            checkout.validate(&monorepo, None),
+            Err(Error::AlreadExists(_))
+        ));
+    }
+
+    #[test]
+    fn post_checkout_runs_in_working_copy() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let command = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        post_checkout(&command(&["touch", "marker"]), temp_dir.path()).unwrap();
+        assert!(temp_dir.path().join("marker").exists());
+
+        post_checkout(&[], temp_dir.path()).unwrap();
+        assert!(matches!(
+            post_checkout(&command(&["sh", "-c", "exit 3"]), temp_dir.path()),
+            Err(Error::PostCheckout {
+                path,
+                failure: PostCheckoutFailure::Status(status),
+                ..
+            }) if path == temp_dir.path() && status.code() == Some(3)
+        ));
+        assert!(matches!(
+            post_checkout(&command(&["does-not-exist"]), temp_dir.path()),
+            Err(Error::PostCheckout {
+                failure: PostCheckoutFailure::Spawn(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn project_path_flat() {
+        assert_eq!(
+            checkout("/code", "alpha", PathLayout::Flat, "alice")
+                .project_path()
+                .unwrap(),
+            PathBuf::from("/code/alpha")
+        );
+        assert_eq!(
+            checkout("/code/alpha", "alpha", PathLayout::Flat, "alice")
+                .project_path()
+                .unwrap(),
+            PathBuf::from("/code/alpha")
+        );
+    }
+
+    #[test]
+    fn project_path_namespaced_by_owner() {
+        assert_eq!(
+            checkout("/code", "alpha", PathLayout::NamespacedByOwner, "alice")
+                .project_path()
+                .unwrap(),
+            PathBuf::from("/code/alice/alpha")
+        );
+    }
+
+    #[test]
+    fn project_path_outside_of_path() {
+        for (name, owner) in [
+            ("..", "alice"),
+            ("a/b", "alice"),
+            ("alpha", "../alice"),
+            ("alpha", ""),
+        ] {
+            assert!(matches!(
+                checkout("/code", name, PathLayout::NamespacedByOwner, owner).project_path(),
+                Err(Error::InvalidDirectoryName(_))
+            ));
+        }
+        assert!(matches!(
+            checkout("/code", "/etc", PathLayout::Flat, "alice").project_path(),
+            Err(Error::InvalidDirectoryName(_))
+        ));
+    }
+}
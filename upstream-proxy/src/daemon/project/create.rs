@@ -21,6 +21,10 @@ pub enum Error {
     /// An error occurred while validating input.
     #[error(transparent)]
     Validation(#[from] validation::Error),
+
+    /// The initial commit of a new project could not be signed.
+    #[error(transparent)]
+    Signing(#[from] crate::signed_commit::Error),
 }
 
 /// The signature of a git author. Used internally to convert into a
@@ -88,6 +92,9 @@ pub struct Create {
     pub default_branch: OneLevel,
     /// What kind of working copy we're working with, i.e. new or existing.
     pub repo: Repo,
+    /// Whether the initial commit of a new repository is signed with the peer key.
+    #[serde(default)]
+    pub sign: bool,
 }
 
 impl Create {
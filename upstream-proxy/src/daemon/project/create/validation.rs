@@ -190,16 +190,22 @@ impl Repository {
 
     /// Initialise the [`git2::Repository`].
     ///
+    /// If `signer` is given, the initial commit of a new repository is signed with it. Existing
+    /// repositories are left untouched.
+    ///
     /// # Errors
     ///
     ///   * Failed to setup the repository
-    pub fn setup_repo<F>(
+    ///   * Failed to sign the initial commit
+    pub fn setup_repo<F, S>(
         self,
         open_storage: F,
         description: &str,
+        signer: Option<&S>,
     ) -> Result<git2::Repository, super::Error>
     where
         F: CanOpenStorage + Clone + 'static,
+        S: librad::Signer,
     {
         match self {
             Self::Existing {
@@ -225,6 +231,7 @@ impl Repository {
                     &repo,
                     &default_branch,
                     &git2::Signature::try_from(signature)?,
+                    signer,
                 )?;
                 let mut remote =
                     Self::setup_remote(&repo, open_storage.clone(), url, &default_branch)?;
@@ -265,11 +272,15 @@ impl Repository {
         git2::Repository::init_opts(path, &options)
     }
 
-    fn initial_commit(
+    fn initial_commit<S>(
         repo: &git2::Repository,
         default_branch: &OneLevel,
         signature: &git2::Signature<'static>,
-    ) -> Result<(), git2::Error> {
+        signer: Option<&S>,
+    ) -> Result<(), super::Error>
+    where
+        S: librad::Signer,
+    {
         // Now let's create an empty tree for this commit
         let tree_id = {
             let mut index = repo.index()?;
@@ -279,17 +290,33 @@ impl Repository {
         };
         {
             let tree = repo.find_tree(tree_id)?;
+            let reference = format!("refs/heads/{}", default_branch.as_str());
             // Normally creating a commit would involve looking up the current HEAD
             // commit and making that be the parent of the initial commit, but here this
             // is the first commit so there will be no parent.
-            repo.commit(
-                Some(&format!("refs/heads/{}", default_branch.as_str())),
-                signature,
-                signature,
-                "Initial commit",
-                &tree,
-                &[],
-            )?;
+            match signer {
+                None => {
+                    repo.commit(
+                        Some(&reference),
+                        signature,
+                        signature,
+                        "Initial commit",
+                        &tree,
+                        &[],
+                    )?;
+                },
+                Some(signer) => {
+                    let commit_id = crate::signed_commit::commit(
+                        repo,
+                        signer,
+                        signature,
+                        "Initial commit",
+                        &tree,
+                        &[],
+                    )?;
+                    repo.reference(&reference, commit_id, false, "Initial commit")?;
+                },
+            }
         }
         Ok(())
     }
@@ -354,7 +381,7 @@ impl Repository {
     ) -> Result<Option<Remote<LocalUrl>>, Error> {
         match Remote::<LocalUrl>::find(repo, reflike!("rad")) {
             Err(remote::FindError::ParseUrl(_)) => {
-                tracing::warn!("renaming invalid rad URL");
+                tracing::warn!(repo = %repo.path().display(), %url, "renaming invalid rad URL");
                 repo.remote_rename("rad", "rad_old")?;
                 Ok(None)
             },
@@ -367,3 +394,104 @@ impl Repository {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use librad::git::Urn;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn existing_repo_reuses_matching_rad_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(
+            Some("refs/heads/master"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+
+        let url = LocalUrl::from(Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero())));
+        let mut rad = Remote::rad_remote(
+            url.clone(),
+            Refspec {
+                src: refspec_pattern!("refs/heads/*"),
+                dst: refspec_pattern!("refs/remotes/rad/*"),
+                force: Force::True,
+            },
+        );
+        rad.save(&repo).unwrap();
+
+        let validate = |url: LocalUrl| {
+            Repository::validate(
+                super::super::Repo::Existing {
+                    path: dir.path().to_path_buf(),
+                },
+                url,
+                OneLevel::from(reflike!("master")),
+                super::super::Signature {
+                    name: "alice".to_string(),
+                    email: "alice@example.com".to_string(),
+                },
+            )
+        };
+
+        // A `rad` remote left behind by a previous attempt is reused.
+        for _ in 0..2 {
+            match validate(url.clone()).unwrap() {
+                Repository::Existing { url: found, .. } => assert_eq!(found, url),
+                Repository::New { .. } => panic!("expected an existing repository"),
+            }
+        }
+
+        let other = LocalUrl::from(Urn::new(radicle_git_ext::Oid::from(
+            git2::Oid::from_bytes(&[1; 20]).unwrap(),
+        )));
+        assert!(matches!(validate(other), Err(Error::UrlMismatch { .. })));
+    }
+
+    #[test]
+    fn initial_commit_carries_signature_when_signing() {
+        let dir = tempfile::tempdir().unwrap();
+        let default_branch = OneLevel::from(reflike!("main"));
+        let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+        let key = link_crypto::SecretKey::new();
+
+        let signed =
+            Repository::initialise(dir.path().join("signed"), "", &default_branch).unwrap();
+        Repository::initial_commit(&signed, &default_branch, &signature, Some(&key)).unwrap();
+        let head = signed.refname_to_id("refs/heads/main").unwrap();
+        let peer_id =
+            librad::PeerId::from(librad::PublicKey::from(librad::Signer::public_key(&key)));
+        crate::signed_commit::verify(&signed, head, &peer_id).unwrap();
+        let other = librad::PeerId::from(link_crypto::SecretKey::new());
+        assert!(matches!(
+            crate::signed_commit::verify(&signed, head, &other),
+            Err(crate::signed_commit::Error::Unverified)
+        ));
+
+        let unsigned =
+            Repository::initialise(dir.path().join("unsigned"), "", &default_branch).unwrap();
+        Repository::initial_commit(
+            &unsigned,
+            &default_branch,
+            &signature,
+            None::<&link_crypto::SecretKey>,
+        )
+        .unwrap();
+        let head = unsigned.refname_to_id("refs/heads/main").unwrap();
+        assert!(matches!(
+            crate::signed_commit::verify(&unsigned, head, &peer_id),
+            Err(crate::signed_commit::Error::Git(_))
+        ));
+    }
+}
@@ -69,10 +69,19 @@ pub struct Request<S, T> {
     /// The timestamp of the latest action to be taken on this request.
     #[serde(with = "serde_millis", bound = "T: serde_millis::Milliseconds")]
     timestamp: T,
+    /// The timestamp at which the request was created. Requests that were persisted before
+    /// this was recorded don't have one.
+    #[serde(default, bound = "T: serde_millis::Milliseconds")]
+    started_at: Option<Millis<T>>,
     /// The state of the request, as mentioned above.
     state: S,
 }
 
+/// A timestamp that is (de)serialized as milliseconds, see [`serde_millis`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent, bound = "T: serde_millis::Milliseconds")]
+pub struct Millis<T>(#[serde(with = "serde_millis")] pub T);
+
 impl<S, T> Deref for Request<S, T> {
     type Target = S;
 
@@ -102,6 +111,32 @@ impl<S, T> Request<S, T> {
         &self.timestamp
     }
 
+    /// Get the timestamp at which the `Request` was created, if it is known.
+    pub fn started_at(&self) -> Option<&T> {
+        self.started_at.as_ref().map(|Millis(timestamp)| timestamp)
+    }
+
+    /// Build the gossip [`Payload`] for this `Request`, directed at one of
+    /// its `Available` peers. If there are several, the smallest `PeerId` is
+    /// picked so the choice is stable.
+    ///
+    /// Use the [`From`] implementation for a `Payload` without an `origin`.
+    pub fn to_gossip(&self) -> Payload
+    where
+        S: HasPeers,
+    {
+        Payload {
+            urn: self.urn.clone(),
+            rev: None,
+            origin: self
+                .state
+                .iter()
+                .filter(|(_, status)| **status == Status::Available)
+                .map(|(peer_id, _)| *peer_id)
+                .min(),
+        }
+    }
+
     /// Transition this `Request` into an `Cancelled` state. We can only
     /// transition a particular subset of the states which are: `{Created,
     /// Requested, Found, Cloning, Cancelled}`.
@@ -114,6 +149,7 @@ impl<S, T> Request<S, T> {
     {
         Request {
             urn: self.urn,
+            started_at: self.started_at,
             attempts: self.attempts,
             timestamp,
             state: self.state.cancel(),
@@ -150,6 +186,7 @@ impl<S, T> Request<S, T> {
         if self.attempts.queries > max_queries {
             Either::Right(Request {
                 urn: self.urn,
+                started_at: self.started_at,
                 attempts: self.attempts,
                 timestamp,
                 state: self.state.time_out(TimedOut::Query),
@@ -157,6 +194,7 @@ impl<S, T> Request<S, T> {
         } else if self.attempts.clones > max_clones {
             Either::Right(Request {
                 urn: self.urn,
+                started_at: self.started_at,
                 attempts: self.attempts,
                 timestamp,
                 state: self.state.time_out(TimedOut::Clone),
@@ -191,11 +229,15 @@ impl<T> Request<Created, T> {
     /// Once this request has been made, we can transition this `Request` to the
     /// `Requested` state by calling [`Request::request`].
     #[allow(clippy::needless_pass_by_value)]
-    pub fn new(urn: Urn, timestamp: T) -> Self {
+    pub fn new(urn: Urn, timestamp: T) -> Self
+    where
+        T: Clone,
+    {
         let urn = Urn { path: None, ..urn };
         Self {
             urn,
             attempts: Attempts::new(),
+            started_at: Some(Millis(timestamp.clone())),
             timestamp,
             state: Created {},
         }
@@ -211,6 +253,7 @@ impl<T> Request<Created, T> {
     pub fn request(self, timestamp: T) -> Request<Requested, T> {
         Request {
             urn: self.urn,
+            started_at: self.started_at,
             attempts: Attempts {
                 queries: self.attempts.queries + 1,
                 ..self.attempts
@@ -234,6 +277,7 @@ impl<T> Request<Requested, T> {
         peers.entry(peer).or_insert(Status::Available);
         Request {
             urn: self.urn,
+            started_at: self.started_at,
             attempts: self.attempts,
             timestamp,
             state: Found { peers },
@@ -263,6 +307,7 @@ impl<T> Request<Found, T> {
             .or_insert(Status::InProgress);
         let this = Request {
             urn: self.urn,
+            started_at: self.started_at,
             attempts: Attempts {
                 queries: self.attempts.queries,
                 clones: self.attempts.clones + 1,
@@ -285,6 +330,7 @@ impl<T> Request<Found, T> {
         if self.state.all_failed() {
             Either::Left(Request {
                 urn: self.urn,
+                started_at: self.started_at,
                 attempts: self.attempts,
                 timestamp: self.timestamp,
                 state: Requested {
@@ -319,6 +365,7 @@ impl<T> Request<Cloning, T> {
             .or_insert(Status::Failed { reason });
         Request {
             urn: self.urn,
+            started_at: self.started_at,
             attempts: self.attempts,
             timestamp,
             state: Found { peers },
@@ -333,9 +380,62 @@ impl<T> Request<Cloning, T> {
     pub fn cloned(self, remote_peer: PeerId, timestamp: T) -> Request<Cloned, T> {
         Request {
             urn: self.urn.clone(),
+            started_at: self.started_at,
             attempts: self.attempts,
             timestamp,
             state: Cloned { remote_peer },
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::SystemTime;
+
+    #[test]
+    fn failed_clone_records_reason() {
+        let urn = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        let peer = PeerId::from(link_crypto::SecretKey::new());
+        let request = Request::new(urn, SystemTime::now())
+            .request(SystemTime::now())
+            .into_found(peer, SystemTime::now())
+            .cloning(Queries::Infinite, Clones::Infinite, peer, SystemTime::now())
+            .unwrap_right()
+            .failed(peer, "connection timed out".to_string(), SystemTime::now())
+            .unwrap_left();
+
+        let expected = Status::Failed {
+            reason: "connection timed out".to_string(),
+        };
+        assert_eq!(request.peers.get(&peer), Some(&expected));
+
+        let json = serde_json::to_value(&request).unwrap();
+        let request = serde_json::from_value::<Request<Requested, SystemTime>>(json).unwrap();
+        assert_eq!(request.peers.get(&peer), Some(&expected));
+    }
+
+    #[test]
+    fn found_request_gossips_with_origin() {
+        let urn = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        let peer1 = PeerId::from(link_crypto::SecretKey::new());
+        let peer2 = PeerId::from(link_crypto::SecretKey::new());
+        let requested = Request::new(urn.clone(), ()).request(());
+        assert_eq!(
+            SomeRequest::from(requested.clone()).to_gossip(),
+            Payload {
+                urn: urn.clone(),
+                rev: None,
+                origin: None,
+            }
+        );
+
+        let found = requested.into_found(peer1, ()).found(peer2, ());
+        let gossip = found.to_gossip();
+        assert_eq!(gossip.urn, urn);
+        assert_eq!(gossip.origin, Some(peer1.min(peer2)));
+        assert_eq!(SomeRequest::from(found.clone()).to_gossip(), gossip);
+        assert_eq!(Payload::from(found).origin, None);
+    }
+}
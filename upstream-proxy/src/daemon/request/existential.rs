@@ -5,7 +5,7 @@
 
 //! The enumeration of different [`super::Request`] states unified under a
 //! single enum called [`SomeRequest`].
-use librad::PeerId;
+use librad::{git::Urn, net::protocol::gossip::Payload, PeerId};
 use serde::{Deserialize, Serialize};
 
 use super::{
@@ -131,6 +131,19 @@ impl<T> SomeRequest<T> {
         }
     }
 
+    /// Get the [`Urn`] that the underlying `Request` is searching for.
+    pub const fn urn(&self) -> &Urn {
+        match self {
+            SomeRequest::Created(request) => request.urn(),
+            SomeRequest::Requested(request) => request.urn(),
+            SomeRequest::Found(request) => request.urn(),
+            SomeRequest::Cloning(request) => request.urn(),
+            SomeRequest::Cloned(request) => request.urn(),
+            SomeRequest::Cancelled(request) => request.urn(),
+            SomeRequest::TimedOut(request) => request.urn(),
+        }
+    }
+
     /// Get the current timestamp of the underlying `Request`.
     pub const fn timestamp(&self) -> &T {
         match self {
@@ -144,6 +157,19 @@ impl<T> SomeRequest<T> {
         }
     }
 
+    /// Get the timestamp at which the underlying `Request` was created, if it is known.
+    pub fn started_at(&self) -> Option<&T> {
+        match self {
+            SomeRequest::Created(request) => request.started_at(),
+            SomeRequest::Requested(request) => request.started_at(),
+            SomeRequest::Found(request) => request.started_at(),
+            SomeRequest::Cloning(request) => request.started_at(),
+            SomeRequest::Cloned(request) => request.started_at(),
+            SomeRequest::Cancelled(request) => request.started_at(),
+            SomeRequest::TimedOut(request) => request.started_at(),
+        }
+    }
+
     /// We can cancel an underlying `Request` if it is allowed to be cancelled.
     /// In the case that it is allowed, then we get back the cancelled
     /// request in the `Right` variant. Otherwise we get back our original
@@ -178,6 +204,25 @@ impl<T> SomeRequest<T> {
         }
     }
 
+    /// Build the gossip [`Payload`] for the underlying `Request`. Only `Found`
+    /// and `Cloning` requests know of peers, so the `origin` is left empty for
+    /// the other states, see [`Request::to_gossip`].
+    pub fn to_gossip(&self) -> Payload {
+        match self {
+            SomeRequest::Found(request) => request.to_gossip(),
+            SomeRequest::Cloning(request) => request.to_gossip(),
+            SomeRequest::Created(_)
+            | SomeRequest::Requested(_)
+            | SomeRequest::Cloned(_)
+            | SomeRequest::Cancelled(_)
+            | SomeRequest::TimedOut(_) => Payload {
+                urn: self.urn().clone(),
+                rev: None,
+                origin: None,
+            },
+        }
+    }
+
     /// Get any peers associated with this request
     pub fn peers(&self) -> Option<&std::collections::HashMap<PeerId, Status>> {
         match self {
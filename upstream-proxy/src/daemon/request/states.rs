@@ -53,6 +53,15 @@ pub enum RequestState {
     TimedOut,
 }
 
+impl RequestState {
+    /// Whether a `Request` in this state will not transition any further, i.e.
+    /// it is `Cloned`, `Cancelled` or `TimedOut`.
+    #[must_use]
+    pub const fn is_terminal(&self) -> bool {
+        matches!(self, Self::Cloned | Self::Cancelled | Self::TimedOut)
+    }
+}
+
 impl fmt::Display for RequestState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self)
@@ -74,6 +74,53 @@ impl<T> From<Request<TimedOut, T>> for Error {
 /// the requested urn.
 pub type Created<T> = Either<SomeRequest<T>, SomeRequest<T>>;
 
+/// The number of requests in each [`RequestState`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Counts {
+    /// Number of requests in the `Created` state.
+    pub created: usize,
+    /// Number of requests in the `Requested` state.
+    pub requested: usize,
+    /// Number of requests in the `Found` state.
+    pub found: usize,
+    /// Number of requests in the `Cloning` state.
+    pub cloning: usize,
+    /// Number of requests in the `Cloned` state.
+    pub cloned: usize,
+    /// Number of requests in the `Cancelled` state.
+    pub cancelled: usize,
+    /// Number of requests in the `TimedOut` state.
+    pub timed_out: usize,
+}
+
+impl Counts {
+    /// Increment the count for `state`.
+    fn increment(&mut self, state: &RequestState) {
+        let count = match state {
+            RequestState::Created => &mut self.created,
+            RequestState::Requested => &mut self.requested,
+            RequestState::Found => &mut self.found,
+            RequestState::Cloning => &mut self.cloning,
+            RequestState::Cloned => &mut self.cloned,
+            RequestState::Cancelled => &mut self.cancelled,
+            RequestState::TimedOut => &mut self.timed_out,
+        };
+        *count += 1;
+    }
+}
+
+/// A snapshot of the [`WaitingRoom`] metrics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metrics {
+    /// The number of requests that are currently in each state.
+    pub current: Counts,
+    /// The number of times any request entered each state over the lifetime of the waiting
+    /// room.
+    pub lifetime: Counts,
+}
+
 /// A `WaitingRoom` knows about a set of `Request`s that have been made, and can
 /// look them up via their `Urn`.
 ///
@@ -88,6 +135,10 @@ pub struct WaitingRoom<T, D> {
 
     /// The configuration of the waiting room.
     config: Config<D>,
+
+    /// Counts of the states requests entered over the lifetime of the waiting room.
+    #[serde(default)]
+    lifetime: Counts,
 }
 
 /// The `Config` for the waiting room tells it what are the maximum number of
@@ -132,6 +183,21 @@ impl<T, D> WaitingRoom<T, D> {
         Self {
             requests: HashMap::new(),
             config,
+            lifetime: Counts::default(),
+        }
+    }
+
+    /// Get the current [`Metrics`] of the `WaitingRoom`.
+    #[must_use]
+    pub fn metrics(&self) -> Metrics {
+        let mut current = Counts::default();
+        for request in self.requests.values() {
+            current.increment(&RequestState::from(request));
+        }
+
+        Metrics {
+            current,
+            lifetime: self.lifetime,
         }
     }
 
@@ -164,12 +230,27 @@ impl<T, D> WaitingRoom<T, D> {
             None => {
                 let request = SomeRequest::Created(Request::new(urn.clone(), timestamp));
                 self.requests.insert(urn.id, request.clone());
+                self.lifetime.increment(&RequestState::Created);
                 Either::Left(request)
             },
             Some(request) => Either::Right(request.clone()),
         }
     }
 
+    /// Create a fresh `Request` for each of the `urns` that is not in the
+    /// `WaitingRoom` yet, see [`WaitingRoom::request`]. Requests that are
+    /// already present are left untouched.
+    ///
+    /// Returns the number of requests that were created.
+    pub fn enqueue_many(&mut self, urns: impl IntoIterator<Item = Urn>, timestamp: T) -> usize
+    where
+        T: Clone,
+    {
+        urns.into_iter()
+            .filter(|urn| self.request(urn, timestamp.clone()).is_left())
+            .count()
+    }
+
     /// Transition the `Request` found at the provided `urn` and call the
     /// transition function to move it into its `Next` state.
     ///
@@ -191,20 +272,36 @@ impl<T, D> WaitingRoom<T, D> {
     {
         match self.get(urn) {
             None => Err(Error::MissingUrn(urn.clone())),
-            Some(request) => match request.clone().transition(matcher, transition) {
-                Either::Right(Either::Right(next)) => {
-                    self.requests.insert(urn.id, next.into());
-                    Ok(())
-                },
-                Either::Right(Either::Left(timeout)) => {
-                    self.requests.insert(urn.id, timeout.clone().into());
-                    Err(timeout.into())
-                },
-                Either::Left(mismatch) => Err(Error::StateMismatch((&mismatch).into())),
+            Some(request) => {
+                let previous_state = RequestState::from(request);
+                match request.clone().transition(matcher, transition) {
+                    Either::Right(Either::Right(next)) => {
+                        let next: SomeRequest<T> = next.into();
+                        self.record_transition(&previous_state, &next);
+                        self.requests.insert(urn.id, next);
+                        Ok(())
+                    },
+                    Either::Right(Either::Left(timeout)) => {
+                        let next: SomeRequest<T> = timeout.clone().into();
+                        self.record_transition(&previous_state, &next);
+                        self.requests.insert(urn.id, next);
+                        Err(timeout.into())
+                    },
+                    Either::Left(mismatch) => Err(Error::StateMismatch((&mismatch).into())),
+                }
             },
         }
     }
 
+    /// Count `next` in the lifetime metrics if it is in a different state than
+    /// `previous_state`.
+    fn record_transition(&mut self, previous_state: &RequestState, next: &SomeRequest<T>) {
+        let next_state = RequestState::from(next);
+        if next_state != *previous_state {
+            self.lifetime.increment(&next_state);
+        }
+    }
+
     /// Tell the `WaitingRoom` that a query was made for the given `urn`.
     ///
     /// If the underlying `Request` was in the `Created` state then it will
@@ -427,19 +524,76 @@ impl<T, D> WaitingRoom<T, D> {
         created.or(requested).map(|(urn, _request)| urn)
     }
 
-    /// Get the next `Request` that is in the the `Found` state and the status
-    /// of the peer is `Available`.
-    pub fn next_clone(&self) -> Option<(Urn, PeerId)> {
-        self.find_by_state(RequestState::Found)
-            .and_then(|(urn, request)| match request {
-                SomeRequest::Found(request) => {
-                    request.iter().find_map(|(peer_id, status)| match status {
-                        Status::Available => Some((urn.clone(), *peer_id)),
-                        _ => None,
-                    })
-                },
-                _ => None,
+    /// Pick the peer to clone the given `urn` from next.
+    ///
+    /// Only peers whose status is `Available` are considered, i.e. peers that
+    /// already failed or that a clone is in progress for are skipped. If there
+    /// are several, the smallest `PeerId` is picked so the choice is stable.
+    ///
+    /// Returns `None` if the `urn` is not in the `Found` state or all of its
+    /// peers have been tried.
+    #[must_use]
+    pub fn next_clone_target(&self, urn: &Urn) -> Option<PeerId> {
+        match self.get(urn)? {
+            SomeRequest::Found(request) => request
+                .iter()
+                .filter(|(_, status)| **status == Status::Available)
+                .map(|(peer_id, _)| *peer_id)
+                .min(),
+            _ => None,
+        }
+    }
+
+    /// Advance the `WaitingRoom` by starting a clone for every request in the
+    /// `Found` state that has a peer left to clone from, see
+    /// [`WaitingRoom::next_clone_target`].
+    ///
+    /// Once a clone is reported with [`WaitingRoom::cloning_failed`] the
+    /// request moves back to `Found` and the next call to `process` attempts
+    /// the next available peer.
+    ///
+    /// Returns the `Urn`/`PeerId` pairs that a clone should be started for.
+    /// Requests that exceed the maximum number of clones transition to the
+    /// `TimedOut` state instead and are not returned.
+    pub fn process(&mut self, timestamp: T) -> Vec<(Urn, PeerId)>
+    where
+        T: Clone,
+    {
+        let targets = self
+            .filter_by_state(RequestState::Found)
+            .filter_map(|(urn, _)| self.next_clone_target(&urn).map(|peer_id| (urn, peer_id)))
+            .collect::<Vec<_>>();
+
+        targets
+            .into_iter()
+            .filter(|(urn, peer_id)| self.cloning(urn, *peer_id, timestamp.clone()).is_ok())
+            .collect()
+    }
+
+    /// Remove the requests that are in a terminal state, see
+    /// [`RequestState::is_terminal`], and whose timestamp is older than
+    /// `older_than` at the time `now`. Requests that are still in flight are
+    /// never removed.
+    ///
+    /// Returns the `Urn`/`SomeRequest` pairs that were removed.
+    pub fn evict_terminal(&mut self, older_than: D, now: T) -> Vec<(Urn, SomeRequest<T>)>
+    where
+        T: Add<D, Output = T> + PartialOrd + Clone,
+        D: Clone,
+    {
+        let expired = self
+            .iter()
+            .filter(|(_, request)| {
+                RequestState::from(*request).is_terminal()
+                    && request.timestamp().clone() + older_than.clone() <= now
             })
+            .map(|(urn, _)| urn)
+            .collect::<Vec<_>>();
+
+        expired
+            .into_iter()
+            .filter_map(|urn| self.remove(&urn).map(|request| (urn, request)))
+            .collect()
     }
 }
 
@@ -452,3 +606,176 @@ where
         self.requests.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn metrics_track_transitions() {
+        let mut waiting_room: WaitingRoom<(), ()> = WaitingRoom::new(Config::default());
+        let urn = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        let peer = PeerId::from(link_crypto::SecretKey::new());
+
+        let _request = waiting_room.request(&urn, ());
+        waiting_room.queried(&urn, ()).unwrap();
+        waiting_room.queried(&urn, ()).unwrap();
+        waiting_room.found(&urn, peer, ()).unwrap();
+        waiting_room.cloning(&urn, peer, ()).unwrap();
+        waiting_room.cloned(&urn, peer, ()).unwrap();
+
+        assert_eq!(
+            waiting_room.metrics(),
+            Metrics {
+                current: Counts {
+                    cloned: 1,
+                    ..Counts::default()
+                },
+                lifetime: Counts {
+                    created: 1,
+                    requested: 1,
+                    found: 1,
+                    cloning: 1,
+                    cloned: 1,
+                    ..Counts::default()
+                },
+            }
+        );
+
+        waiting_room.remove(&urn);
+        assert_eq!(waiting_room.metrics().current, Counts::default());
+        assert_eq!(waiting_room.metrics().lifetime.cloned, 1);
+    }
+
+    #[test]
+    fn enqueue_many_is_idempotent() {
+        let mut waiting_room: WaitingRoom<(), ()> = WaitingRoom::new(Config::default());
+        let urns = (1..=4)
+            .map(|i| {
+                Urn::new(radicle_git_ext::Oid::from(
+                    git2::Oid::from_bytes(&[i; 20]).unwrap(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(waiting_room.enqueue_many(urns[..3].to_vec(), ()), 3);
+        assert_eq!(waiting_room.metrics().current.created, 3);
+
+        waiting_room.queried(&urns[0], ()).unwrap();
+        assert_eq!(waiting_room.enqueue_many(urns.clone(), ()), 1);
+        assert_eq!(waiting_room.enqueue_many(urns.clone(), ()), 0);
+        assert_eq!(
+            waiting_room.metrics().current,
+            Counts {
+                created: 3,
+                requested: 1,
+                ..Counts::default()
+            }
+        );
+        assert_eq!(waiting_room.metrics().lifetime.created, 4);
+    }
+
+    #[test]
+    fn evict_terminal_keeps_requests_in_flight() {
+        let mut waiting_room: WaitingRoom<u32, u32> = WaitingRoom::new(Config::default());
+        let urns = (1..=3)
+            .map(|i| {
+                Urn::new(radicle_git_ext::Oid::from(
+                    git2::Oid::from_bytes(&[i; 20]).unwrap(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let peer = PeerId::from(link_crypto::SecretKey::new());
+
+        // Cloned at 2.
+        let _request = waiting_room.request(&urns[0], 0);
+        waiting_room.queried(&urns[0], 0).unwrap();
+        waiting_room.found(&urns[0], peer, 1).unwrap();
+        waiting_room.cloning(&urns[0], peer, 1).unwrap();
+        waiting_room.cloned(&urns[0], peer, 2).unwrap();
+        // Cancelled at 8.
+        let _request = waiting_room.request(&urns[1], 0);
+        waiting_room.canceled(&urns[1], 8).unwrap();
+        // Still cloning since 1.
+        let _request = waiting_room.request(&urns[2], 0);
+        waiting_room.queried(&urns[2], 0).unwrap();
+        waiting_room.found(&urns[2], peer, 1).unwrap();
+        waiting_room.cloning(&urns[2], peer, 1).unwrap();
+
+        let evicted = waiting_room.evict_terminal(5, 10);
+        assert_eq!(
+            evicted
+                .iter()
+                .map(|(urn, request)| (urn.clone(), RequestState::from(request)))
+                .collect::<Vec<_>>(),
+            vec![(urns[0].clone(), RequestState::Cloned)]
+        );
+        assert!(waiting_room.get(&urns[0]).is_none());
+        assert_eq!(
+            RequestState::from(waiting_room.get(&urns[1]).unwrap()),
+            RequestState::Cancelled
+        );
+        assert_eq!(
+            RequestState::from(waiting_room.get(&urns[2]).unwrap()),
+            RequestState::Cloning
+        );
+
+        let evicted = waiting_room.evict_terminal(5, 100);
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].0, urns[1]);
+        assert_eq!(
+            RequestState::from(waiting_room.get(&urns[2]).unwrap()),
+            RequestState::Cloning
+        );
+        assert_eq!(waiting_room.metrics().lifetime.cloned, 1);
+    }
+
+    #[test]
+    fn process_retries_with_next_peer() {
+        let mut waiting_room: WaitingRoom<(), ()> = WaitingRoom::new(Config::default());
+        let urn = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        let peer1 = PeerId::from(link_crypto::SecretKey::new());
+        let peer2 = PeerId::from(link_crypto::SecretKey::new());
+        let failure = || -> Box<dyn std::error::Error> {
+            Box::new(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "connection timed out",
+            ))
+        };
+
+        let _request = waiting_room.request(&urn, ());
+        waiting_room.queried(&urn, ()).unwrap();
+        assert_eq!(waiting_room.process(()), vec![]);
+
+        waiting_room.found(&urn, peer1, ()).unwrap();
+        waiting_room.found(&urn, peer2, ()).unwrap();
+        let first = waiting_room.next_clone_target(&urn).unwrap();
+        assert_eq!(waiting_room.process(()), vec![(urn.clone(), first)]);
+        assert_eq!(
+            RequestState::from(waiting_room.get(&urn).unwrap()),
+            RequestState::Cloning
+        );
+        // Nothing to do while the clone is in progress.
+        assert_eq!(waiting_room.next_clone_target(&urn), None);
+        assert_eq!(waiting_room.process(()), vec![]);
+
+        waiting_room
+            .cloning_failed(&urn, first, (), failure())
+            .unwrap();
+        let second = if first == peer1 { peer2 } else { peer1 };
+        assert_eq!(waiting_room.next_clone_target(&urn), Some(second));
+        assert_eq!(waiting_room.process(()), vec![(urn.clone(), second)]);
+
+        waiting_room
+            .cloning_failed(&urn, second, (), failure())
+            .unwrap();
+        assert_eq!(
+            RequestState::from(waiting_room.get(&urn).unwrap()),
+            RequestState::Requested
+        );
+        assert_eq!(waiting_room.next_clone_target(&urn), None);
+        assert_eq!(waiting_room.process(()), vec![]);
+        assert_eq!(waiting_room.metrics().lifetime.cloning, 2);
+    }
+}
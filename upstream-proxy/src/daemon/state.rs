@@ -7,8 +7,10 @@
 
 use std::{
     convert::{TryFrom as _, TryInto},
+    future::Future,
     net::SocketAddr,
     path::PathBuf,
+    time::Duration,
 };
 
 use either::Either;
@@ -157,6 +159,52 @@ where
         .await?)
 }
 
+/// Like [`clone_project`], but if a `timeout` is given fails with [`Error::Timeout`] when the
+/// clone doesn't complete within it, e.g. because `remote_peer` is unreachable. A clone that timed
+/// out is abandoned before this returns, so it doesn't race with a retry.
+///
+/// # Errors
+///   * See [`clone_project`].
+///   * The clone didn't complete within `timeout`.
+pub async fn clone_project_with_timeout<S, Addrs>(
+    peer: &Peer<S>,
+    urn: Urn,
+    remote_peer: PeerId,
+    addr_hints: Addrs,
+    timeout: Option<Duration>,
+) -> Result<replication::Success, Error>
+where
+    S: Clone + Signer,
+    Addrs: IntoIterator<Item = SocketAddr> + Send + 'static,
+{
+    let peer = peer.clone();
+    let clone = async move { clone_project(&peer, urn, remote_peer, addr_hints).await };
+    match timeout {
+        Some(timeout) => with_timeout(timeout, clone).await,
+        None => clone.await,
+    }
+}
+
+/// Runs `fut` as a task and fails with [`Error::Timeout`] if it doesn't resolve within `timeout`.
+///
+/// A task that timed out is aborted and awaited, so it is dropped at its next await point before
+/// this returns.
+async fn with_timeout<T, F>(timeout: Duration, fut: F) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut task = tokio::spawn(fut);
+    match tokio::time::timeout(timeout, &mut task).await {
+        Ok(res) => res?,
+        Err(_elapsed) => {
+            task.abort();
+            task.await.ok();
+            Err(Error::Timeout(timeout))
+        },
+    }
+}
+
 /// Get the project found at `urn`.
 ///
 /// # Errors
@@ -171,7 +219,65 @@ where
         .map_err(Error::from)
 }
 
-/// Returns the list of [`Project`]s for the local peer.
+/// Verifies the identity history of the project found at `urn`, i.e. that every revision is
+/// signed by a quorum of the delegates of its parent.
+///
+/// # Errors
+///
+///   * The project can't be found.
+///   * The history of the project is not valid, see [`Error::ProjectVerification`].
+///   * The storage operations fail.
+pub async fn verify_project<S>(peer: &Peer<S>, urn: Urn) -> Result<(), Error>
+where
+    S: Clone + Signer,
+{
+    let verified = peer
+        .using_storage({
+            let urn = urn.clone();
+            move |store| project::verify(store, &urn)
+        })
+        .await?
+        .map_err(|err| match err {
+            identities::Error::VerifyProject(_) => Error::ProjectVerification {
+                urn: urn.clone(),
+                source: Box::new(err),
+            },
+            err => Error::from(err),
+        })?;
+
+    verified.map(|_| ()).ok_or(Error::ProjectNotFound(urn))
+}
+
+/// Returns the identity for `urn`, which may be a person or a project.
+///
+/// # Errors
+///
+///   * Resolving the identity from the store fails.
+pub async fn get_identity<S>(peer: &Peer<S>, urn: Urn) -> Result<Option<SomeIdentity>, Error>
+where
+    S: Clone + Signer,
+{
+    peer.using_storage(move |store| identities::any::get(store, &urn))
+        .await?
+        .map_err(Error::from)
+}
+
+/// Checks whether an identity for `urn` is present in the monorepo, without reading its
+/// metadata like [`get_project`] does.
+///
+/// # Errors
+///
+///   * The storage access fails.
+pub async fn project_exists<S>(peer: &Peer<S>, urn: Urn) -> Result<bool, Error>
+where
+    S: Clone + Signer,
+{
+    peer.using_storage(move |store| store.has_urn(&urn))
+        .await?
+        .map_err(Error::from)
+}
+
+/// Returns the list of [`Project`]s for the local peer, ordered by name and then by URN.
 ///
 /// # Errors
 ///
@@ -180,12 +286,34 @@ pub async fn list_projects<S>(peer: &Peer<S>) -> Result<Vec<Project>, Error>
 where
     S: Clone + Signer,
 {
-    // FIXME(xla): Instead of implicitely expecting a presence of a default owner,
-    // there either should be an explicit argument, or it's made impossible to
-    // call this function without an owner associated with the state.
-    let owner = match default_owner(peer).await? {
-        None => return Err(Error::MissingOwner),
-        Some(owner) => owner.into_inner().into_inner(),
+    list_projects_for_owner(peer, None).await
+}
+
+/// Returns the list of [`Project`]s whose `rad/self` points to `owner`. Falls back to the
+/// default owner of the local peer if `owner` is `None`.
+///
+/// The projects are ordered by name and then by URN, so the order is stable across calls.
+///
+/// # Errors
+///
+///   * No `owner` was given and the local peer has no default owner.
+///   * Retrieving the project entities from the store fails.
+pub async fn list_projects_for_owner<S>(
+    peer: &Peer<S>,
+    owner: Option<Urn>,
+) -> Result<Vec<Project>, Error>
+where
+    S: Clone + Signer,
+{
+    let owner = match owner {
+        Some(owner) => owner,
+        // FIXME(xla): Instead of implicitely expecting a presence of a default owner,
+        // there either should be an explicit argument, or it's made impossible to
+        // call this function without an owner associated with the state.
+        None => match default_owner(peer).await? {
+            None => return Err(Error::MissingOwner),
+            Some(owner) => owner.urn(),
+        },
     };
 
     peer.using_storage(move |store| {
@@ -196,9 +324,8 @@ where
                     let rad_self = Reference::rad_self(Namespace::from(project.urn()), None);
                     let urn = Urn::try_from(rad_self).ok()?;
                     let project_self = person::get(store, &urn).ok()??;
-                    // Filter projects that have a rad/self pointing to current default
-                    // owner
-                    if project_self == owner {
+                    // Filter projects that have a rad/self pointing to the owner
+                    if project_self.urn() == owner {
                         Some(project)
                     } else {
                         None
@@ -211,6 +338,42 @@ where
         Ok::<_, Error>(projects)
     })
     .await?
+    .map(|mut projects| {
+        projects.sort_by_cached_key(|project| {
+            (
+                project.subject().name.to_string(),
+                project.urn().to_string(),
+            )
+        });
+        projects
+    })
+}
+
+/// Returns at most `limit` of the [`Project`]s listed by [`list_projects_for_owner`], skipping
+/// the first `offset`, together with the total number of projects.
+///
+/// # Errors
+///
+///   * No `owner` was given and the local peer has no default owner.
+///   * Retrieving the project entities from the store fails.
+pub async fn list_projects_paged<S>(
+    peer: &Peer<S>,
+    owner: Option<Urn>,
+    offset: usize,
+    limit: usize,
+) -> Result<(Vec<Project>, usize), Error>
+where
+    S: Clone + Signer,
+{
+    let projects = list_projects_for_owner(peer, owner).await?;
+    Ok(page(projects, offset, limit))
+}
+
+/// Splits the page starting at `offset` with at most `limit` entries off `items` and returns it
+/// together with the total number of `items`.
+fn page<T>(items: Vec<T>, offset: usize, limit: usize) -> (Vec<T>, usize) {
+    let total = items.len();
+    (items.into_iter().skip(offset).take(limit).collect(), total)
 }
 
 /// Retrieves the [`librad::git::refs::Refs`] for the state owner.
@@ -245,6 +408,7 @@ where
 {
     let default_branch = create.default_branch.to_string();
     let description = create.description.to_string();
+    let sign = create.sign;
     let name = create
         .repo
         .project_name()
@@ -295,8 +459,9 @@ where
             .cloned()
             .unwrap_or_default();
         move || {
+            let signer = sign.then(|| peer.signer().clone());
             repository
-                .setup_repo(settings(&peer), &desc)
+                .setup_repo(settings(&peer), &desc, signer.as_ref())
                 .map_err(crate::daemon::project::create::Error::from)
         }
     })
@@ -308,6 +473,97 @@ where
     Ok(project)
 }
 
+/// Forks the project identified by `source` so that `owner` becomes its sole delegate.
+///
+/// The fork is a new project with the same name, description and default branch. The default
+/// branch of `source`, as found by [`find_default_branch`], becomes the fork's default branch so
+/// the git history is preserved. Use [`checkout`] to create a working copy of the fork.
+///
+/// # Errors
+///
+///   * `owner` is already a delegate of `source`.
+///   * The source project or its default branch could not be found.
+///   * The fork was already created.
+///   * The storage operations fail.
+pub async fn fork_project<S>(
+    peer: &Peer<S>,
+    owner: &LocalIdentity,
+    source: Urn,
+) -> Result<Project, Error>
+where
+    S: Clone + Signer,
+{
+    let project = get_project(peer, source.clone())
+        .await?
+        .ok_or_else(|| Error::ProjectNotFound(source.clone()))?;
+    let owned = project
+        .delegations()
+        .iter()
+        .any(|delegation| match delegation {
+            Either::Left(pk) => PeerId::from(*pk) == peer.peer_id(),
+            Either::Right(indirect) => indirect.urn() == owner.urn(),
+        });
+    if owned {
+        return Err(Error::ForkOwnProject(source));
+    }
+
+    let default_branch =
+        project
+            .subject()
+            .default_branch
+            .clone()
+            .ok_or_else(|| Error::NoDefaultBranch {
+                name: project.subject().name.to_string(),
+                urn: source.clone(),
+            })?;
+    let source_branch = find_default_branch(peer, source).await?;
+    let branch_name = default_branch.parse::<RefLike>()?;
+
+    let owner = owner.clone();
+    let payload = payload::Project {
+        default_branch: Some(default_branch),
+        description: project.subject().description.clone(),
+        name: project.subject().name.clone(),
+    };
+    let delegations = Indirect::from(owner.clone().into_inner().into_inner());
+    let fork = peer
+        .using_storage(move |store| {
+            let urn = project::urn(store, payload.clone(), delegations.clone())?;
+            if store.has_urn(&urn)? {
+                return Err(Error::IdentityExists(urn));
+            }
+
+            let fork = project::create(store, owner, payload, delegations)?;
+            let head = store
+                .as_raw()
+                .find_reference(&source_branch.to_string())?
+                .peel_to_commit()?
+                .id();
+            let fork_branch = Reference::head(Namespace::from(fork.urn()), None, branch_name);
+            store.as_raw().reference(
+                &fork_branch.to_string(),
+                head,
+                false,
+                &format!("forked from {}", source_branch),
+            )?;
+            Refs::update(store, &fork.urn())?;
+
+            Ok(fork)
+        })
+        .await??;
+
+    tracing::debug!(
+        urn = ?fork.urn(),
+        name = ?fork.subject().name,
+        "forked project",
+    );
+
+    update_include(peer, fork.urn()).await?;
+    gossip::announce(peer, &fork.urn(), None);
+
+    Ok(fork)
+}
+
 /// Wrapper around the storage track.
 ///
 /// # Errors
@@ -317,31 +573,78 @@ pub async fn track<S>(peer: &Peer<S>, urn: Urn, remote_peer: PeerId) -> Result<(
 where
     S: Clone + Signer,
 {
-    {
-        match peer
-            .using_storage({
-                let urn = urn.clone();
-                move |store| {
-                    tracking::track(
-                        store,
-                        &urn,
-                        Some(remote_peer),
-                        tracking::Config::default(),
-                        tracking::policy::Track::MustNotExist,
-                    )
-                }
-            })
-            .await??
-        {
-            Ok(r) => {
-                tracing::trace!(reference = %r.name, peer = %remote_peer, "successfully tracked peer");
-            },
-            Err(err) => tracing::trace!(err = %err, "tracking policy violated"),
+    track_peer(peer, &urn, remote_peer).await?;
+    update_include(peer, urn).await?;
+    Ok(())
+}
+
+/// Track all `remote_peers` for the project identified by `urn`.
+///
+/// Unlike calling [`track`] for every peer, a failure to track one of the peers does not abort
+/// the operation. Instead the outcome for every peer is returned.
+///
+/// # Errors
+///
+/// * When updating the include file fails.
+pub async fn track_all<S, I>(
+    peer: &Peer<S>,
+    urn: Urn,
+    remote_peers: I,
+) -> Result<Vec<(PeerId, Result<(), Error>)>, Error>
+where
+    S: Clone + Signer,
+    I: IntoIterator<Item = PeerId> + Send,
+    I::IntoIter: Send,
+{
+    let mut outcomes = Vec::new();
+    for remote_peer in remote_peers {
+        let result = track_peer(peer, &urn, remote_peer).await;
+        if let Err(err) = &result {
+            tracing::warn!(%urn, peer = %remote_peer, ?err, "failed to track peer");
         }
+        outcomes.push((remote_peer, result));
     }
 
-    gossip::query(peer, &urn, Some(remote_peer));
     update_include(peer, urn).await?;
+    Ok(outcomes)
+}
+
+/// Track `remote_peer` in the storage and ask the network for the peer's view of `urn`.
+///
+/// The include file is not updated.
+async fn track_peer<S>(peer: &Peer<S>, urn: &Urn, remote_peer: PeerId) -> Result<(), Error>
+where
+    S: Clone + Signer,
+{
+    match peer
+        .using_storage({
+            let urn = urn.clone();
+            move |store| {
+                tracking::track(
+                    store,
+                    &urn,
+                    Some(remote_peer),
+                    tracking::Config::default(),
+                    tracking::policy::Track::MustNotExist,
+                )
+            }
+        })
+        .await??
+    {
+        Ok(r) => {
+            tracing::trace!(reference = %r.name, peer = %remote_peer, "successfully tracked peer");
+        },
+        Err(err) => tracing::trace!(err = %err, "tracking policy violated"),
+    }
+
+    gossip::query(
+        peer,
+        librad::net::protocol::gossip::Payload {
+            urn: urn.clone(),
+            rev: None,
+            origin: Some(remote_peer),
+        },
+    );
     Ok(())
 }
 
@@ -478,26 +781,108 @@ where
 ///
 /// The `peer_id` is from which peer we wish to base our checkout from.
 ///
+/// The `layout` determines the directory of the working copy below `destination`, see
+/// [`crate::daemon::project::checkout::PathLayout`].
+///
+/// If `post_checkout` is given, the program and arguments are run in the working copy once it
+/// was created.
+///
 /// # Errors
 ///
 /// * if the project can't be found
+/// * if the local peer has no default owner when checking out our own project
 /// * if the include file creation fails
 /// * if the clone of the working copy fails
+/// * if the post-checkout command fails
 pub async fn checkout<S, P>(
     peer: &Peer<S>,
     urn: Urn,
     peer_id: P,
     destination: PathBuf,
+    layout: crate::daemon::project::checkout::PathLayout,
+    post_checkout: Option<Vec<String>>,
+) -> Result<PathBuf, Error>
+where
+    S: Clone + Signer,
+    P: Into<Option<PeerId>> + Send + 'static,
+{
+    let (mut checkout, ownership) =
+        prepare_checkout(peer, urn.clone(), peer_id.into(), destination, layout).await?;
+    checkout.include_path = update_include(peer, urn).await?;
+    checkout.post_checkout = post_checkout;
+
+    let settings = settings(peer);
+    let path = spawn_blocking(move || checkout.run(settings, ownership)).await??;
+
+    Ok(path)
+}
+
+/// Checks that [`checkout`] can create a working copy with the given arguments, without cloning
+/// anything or writing the include file. Returns the directory the working copy would be
+/// created in.
+///
+/// See [`crate::daemon::project::Checkout::validate`] for the checks that are performed.
+///
+/// # Errors
+///
+/// * if the project can't be found
+/// * if the local peer has no default owner when checking out our own project
+/// * if any of the checks fails
+pub async fn validate_checkout<S, P>(
+    peer: &Peer<S>,
+    urn: Urn,
+    peer_id: P,
+    destination: PathBuf,
+    layout: crate::daemon::project::checkout::PathLayout,
 ) -> Result<PathBuf, Error>
 where
     S: Clone + Signer,
     P: Into<Option<PeerId>> + Send + 'static,
 {
     let peer_id = peer_id.into();
+    let (checkout, _ownership) = prepare_checkout(peer, urn, peer_id, destination, layout).await?;
+
+    let monorepo = monorepo(peer);
+    let path = spawn_blocking(move || {
+        let monorepo = git2::Repository::open(monorepo)?;
+        checkout.validate(&monorepo, peer_id)?;
+        Ok::<_, crate::daemon::project::checkout::Error>(checkout.project_path()?)
+    })
+    .await??;
+
+    Ok(path)
+}
+
+/// Gathers everything that is needed to check out the project of the given `urn` from
+/// `peer_id`, see [`checkout`].
+///
+/// The include path of the returned [`crate::daemon::project::Checkout`] is where the include
+/// file of the project is stored, but the file isn't written.
+async fn prepare_checkout<S>(
+    peer: &Peer<S>,
+    urn: Urn,
+    peer_id: Option<PeerId>,
+    destination: PathBuf,
+    layout: crate::daemon::project::checkout::PathLayout,
+) -> Result<
+    (
+        crate::daemon::project::Checkout<PathBuf>,
+        crate::daemon::project::checkout::Ownership,
+    ),
+    Error,
+>
+where
+    S: Clone + Signer,
+{
     let proj = get_project(peer, urn.clone())
         .await?
         .ok_or_else(|| Error::ProjectNotFound(urn.clone()))?;
-    let include_path = update_include(peer, urn.clone()).await?;
+    let include_path = Include::from_tracked_persons(
+        paths(peer).git_includes_dir().to_path_buf(),
+        LocalUrl::from(urn.clone()),
+        Vec::<(RefLike, PeerId)>::new(),
+    )
+    .file_path();
     let name = proj.subject().name.to_string();
     let default_branch: OneLevel = OneLevel::from(
         proj.subject()
@@ -509,16 +894,15 @@ where
             })?
             .parse::<RefLike>()?,
     );
-    let checkout = crate::daemon::project::Checkout {
-        urn: proj.urn(),
-        name,
-        default_branch,
-        path: destination,
-        include_path,
-    };
 
-    let ownership = match peer_id {
-        None => crate::daemon::project::checkout::Ownership::Local(peer.peer_id()),
+    let (ownership, owner) = match peer_id {
+        None => {
+            let owner = default_owner(peer).await?.ok_or(Error::MissingOwner)?;
+            (
+                crate::daemon::project::checkout::Ownership::Local(peer.peer_id()),
+                owner.into_inner().into_inner().subject().name.to_string(),
+            )
+        },
         Some(remote) => {
             let handle = {
                 let rad_self =
@@ -534,18 +918,29 @@ where
                 person.subject().name.to_string()
             };
 
-            crate::daemon::project::checkout::Ownership::Remote {
+            (
+                crate::daemon::project::checkout::Ownership::Remote {
+                    handle: handle.clone(),
+                    remote,
+                    local: peer.peer_id(),
+                },
                 handle,
-                remote,
-                local: peer.peer_id(),
-            }
+            )
         },
     };
 
-    let settings = settings(peer);
-    let path = spawn_blocking(move || checkout.run(settings, ownership)).await??;
+    let checkout = crate::daemon::project::Checkout {
+        urn: proj.urn(),
+        name,
+        default_branch,
+        path: destination,
+        layout,
+        owner,
+        post_checkout: None,
+        include_path,
+    };
 
-    Ok(path)
+    Ok((checkout, ownership))
 }
 
 /// Prepare the include file for the given `project` with the latest tracked
@@ -698,6 +1093,112 @@ where
     }
 }
 
+/// Branch names tried by [`resolve_default_branch`] if the configured default branch is missing.
+const FALLBACK_BRANCHES: [&str; 2] = ["main", "master"];
+
+/// Like [`get_branch`] with the project's default branch, but falls back to other branches of
+/// `remote` if the default branch was deleted or renamed.
+///
+/// The first branch that exists in this order is returned:
+///     * The default branch configured in the project metadata.
+///     * `main` and then `master`.
+///     * The first of the remaining branches of `remote` in lexical order.
+///
+/// # Errors
+///   * If the project could not be found.
+///   * If `remote` has no branches for the project.
+///   * If the storage operations fail.
+pub async fn resolve_default_branch<S, P>(
+    peer: &Peer<S>,
+    urn: Urn,
+    remote: P,
+) -> Result<Reference<Single>, Error>
+where
+    S: Clone + Signer,
+    P: Into<Option<PeerId>> + Clone + Send,
+{
+    let project = get_project(peer, urn.clone())
+        .await?
+        .ok_or_else(|| Error::ProjectNotFound(urn.clone()))?;
+
+    let mut candidates = project
+        .subject()
+        .default_branch
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    for name in FALLBACK_BRANCHES {
+        if !candidates.iter().any(|candidate| candidate == name) {
+            candidates.push(name.to_string());
+        }
+    }
+
+    for name in candidates {
+        match get_branch(peer, urn.clone(), remote.clone(), Cstring::from(name)).await {
+            Err(Error::MissingRef { .. }) => continue,
+            result => return result,
+        }
+    }
+
+    let remote = match remote.into() {
+        Some(peer_id) if peer_id == peer.peer_id() => None,
+        remote => remote,
+    };
+    let prefix = match remote {
+        Some(peer_id) => format!(
+            "refs/namespaces/{}/refs/remotes/{}/heads/",
+            urn.encode_id(),
+            peer_id
+        ),
+        None => format!("refs/namespaces/{}/refs/heads/", urn.encode_id()),
+    };
+    let first = {
+        let prefix = prefix.clone();
+        peer.using_storage(move |store| -> Result<Option<String>, Error> {
+            let mut names = store
+                .as_raw()
+                .references_glob(&format!("{}*", prefix))?
+                .names()
+                .filter_map(|name| Some(name.ok()?.strip_prefix(&prefix)?.to_string()))
+                .collect::<Vec<_>>();
+            names.sort();
+            Ok(names.into_iter().next())
+        })
+        .await??
+    };
+
+    match first {
+        Some(name) => get_branch(peer, urn, remote, Cstring::from(name)).await,
+        None => Err(Error::NoDefaultBranch {
+            name: project.subject().name.to_string(),
+            urn,
+        }),
+    }
+}
+
+/// Resolves the commit the default branch of `remote` points to, see
+/// [`resolve_default_branch`]. Unlike browsing the branch this only reads the reference.
+///
+/// # Errors
+///   * If the project could not be found.
+///   * If `remote` has no branches for the project.
+///   * If the storage operations fail.
+pub async fn head_oid<S, P>(peer: &Peer<S>, urn: Urn, remote: P) -> Result<git2::Oid, Error>
+where
+    S: Clone + Signer,
+    P: Into<Option<PeerId>> + Clone + Send,
+{
+    let reference = resolve_default_branch(peer, urn, remote).await?;
+    peer.using_storage(move |store| -> Result<git2::Oid, Error> {
+        Ok(store
+            .as_raw()
+            .find_reference(&reference.to_string())?
+            .peel_to_commit()?
+            .id())
+    })
+    .await?
+}
+
 /// Returns the [`PathBuf`] to the underlying monorepo.
 #[must_use]
 pub fn monorepo<S>(peer: &Peer<S>) -> PathBuf
@@ -749,6 +1250,109 @@ where
     .await?
 }
 
+/// Size and contents of the monorepo, see [`storage_stats`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStats {
+    /// Size of all files in the monorepo.
+    pub disk_bytes: u64,
+    /// Number of git objects, loose and packed.
+    pub object_count: u64,
+    /// Number of project identities.
+    pub project_count: usize,
+    /// Number of person identities.
+    pub user_count: usize,
+}
+
+/// Computes the [`StorageStats`] of the monorepo of `peer`.
+///
+/// Packed objects are counted from the headers of the pack indices, so the objects themselves are
+/// never read.
+///
+/// # Errors
+///
+///   * Listing the identities fails.
+///   * Reading the monorepo directory fails.
+pub async fn storage_stats<S>(peer: &Peer<S>) -> Result<StorageStats, Error>
+where
+    S: Clone + Signer,
+{
+    let (project_count, user_count) =
+        list_identities(peer)
+            .await?
+            .iter()
+            .fold((0, 0), |(projects, users), identity| match identity {
+                SomeIdentity::Project(_) => (projects + 1, users),
+                SomeIdentity::Person(_) => (projects, users + 1),
+                _ => (projects, users),
+            });
+    let git_dir = monorepo(peer);
+    let (disk_bytes, object_count) = spawn_blocking(move || disk_usage(&git_dir)).await??;
+
+    Ok(StorageStats {
+        disk_bytes,
+        object_count,
+        project_count,
+        user_count,
+    })
+}
+
+/// The size of all files below `git_dir` and the number of objects in the repository.
+fn disk_usage(git_dir: &std::path::Path) -> Result<(u64, u64), Error> {
+    let objects_dir = git_dir.join("objects");
+    let mut disk_bytes = 0;
+    let mut object_count = 0;
+    for entry in walkdir::WalkDir::new(git_dir) {
+        let entry = entry.map_err(std::io::Error::from)?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        disk_bytes += entry.metadata().map_err(std::io::Error::from)?.len();
+
+        let path = entry.path();
+        let in_objects = path
+            .strip_prefix(&objects_dir)
+            .map(|relative| relative.iter().collect::<Vec<_>>());
+        match in_objects.as_deref() {
+            // Loose objects are stored as `objects/<2 hex digits>/<remaining hex digits>`.
+            Ok([dir, _]) if dir.len() == 2 => object_count += 1,
+            Ok([dir, file])
+                if *dir == "pack"
+                    && std::path::Path::new(file).extension()
+                        == Some(std::ffi::OsStr::new("idx")) =>
+            {
+                object_count += pack_object_count(path)?;
+            },
+            _ => {},
+        }
+    }
+
+    Ok((disk_bytes, object_count))
+}
+
+/// Reads the number of objects in a pack from the last entry of the fan-out table of its index
+/// at `idx`.
+fn pack_object_count(idx: &std::path::Path) -> Result<u64, Error> {
+    use std::io::Read as _;
+
+    /// Marks version 2 and later of the index format, which is followed by the version number.
+    const MAGIC: [u8; 4] = [0xff, b't', b'O', b'c'];
+    /// Size of the fan-out table without its last entry.
+    const FANOUT_PREFIX: usize = 255 * 4;
+
+    let mut header = [0; 8 + FANOUT_PREFIX + 4];
+    std::fs::File::open(idx)?.read_exact(&mut header)?;
+    let offset = if header[..4] == MAGIC {
+        8 + FANOUT_PREFIX
+    } else {
+        FANOUT_PREFIX
+    };
+    let mut count = [0; 4];
+    count.copy_from_slice(&header[offset..offset + 4]);
+
+    Ok(u64::from(u32::from_be_bytes(count)))
+}
+
 /// Determine the [`peer::Role`] for a given [`Project`] and [`PeerId`].
 ///
 /// If `peer` is `Either::Left` then we have the local `PeerId` and we can
@@ -777,3 +1381,488 @@ fn role(
 
     Ok(role)
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Creates a new identity with `handle` and makes it the default owner of `peer`.
+    async fn switch_owner(peer: &Peer<BoxedSigner>, handle: &str) -> LocalIdentity {
+        set_default_owner(peer, None::<LocalIdentity>)
+            .await
+            .unwrap();
+        init_owner(
+            peer,
+            crate::identity::Metadata {
+                handle: handle.to_string(),
+                ethereum: None,
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    fn urns(projects: &[Project]) -> Vec<Urn> {
+        projects.iter().map(Project::urn).collect()
+    }
+
+    #[tokio::test]
+    async fn clone_project_from_peer() {
+        let (alice_peer, alice_addrs, _alice_shutdown) =
+            crate::peer::test::TestPeer::running().await;
+        let (bob_peer, _bob_addrs, _bob_shutdown) = crate::peer::test::TestPeer::running().await;
+        let alice = alice_peer.peer.librad_peer();
+        let bob = bob_peer.peer.librad_peer();
+
+        let alice_owner = switch_owner(alice, "alice").await;
+        let urn = crate::peer::test::create_project(alice, &alice_owner, "alpha")
+            .await
+            .urn();
+        {
+            let urn = urn.clone();
+            alice
+                .using_storage(move |store| Refs::update(store, &urn))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        switch_owner(bob, "bob").await;
+        assert!(list_projects(bob).await.unwrap().is_empty());
+        clone_project_with_timeout(
+            bob,
+            urn.clone(),
+            alice.peer_id(),
+            alice_addrs,
+            Some(Duration::from_secs(10)),
+        )
+        .await
+        .unwrap();
+
+        let projects = list_projects(bob).await.unwrap();
+        assert_eq!(urns(&projects), vec![urn]);
+    }
+
+    #[tokio::test]
+    async fn fork_cloned_project() {
+        let (alice_peer, alice_addrs, _alice_shutdown) =
+            crate::peer::test::TestPeer::running().await;
+        let (bob_peer, _bob_addrs, _bob_shutdown) = crate::peer::test::TestPeer::running().await;
+        let alice = alice_peer.peer.librad_peer();
+        let bob = bob_peer.peer.librad_peer();
+
+        let alice_owner = switch_owner(alice, "alice").await;
+        let urn = crate::peer::test::create_project(alice, &alice_owner, "alpha")
+            .await
+            .urn();
+        let head = crate::peer::test::commit_empty(alice, &urn, "main").await;
+        {
+            let urn = urn.clone();
+            alice
+                .using_storage(move |store| Refs::update(store, &urn))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let bob_owner = switch_owner(bob, "bob").await;
+        clone_project_with_timeout(
+            bob,
+            urn.clone(),
+            alice.peer_id(),
+            alice_addrs,
+            Some(Duration::from_secs(10)),
+        )
+        .await
+        .unwrap();
+
+        let fork = fork_project(bob, &bob_owner, urn.clone()).await.unwrap();
+        assert_ne!(fork.urn(), urn);
+        assert_eq!(fork.subject().name.to_string(), "alpha");
+        assert!(fork
+            .delegations()
+            .iter()
+            .any(|delegation| matches!(delegation, Either::Right(indirect) if indirect.urn() == bob_owner.urn())));
+        assert_eq!(head_oid(bob, fork.urn(), None).await.unwrap(), head);
+
+        assert!(matches!(
+            fork_project(bob, &bob_owner, urn.clone()).await,
+            Err(Error::IdentityExists(_))
+        ));
+        assert!(matches!(
+            fork_project(alice, &alice_owner, urn).await,
+            Err(Error::ForkOwnProject(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn storage_stats_counts_identities() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+        let owner = switch_owner(peer, "alice").await;
+        crate::peer::test::create_project(peer, &owner, "alpha").await;
+
+        let stats = storage_stats(peer).await.unwrap();
+        assert_eq!(stats.project_count, 1);
+        assert_eq!(stats.user_count, 1);
+        assert!(stats.disk_bytes > 0);
+        assert!(stats.object_count > 0);
+
+        // Packing the objects must not change their count.
+        let git_dir = monorepo(peer);
+        let output = std::process::Command::new("git")
+            .args(["repack", "-a", "-d"])
+            .current_dir(&git_dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let packed = storage_stats(peer).await.unwrap();
+        assert_eq!(packed.object_count, stats.object_count);
+    }
+
+    #[tokio::test]
+    async fn verify_tampered_project() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+        let owner = switch_owner(peer, "alice").await;
+        let urn = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+
+        verify_project(peer, urn.clone()).await.unwrap();
+
+        // Point the identity at a commit that is not signed by anyone.
+        let rad_id = format!("refs/namespaces/{}/refs/rad/id", urn.encode_id());
+        peer.using_storage(move |store| {
+            let repo = store.as_raw();
+            let head = repo.find_reference(&rad_id)?.peel_to_commit()?;
+            let signature = git2::Signature::now("mallory", "mallory@example.com")?;
+            let tampered = repo.commit(
+                None,
+                &signature,
+                &signature,
+                "Tampered identity",
+                &head.tree()?,
+                &[&head],
+            )?;
+            repo.reference(&rad_id, tampered, true, "tamper with identity")?;
+            Ok::<_, git2::Error>(())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(matches!(
+            verify_project(peer, urn).await,
+            Err(Error::ProjectVerification { .. })
+        ));
+        let unknown = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        assert!(matches!(
+            verify_project(peer, unknown).await,
+            Err(Error::ProjectNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn track_all_reports_partial_results() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+        let owner = switch_owner(peer, "alice").await;
+        let urn = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+        let first = PeerId::from(link_crypto::SecretKey::new());
+        let failing = PeerId::from(link_crypto::SecretKey::new());
+        let last = PeerId::from(link_crypto::SecretKey::new());
+
+        // Hold the lock of the tracking entry of `failing` so that writing it fails.
+        let lock = monorepo(peer).join(format!(
+            "refs/rad/remotes/{}/{}.lock",
+            urn.encode_id(),
+            failing
+        ));
+        std::fs::create_dir_all(lock.parent().unwrap()).unwrap();
+        std::fs::write(&lock, "").unwrap();
+
+        let outcomes = track_all(peer, urn.clone(), vec![first, failing, last])
+            .await
+            .unwrap();
+        assert_eq!(
+            outcomes
+                .iter()
+                .map(|(peer_id, result)| (*peer_id, result.is_ok()))
+                .collect::<Vec<_>>(),
+            vec![(first, true), (failing, false), (last, true)]
+        );
+
+        let mut tracked = peer
+            .using_storage(move |store| {
+                let mut peers = vec![];
+                for peer_id in tracking::tracked_peers(store, Some(&urn))? {
+                    peers.push(peer_id?);
+                }
+                Ok::<_, Error>(peers)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        tracked.sort();
+        let mut expected = vec![first, last];
+        expected.sort();
+        assert_eq!(tracked, expected);
+    }
+
+    #[tokio::test]
+    async fn resolve_default_branch_falls_back() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        // The default branch of the project is `main`, which is never created.
+        let owner = switch_owner(peer, "alice").await;
+        let urn = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+        assert!(matches!(
+            resolve_default_branch(peer, urn.clone(), None).await,
+            Err(Error::NoDefaultBranch { .. })
+        ));
+
+        crate::peer::test::commit_empty(peer, &urn, "develop").await;
+        let reference = resolve_default_branch(peer, urn.clone(), None)
+            .await
+            .unwrap();
+        assert!(reference.to_string().ends_with("/refs/heads/develop"));
+
+        crate::peer::test::commit_empty(peer, &urn, "master").await;
+        let reference = resolve_default_branch(peer, urn.clone(), None)
+            .await
+            .unwrap();
+        assert!(reference.to_string().ends_with("/refs/heads/master"));
+    }
+
+    #[tokio::test]
+    async fn head_oid_of_default_branch() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        let owner = switch_owner(peer, "alice").await;
+        let urn = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+        assert!(matches!(
+            head_oid(peer, urn.clone(), None).await,
+            Err(Error::NoDefaultBranch { .. })
+        ));
+
+        let commit = crate::peer::test::commit_empty(peer, &urn, "main").await;
+
+        assert_eq!(head_oid(peer, urn, None).await.unwrap(), commit);
+    }
+
+    #[tokio::test]
+    async fn project_exists_lookup() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        let owner = switch_owner(peer, "alice").await;
+        let urn = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+        assert!(project_exists(peer, urn).await.unwrap());
+
+        let missing = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        assert!(!project_exists(peer, missing).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn list_projects_for_owner_filters_by_owner() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        let alice = switch_owner(peer, "alice").await;
+        let alice_project = crate::peer::test::create_project(peer, &alice, "alice-project")
+            .await
+            .urn();
+        let bob = switch_owner(peer, "bob").await;
+        let bob_project = crate::peer::test::create_project(peer, &bob, "bob-project")
+            .await
+            .urn();
+
+        let projects = list_projects_for_owner(peer, Some(alice.urn()))
+            .await
+            .unwrap();
+        assert_eq!(urns(&projects), vec![alice_project]);
+
+        let projects = list_projects_for_owner(peer, Some(bob.urn()))
+            .await
+            .unwrap();
+        assert_eq!(urns(&projects), vec![bob_project.clone()]);
+
+        // Bob is the default owner now.
+        let projects = list_projects_for_owner(peer, None).await.unwrap();
+        assert_eq!(urns(&projects), vec![bob_project]);
+    }
+
+    #[tokio::test]
+    async fn list_projects_is_ordered() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        let owner = switch_owner(peer, "alice").await;
+        let gamma = crate::peer::test::create_project(peer, &owner, "gamma")
+            .await
+            .urn();
+        let alpha = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+        let beta = crate::peer::test::create_project(peer, &owner, "beta")
+            .await
+            .urn();
+
+        let first = list_projects(peer).await.unwrap();
+        let second = list_projects(peer).await.unwrap();
+        assert_eq!(urns(&first), vec![alpha, beta, gamma]);
+        assert_eq!(urns(&first), urns(&second));
+    }
+
+    #[tokio::test]
+    async fn list_projects_paged_boundaries() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        let owner = switch_owner(peer, "alice").await;
+        let alpha = crate::peer::test::create_project(peer, &owner, "alpha")
+            .await
+            .urn();
+        let beta = crate::peer::test::create_project(peer, &owner, "beta")
+            .await
+            .urn();
+        let gamma = crate::peer::test::create_project(peer, &owner, "gamma")
+            .await
+            .urn();
+
+        let (projects, total) = list_projects_paged(peer, None, 0, 2).await.unwrap();
+        assert_eq!((urns(&projects), total), (vec![alpha, beta], 3));
+
+        let (projects, total) = list_projects_paged(peer, None, 2, 2).await.unwrap();
+        assert_eq!((urns(&projects), total), (vec![gamma], 3));
+
+        let (projects, total) = list_projects_paged(peer, None, 3, 2).await.unwrap();
+        assert_eq!((urns(&projects), total), (vec![], 3));
+    }
+
+    #[test]
+    fn page_boundaries() {
+        let items = (0..5).collect::<Vec<_>>();
+
+        assert_eq!(page(items.clone(), 0, 2), (vec![0, 1], 5));
+        assert_eq!(page(items.clone(), 4, 2), (vec![4], 5));
+        assert_eq!(page(items.clone(), 5, 2), (vec![], 5));
+        assert_eq!(page(items.clone(), 7, 2), (vec![], 5));
+        assert_eq!(page(items, 1, 0), (vec![], 5));
+    }
+
+    #[tokio::test]
+    async fn init_project_reuses_leftover_rad_remote() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+        let owner = switch_owner(peer, "alice").await;
+
+        let path = test_peer.temp_dir.path().join("alpha");
+        let repo = git2::Repository::init(&path).unwrap();
+        let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        repo.commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+
+        // A previous attempt to create the project left its `rad` remote behind.
+        let urn = {
+            let payload = payload::Project {
+                default_branch: Some(Cstring::from("main".to_string())),
+                description: Some(Cstring::from("A project".to_string())),
+                name: Cstring::from("alpha".to_string()),
+            };
+            let delegations = Indirect::from(owner.clone().into_inner().into_inner());
+            peer.using_storage(move |store| project::urn(store, payload, delegations))
+                .await
+                .unwrap()
+                .unwrap()
+        };
+        let url = LocalUrl::from(urn.clone());
+        repo.remote("rad", &url.to_string()).unwrap();
+
+        let create = || crate::daemon::project::Create {
+            description: "A project".to_string(),
+            default_branch: OneLevel::from(librad::reflike!("main")),
+            repo: crate::daemon::project::Repo::Existing { path: path.clone() },
+            sign: false,
+        };
+        let project = init_project(peer, &owner, create()).await.unwrap();
+        assert_eq!(project.urn(), urn);
+        assert_eq!(
+            repo.remotes()
+                .unwrap()
+                .iter()
+                .map(|name| name.unwrap().to_string())
+                .collect::<Vec<_>>(),
+            vec!["rad"]
+        );
+        assert_eq!(
+            repo.find_remote("rad").unwrap().url(),
+            Some(url.to_string().as_str())
+        );
+
+        // Once the project exists it is reported as such instead of failing on the remote.
+        assert!(matches!(
+            init_project(peer, &owner, create()).await,
+            Err(Error::IdentityExists(existing)) if existing == urn
+        ));
+    }
+
+    #[tokio::test]
+    async fn with_timeout_fires() {
+        let timeout = Duration::from_millis(10);
+        let res = with_timeout(timeout, futures::future::pending::<Result<(), Error>>()).await;
+        assert!(matches!(res, Err(Error::Timeout(elapsed)) if elapsed == timeout));
+
+        let res = with_timeout(timeout, async { Ok::<_, Error>(42) }).await;
+        assert_eq!(res.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn with_timeout_abandons_task() {
+        struct Guard(Arc<AtomicBool>);
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let guard = Guard(dropped.clone());
+        let res = with_timeout(Duration::from_millis(10), async move {
+            let _guard = guard;
+            futures::future::pending::<Result<(), Error>>().await
+        })
+        .await;
+
+        assert!(matches!(res, Err(Error::Timeout(_))));
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
@@ -13,7 +13,7 @@ use librad::{
     },
     net,
 };
-use std::{convert::Infallible, panic};
+use std::{convert::Infallible, panic, time::Duration};
 
 /// Errors that may occur when interacting with [`librad::net::peer::Peer`].
 #[derive(Debug, thiserror::Error)]
@@ -93,6 +93,19 @@ pub enum Error {
     #[error("the URN `{0}` already exists")]
     IdentityExists(Urn),
 
+    /// Attempted to fork a project the local owner is already a delegate of.
+    #[error("the project `{0}` is already owned by the local user")]
+    ForkOwnProject(Urn),
+
+    /// The history of the project identity is not valid.
+    #[error("the identity history of the project '{urn}' could not be verified")]
+    ProjectVerification {
+        /// Urn of the project.
+        urn: Urn,
+        /// The reason the verification failed.
+        source: Box<librad::git::identities::Error>,
+    },
+
     /// There were no references for a Browser to be initialised.
     #[error("we could not find a default branch for '{name}@{urn}'")]
     NoDefaultBranch {
@@ -109,6 +122,10 @@ pub enum Error {
         reference: Reference<One>,
     },
 
+    /// An I/O error occurred while reading the monorepo.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     /// A document payload extension was malformed
     #[error(transparent)]
     MalformedPayloadExt(#[from] librad::identities::payload::ExtError),
@@ -116,6 +133,10 @@ pub enum Error {
     /// A spawned task was cancelled
     #[error("spawned task cancelled")]
     TaskCancelled,
+
+    /// An operation did not complete within the given duration.
+    #[error("the operation did not complete within {0:?}")]
+    Timeout(Duration),
 }
 
 /// Error while performing tracking operation
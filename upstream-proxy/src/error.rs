@@ -40,9 +40,59 @@ pub enum Error {
     #[error("project not found")]
     ProjectNotFound,
 
+    /// The given id is neither a `%`-prefixed nor a bare URN, nor a `<handle>@<peer_id>`
+    /// identifier.
+    #[error("invalid id `{id}`: {reason}")]
+    InvalidId { id: String, reason: String },
+
+    /// No known person matches the given `<handle>@<peer_id>` identifier.
+    #[error("identifier `{0}` not found")]
+    IdentifierNotFound(crate::identifier::Identifier),
+
+    /// There is no identity with the given URN.
+    #[error("identity `{0}` not found")]
+    IdentityNotFound(link_identities::git::Urn),
+
+    /// The identity with the given URN is not a person, for example because it is a project.
+    #[error("identity `{0}` is not a person")]
+    NotAPerson(link_identities::git::Urn),
+
+    /// The given project name can't be used as a directory name.
+    #[error("invalid project name `{0}`")]
+    InvalidProjectName(String),
+
+    /// The requested branch, tag or commit does not exist.
+    #[error("revision `{0}` not found")]
+    RevisionNotFound(String),
+
+    /// A path that was expected to be a directory is a file.
+    #[error("`{0}` is a file, not a directory")]
+    NotADirectory(String),
+
+    /// Two revisions that were expected to share history have no common ancestor.
+    #[error("`{base}` and `{head}` have unrelated histories")]
+    UnrelatedHistories { base: String, head: String },
+
     #[error("missing default branch")]
     MissingDefaultBranch,
 
+    /// The default branch of a project has no commits yet.
+    #[error("default branch `{0}` has no commits")]
+    EmptyDefaultBranch(String),
+
+    /// An `Idempotency-Key` was reused for a request with a different body.
+    #[error("idempotency key `{0}` was used for a different request")]
+    IdempotencyKeyMismatch(String),
+
+    /// A request with the same `Idempotency-Key` is still being processed.
+    #[error("a request with idempotency key `{0}` is still being processed")]
+    IdempotencyKeyPending(String),
+
+    /// The operation was given up because its caller went away, see
+    /// [`crate::browser::using_cancellable`].
+    #[error("the operation was cancelled")]
+    Cancelled,
+
     #[error("Failed to open readonly Git storage")]
     OpenReadOnlyGitStorage(#[from] librad::git::storage::read::error::Init),
 
@@ -165,20 +165,8 @@ fn validate_commit(repo: &git2::Repository, oid: git2::Oid) -> anyhow::Result<()
         "failed to parse commit message as envelope for {oid}"
     ))?;
 
-    let (signature_encoded_bytes, signed) =
-        repo.extract_signature(&oid, Some("radicle-ed25519"))?;
-    let signature = {
-        let signature_encoded =
-            std::str::from_utf8(&signature_encoded_bytes).context("invalid signature field")?;
-        let signature_bytes =
-            base64::decode(signature_encoded).context("invalid base64 signature encoding")?;
-        minicbor::decode::<link_crypto::Signature>(&signature_bytes)
-            .context("failed to decode signature")?
-    };
-
-    if !signature.verify(&signed, envelope.peer_id.as_public_key()) {
-        anyhow::bail!("signature could not be verified");
-    }
+    crate::signed_commit::verify(repo, oid, &envelope.peer_id)
+        .context(format!("invalid signature of {oid}"))?;
 
     Ok(())
 }
@@ -193,24 +181,10 @@ fn commit_signed<'repo>(
     tree: &git2::Tree,
     parents: &[&git2::Commit],
 ) -> anyhow::Result<git2::Commit<'repo>> {
-    use librad::Signer as _;
-
     let git_signature = repo.signature().context("failed to get repo signature")?;
-    let commit_buffer =
-        repo.commit_create_buffer(&git_signature, &git_signature, message, tree, parents)?;
-
-    let signature = signer
-        .sign_blocking(commit_buffer.as_ref())
-        .context("failed to sign commit")?;
-    let signature = link_crypto::Signature::from(signature);
-    let signature_bytes = minicbor::to_vec(signature).context("failed to encode signature")?;
-    let commit_id = repo.commit_signed(
-        commit_buffer
-            .as_str()
-            .expect("commit buffer is not valid UTF-8"),
-        &base64::encode(signature_bytes),
-        Some("radicle-ed25519"),
-    )?;
+    let commit_id =
+        crate::signed_commit::commit(repo, signer, &git_signature, message, tree, parents)
+            .context("failed to create signed commit")?;
     let commit = repo.find_commit(commit_id).context("commit not found")?;
     repo.reference(reference, commit_id, true, "update")?;
     Ok(commit)
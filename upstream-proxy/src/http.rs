@@ -6,8 +6,15 @@
 
 //! HTTP API delivering JSON over `RESTish` endpoints.
 
+use std::sync::Arc;
+
+use futures::{stream::BoxStream, StreamExt as _, TryStreamExt as _};
 use serde::Deserialize;
-use warp::{filters::BoxedFilter, path, Filter, Rejection, Reply};
+use warp::{
+    filters::BoxedFilter,
+    hyper::body::{Buf as _, Bytes},
+    path, Filter, Rejection, Reply,
+};
 
 use link_crypto::PeerId;
 
@@ -35,15 +42,122 @@ macro_rules! combine {
     }
 }
 
+/// Default for [`BodyLimits::json`].
+pub const DEFAULT_MAX_JSON_BODY_BYTES: u64 = 1024 * 1024;
+/// Default for [`BodyLimits::bundle`].
+pub const DEFAULT_MAX_BUNDLE_BODY_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Maximum sizes of request bodies. Requests with larger bodies are rejected with
+/// `413 Payload Too Large` before the body is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimits {
+    /// Maximum size of JSON bodies in bytes.
+    pub json: u64,
+    /// Maximum size of uploaded git bundles in bytes.
+    pub bundle: u64,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            json: DEFAULT_MAX_JSON_BODY_BYTES,
+            bundle: DEFAULT_MAX_BUNDLE_BODY_BYTES,
+        }
+    }
+}
+
+/// Default for [`HistoryWalkLimits::max_concurrent`].
+pub const DEFAULT_MAX_CONCURRENT_HISTORY_WALKS: std::num::NonZeroUsize =
+    match std::num::NonZeroUsize::new(4) {
+        Some(max) => max,
+        None => panic!("the default number of history walks must not be 0"),
+    };
+/// Default for [`HistoryWalkLimits::timeout`].
+pub const DEFAULT_HISTORY_WALK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Limits for requests that walk the history of a project, like listing commits. Requests
+/// beyond `max_concurrent` wait for a running one to finish and are rejected with
+/// `503 Service Unavailable` if that takes longer than `timeout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryWalkLimits {
+    /// Maximum number of history walks running at the same time. At least one walk has to be
+    /// allowed, otherwise every request would wait for a permit until it times out.
+    pub max_concurrent: std::num::NonZeroUsize,
+    /// Maximum time a request waits for a running history walk to finish.
+    pub timeout: std::time::Duration,
+}
+
+impl Default for HistoryWalkLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT_HISTORY_WALKS,
+            timeout: DEFAULT_HISTORY_WALK_TIMEOUT,
+        }
+    }
+}
+
+/// Shared state enforcing [`HistoryWalkLimits`] across all requests, see
+/// [`with_history_walk_permit`].
+#[derive(Debug, Clone)]
+pub struct HistoryWalks {
+    /// Holds one permit per history walk that may run.
+    semaphore: Arc<tokio::sync::Semaphore>,
+    /// See [`HistoryWalkLimits::timeout`].
+    timeout: std::time::Duration,
+}
+
+impl HistoryWalks {
+    /// Creates the shared state for `limits`.
+    #[must_use]
+    pub fn new(limits: HistoryWalkLimits) -> Self {
+        Self {
+            semaphore: Arc::new(tokio::sync::Semaphore::new(limits.max_concurrent.get())),
+            timeout: limits.timeout,
+        }
+    }
+}
+
+/// Permission to walk the history of a project, see [`with_history_walk_permit`]. Handlers hold
+/// on to it until they are done.
+#[derive(Debug)]
+pub struct HistoryWalkPermit(tokio::sync::OwnedSemaphorePermit);
+
+/// Configuration of the HTTP API.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Limits for the size of request bodies.
+    pub body_limits: BodyLimits,
+    /// Limits for requests that walk the history of a project.
+    pub history_walks: HistoryWalkLimits,
+    /// Reject all requests that may modify state, see [`with_read_only_guard`].
+    pub read_only: bool,
+    /// Serve endpoints for debugging repositories, see [`with_debug_guard`].
+    pub debug_endpoints: bool,
+    /// Command run in every working copy checked out through the API, see
+    /// [`crate::daemon::state::checkout`].
+    pub post_checkout: Option<Vec<String>>,
+}
+
 /// Main entry point for HTTP API.
 pub fn api(
     ctx: context::Context,
+    config: Config,
 ) -> impl Filter<Extract = impl Reply, Error = std::convert::Infallible> + Clone {
     let notification_filter = path("notifications").and(notification::filters(ctx.clone()));
-    let project_filter = path("projects").and(project::filters(ctx.clone()));
-    let source_filter = path("source").and(source::filters(ctx));
+    let project_filter = path("projects").and(project::filters(
+        ctx.clone(),
+        config.body_limits,
+        config.post_checkout,
+    ));
+    let source_filter = path("source").and(source::filters(
+        ctx,
+        HistoryWalks::new(config.history_walks),
+        config.debug_endpoints,
+    ));
 
-    let api = path("v1").and(combine!(notification_filter, project_filter, source_filter));
+    let api = path("v1")
+        .and(with_read_only_guard(config.read_only))
+        .and(combine!(notification_filter, project_filter, source_filter));
 
     api.recover(error::recover)
 }
@@ -156,6 +270,333 @@ where
         .boxed()
 }
 
+/// Whether requests with `method` never modify state.
+#[must_use]
+pub fn is_safe_method(method: &warp::http::Method) -> bool {
+    matches!(
+        *method,
+        warp::http::Method::GET | warp::http::Method::HEAD | warp::http::Method::OPTIONS
+    )
+}
+
+/// Rejects all requests that may modify state with `405 Method Not Allowed` if `read_only` is
+/// set. Requests with a method for which [`is_safe_method`] holds always pass.
+#[must_use]
+pub fn with_read_only_guard(read_only: bool) -> BoxedFilter<()> {
+    warp::method()
+        .and_then(move |method: warp::http::Method| async move {
+            if read_only && !is_safe_method(&method) {
+                Err(Rejection::from(error::Response {
+                    status_code: warp::http::StatusCode::METHOD_NOT_ALLOWED,
+                    variant: "READ_ONLY",
+                    message: "the API is in read-only mode".to_string(),
+                }))
+            } else {
+                Ok(())
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+/// Rejects requests for an endpoint meant for debugging as if it did not exist unless
+/// `debug_endpoints` is set.
+#[must_use]
+pub fn with_debug_guard(debug_endpoints: bool) -> BoxedFilter<()> {
+    warp::any()
+        .and_then(move || async move {
+            if debug_endpoints {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+/// Waits until fewer than [`HistoryWalkLimits::max_concurrent`] requests hold a
+/// [`HistoryWalkPermit`] and passes a new permit to the handler.
+///
+/// # Errors
+///
+/// The filter rejects with `503 Service Unavailable` if no permit becomes available within
+/// [`HistoryWalkLimits::timeout`].
+#[must_use]
+pub fn with_history_walk_permit(walks: HistoryWalks) -> BoxedFilter<(HistoryWalkPermit,)> {
+    warp::any()
+        .and_then(move || {
+            let walks = walks.clone();
+            async move {
+                match tokio::time::timeout(walks.timeout, walks.semaphore.acquire_owned()).await {
+                    Ok(permit) => Ok(HistoryWalkPermit(
+                        permit.expect("history walk semaphore is never closed"),
+                    )),
+                    Err(_) => Err(Rejection::from(error::Response {
+                        status_code: warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                        variant: "TOO_MANY_HISTORY_WALKS",
+                        message: "too many requests are walking project histories".to_string(),
+                    })),
+                }
+            }
+        })
+        .boxed()
+}
+
+/// Request body passed to handlers by [`with_limited_body`].
+pub type BodyStream = BoxStream<'static, Result<Bytes, Rejection>>;
+
+/// Streams the body of the request, failing once more than `limit` bytes have been received.
+///
+/// Requests without a `Content-Length` header, e.g. chunked ones, are accepted and counted while
+/// they are read.
+///
+/// # Errors
+///
+/// The filter and the stream reject with `413 Payload Too Large` if the body is larger than
+/// `limit`.
+#[must_use]
+pub fn with_limited_body(limit: u64) -> BoxedFilter<(BodyStream,)> {
+    warp::header::optional::<u64>("content-length")
+        .and_then(move |length: Option<u64>| async move {
+            match length {
+                Some(length) if length > limit => Err(payload_too_large()),
+                _ => Ok(()),
+            }
+        })
+        .untuple_one()
+        .and(warp::body::stream())
+        .map(move |stream| {
+            let mut received: u64 = 0;
+            stream
+                .map(move |chunk: Result<_, warp::Error>| {
+                    let mut chunk = chunk.map_err(|err| {
+                        Rejection::from(error::Response {
+                            status_code: warp::http::StatusCode::BAD_REQUEST,
+                            variant: "INVALID_BODY",
+                            message: err.to_string(),
+                        })
+                    })?;
+                    received = received.saturating_add(chunk.remaining() as u64);
+                    if received > limit {
+                        return Err(payload_too_large());
+                    }
+
+                    Ok(chunk.copy_to_bytes(chunk.remaining()))
+                })
+                .boxed()
+        })
+        .boxed()
+}
+
+/// Deserializes the JSON body of the request if it is at most `limit` bytes long.
+///
+/// # Errors
+///
+/// The filter rejects with `413 Payload Too Large` if the body is too large and with `400 Bad
+/// Request` if it is not valid JSON for `T`.
+#[must_use]
+pub fn with_json_body<T>(limit: u64) -> BoxedFilter<(T,)>
+where
+    for<'de> T: Deserialize<'de> + Send + Sync + 'static,
+{
+    with_limited_body(limit)
+        .and_then(|body: BodyStream| async move {
+            let body = body
+                .try_fold(Vec::new(), |mut body, chunk| async move {
+                    body.extend_from_slice(&chunk);
+                    Ok(body)
+                })
+                .await?;
+
+            serde_json::from_slice(&body).map_err(|err| {
+                Rejection::from(error::Response {
+                    status_code: warp::http::StatusCode::BAD_REQUEST,
+                    variant: "INVALID_BODY",
+                    message: err.to_string(),
+                })
+            })
+        })
+        .boxed()
+}
+
+fn payload_too_large() -> Rejection {
+    Rejection::from(error::Response {
+        status_code: warp::http::StatusCode::PAYLOAD_TOO_LARGE,
+        variant: "PAYLOAD_TOO_LARGE",
+        message: "Request body is too large".to_string(),
+    })
+}
+
+/// Extracts the value of the `If-None-Match` header, if present.
+#[must_use]
+pub fn with_if_none_match() -> BoxedFilter<(Option<String>,)> {
+    warp::header::optional::<String>("if-none-match").boxed()
+}
+
+/// Extracts the value of the `If-Modified-Since` header, if present and a valid HTTP date.
+#[must_use]
+pub fn with_if_modified_since() -> BoxedFilter<(Option<headers::IfModifiedSince>,)> {
+    warp::header::headers_cloned()
+        .map(|headers: warp::http::HeaderMap| {
+            use headers::HeaderMapExt as _;
+
+            headers.typed_get::<headers::IfModifiedSince>()
+        })
+        .boxed()
+}
+
+/// Extracts whether the `Accept` header prefers `text/plain` over `application/json`.
+///
+/// Resolves to `false` if the header is absent, so JSON stays the default representation.
+/// Responses negotiated this way should carry `Vary: Accept`.
+#[must_use]
+pub fn with_accept_plain_text() -> BoxedFilter<(bool,)> {
+    warp::header::optional::<String>("accept")
+        .map(|accept: Option<String>| accept.as_deref().map_or(false, prefers_plain_text))
+        .boxed()
+}
+
+/// Whether `accept` ranks `text/plain` strictly higher than `application/json`.
+///
+/// Media types missing from the header are ranked with a quality of zero and wildcards are
+/// ignored.
+fn prefers_plain_text(accept: &str) -> bool {
+    let quality = |media_type: &str| {
+        accept
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';').map(str::trim);
+                if !params.next()?.eq_ignore_ascii_case(media_type) {
+                    return None;
+                }
+                let q = params
+                    .find_map(|param| param.strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+                Some(q)
+            })
+            .fold(0.0_f32, f32::max)
+    };
+
+    quality("text/plain") > quality("application/json")
+}
+
+/// The `Content-Disposition` header value for downloading a file called `filename`.
+///
+/// The quoted `filename` parameter only contains printable ASCII with quotes and backslashes
+/// escaped. Clients that understand the `filename*` parameter get the exact name from it.
+#[must_use]
+pub fn attachment(filename: &str) -> String {
+    let mut quoted = String::with_capacity(filename.len());
+    for c in filename.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            },
+            ' '..='~' => quoted.push(c),
+            _ => quoted.push('_'),
+        }
+    }
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        quoted,
+        percent_encoding::utf8_percent_encode(filename, percent_encoding::NON_ALPHANUMERIC)
+    )
+}
+
+/// Computes an entity tag from `parts`.
+///
+/// The tag is a digest over the given parts, so callers must only pass values that are stable
+/// across process restarts, e.g. git object ids and paths.
+#[must_use]
+pub fn etag(parts: &[&[u8]]) -> String {
+    use sha2::Digest as _;
+
+    let mut hasher = sha2::Sha256::new();
+    for part in parts {
+        hasher.update(part);
+        hasher.update([0]);
+    }
+
+    format!("\"{}\"", data_encoding::HEXLOWER.encode(&hasher.finalize()))
+}
+
+/// Whether a request with the `If-None-Match` header `if_none_match` already has the
+/// representation tagged `etag`.
+#[must_use]
+pub fn is_not_modified(etag: &str, if_none_match: Option<&str>) -> bool {
+    if_none_match.map_or(false, |header| {
+        header
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+    })
+}
+
+/// Sets the `ETag` header of `reply` to `etag`.
+///
+/// If `if_none_match` matches `etag` the reply is replaced with an empty `304 Not Modified`
+/// response.
+#[must_use]
+pub fn with_etag(
+    reply: impl Reply,
+    etag: &str,
+    if_none_match: Option<&str>,
+) -> warp::reply::Response {
+    let mut response = if is_not_modified(etag, if_none_match) {
+        warp::http::StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        reply.into_response()
+    };
+    if let Ok(value) = warp::http::HeaderValue::from_str(etag) {
+        response
+            .headers_mut()
+            .insert(warp::http::header::ETAG, value);
+    }
+
+    response
+}
+
+/// Sets the `Last-Modified` header of `response` to `committer_time`, which is truncated to whole
+/// seconds. Nothing is changed if `committer_time` is `None` or before the Unix epoch.
+///
+/// If `if_modified_since` is at or after `committer_time` a successful `response` is replaced
+/// with an empty `304 Not Modified` response. `If-None-Match` takes precedence over
+/// `If-Modified-Since`, so callers must not pass `if_modified_since` if the request has an
+/// `If-None-Match` header.
+#[must_use]
+pub fn with_last_modified(
+    response: warp::reply::Response,
+    committer_time: Option<git2::Time>,
+    if_modified_since: Option<&headers::IfModifiedSince>,
+) -> warp::reply::Response {
+    use headers::HeaderMapExt as _;
+
+    // Commit times are seconds since the epoch in UTC, the offset is only relevant for display.
+    let last_modified = match committer_time
+        .and_then(|time| u64::try_from(time.seconds()).ok())
+        .map(|seconds| std::time::UNIX_EPOCH + std::time::Duration::from_secs(seconds))
+    {
+        Some(last_modified) => last_modified,
+        None => return response,
+    };
+
+    let not_modified = response.status() == warp::http::StatusCode::OK
+        && if_modified_since.map_or(false, |since| !since.is_modified(last_modified));
+    let mut response = if not_modified {
+        warp::http::StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        response
+    };
+    response
+        .headers_mut()
+        .typed_insert(headers::LastModified::from(last_modified));
+
+    response
+}
+
 /// Guard against access of wrong paths by the owners Peer ID.
 #[must_use]
 pub fn guard_self_peer_id(peer: &crate::peer::Peer, peer_id: Option<PeerId>) -> Option<PeerId> {
@@ -291,4 +732,390 @@ mod test {
             );
         });
     }
+
+    #[test]
+    fn attachment_escapes_filename() {
+        assert_eq!(
+            attachment("alpha-main.zip"),
+            "attachment; filename=\"alpha-main.zip\"; filename*=UTF-8''alpha%2Dmain%2Ezip"
+        );
+        assert_eq!(
+            attachment("a\"b\\c\r\nd\u{e9}.zip"),
+            "attachment; filename=\"a\\\"b\\\\c__d_.zip\"; \
+             filename*=UTF-8''a%22b%5Cc%0D%0Ad%C3%A9%2Ezip"
+        );
+    }
+
+    #[tokio::test]
+    async fn with_json_body_limit() {
+        let api = with_json_body::<Query>(16)
+            .map(|query| warp::reply::json(&query))
+            .recover(super::error::recover)
+            .boxed();
+
+        let res = warp::test::request()
+            .method("POST")
+            .json(&Query { value: 72 })
+            .reply(&api)
+            .await;
+        assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, serde_json::json!({ "value": 72 }));
+        });
+
+        let res = warp::test::request()
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(format!(
+                r#"{{"value": 72, "padding": "{}"}}"#,
+                "x".repeat(16)
+            ))
+            .reply(&api)
+            .await;
+        assert_response(&res, StatusCode::PAYLOAD_TOO_LARGE, |have| {
+            assert_eq!(have["variant"], "PAYLOAD_TOO_LARGE");
+        });
+    }
+
+    #[tokio::test]
+    async fn with_json_body_accepts_chunked_requests() {
+        use tower::ServiceExt as _;
+
+        let api = with_json_body::<Query>(16)
+            .map(|query| warp::reply::json(&query))
+            .recover(super::error::recover);
+        let chunked_request = |chunks: Vec<&'static str>| {
+            let chunks = chunks.into_iter().map(Ok::<_, std::io::Error>);
+            warp::http::Request::builder()
+                .method("POST")
+                .uri("/")
+                .body(warp::hyper::Body::wrap_stream(futures::stream::iter(
+                    chunks,
+                )))
+                .unwrap()
+        };
+
+        let res = warp::service(api.clone())
+            .oneshot(chunked_request(vec![r#"{"value": "#, "72}"]))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = warp::hyper::body::to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, r#"{"value":72}"#);
+
+        let res = warp::service(api)
+            .oneshot(chunked_request(vec![
+                r#"{"value": 72, "#,
+                r#""padding": "xxxx"}"#,
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    fn with_read_only_guard_test_filter(read_only: bool) -> BoxedFilter<(impl Reply,)> {
+        let source = path("source")
+            .and(warp::get())
+            .map(|| warp::reply::json(&"source"));
+        let create_project = path("projects")
+            .and(warp::post())
+            .map(|| warp::reply::json(&"created"));
+
+        with_read_only_guard(read_only)
+            .and(source.or(create_project))
+            .recover(super::error::recover)
+            .boxed()
+    }
+
+    #[tokio::test]
+    async fn with_read_only_guard_rejects_mutations() {
+        let api = with_read_only_guard_test_filter(true);
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/source")
+            .reply(&api)
+            .await;
+        assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, serde_json::json!("source"));
+        });
+
+        let res = warp::test::request()
+            .method("POST")
+            .path("/projects")
+            .reply(&api)
+            .await;
+        assert_response(&res, StatusCode::METHOD_NOT_ALLOWED, |have| {
+            assert_eq!(have["variant"], "READ_ONLY");
+        });
+
+        let res = warp::test::request()
+            .method("POST")
+            .path("/projects")
+            .reply(&with_read_only_guard_test_filter(false))
+            .await;
+        assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, serde_json::json!("created"));
+        });
+    }
+
+    #[tokio::test]
+    async fn with_debug_guard_hides_endpoints() {
+        let filter = |debug_endpoints| {
+            with_debug_guard(debug_endpoints)
+                .and(path("objects"))
+                .map(|| warp::reply::json(&"objects"))
+                .recover(super::error::recover)
+        };
+
+        let res = warp::test::request()
+            .path("/objects")
+            .reply(&filter(false))
+            .await;
+        assert_response(&res, StatusCode::NOT_FOUND, |have| {
+            assert_eq!(have["variant"], "NOT_FOUND");
+        });
+
+        let res = warp::test::request()
+            .path("/objects")
+            .reply(&filter(true))
+            .await;
+        assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, serde_json::json!("objects"));
+        });
+    }
+
+    /// Walks a "history" for `duration` while holding a [`HistoryWalkPermit`] and records the
+    /// highest number of walks that ran at the same time in `max_running`.
+    fn with_history_walk_permit_test_filter(
+        limits: HistoryWalkLimits,
+        duration: std::time::Duration,
+        max_running: Arc<std::sync::atomic::AtomicUsize>,
+    ) -> BoxedFilter<(impl Reply,)> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = Arc::new(AtomicUsize::new(0));
+        with_history_walk_permit(HistoryWalks::new(limits))
+            .and_then(move |permit: HistoryWalkPermit| {
+                let running = running.clone();
+                let max_running = max_running.clone();
+                async move {
+                    let _permit = permit;
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(duration).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, Rejection>(warp::reply::json(&"walked"))
+                }
+            })
+            .recover(super::error::recover)
+            .boxed()
+    }
+
+    #[tokio::test]
+    async fn with_history_walk_permit_limits_concurrency() {
+        let max_running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let api = with_history_walk_permit_test_filter(
+            HistoryWalkLimits {
+                max_concurrent: std::num::NonZeroUsize::new(2).unwrap(),
+                timeout: std::time::Duration::from_secs(10),
+            },
+            std::time::Duration::from_millis(20),
+            max_running.clone(),
+        );
+
+        let responses = futures::future::join_all(
+            (0..6).map(|_| warp::test::request().method("GET").path("/").reply(&api)),
+        )
+        .await;
+        for res in &responses {
+            assert_response(res, StatusCode::OK, |have| {
+                assert_eq!(have, serde_json::json!("walked"));
+            });
+        }
+        assert_eq!(max_running.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn with_history_walk_permit_times_out() {
+        let api = with_history_walk_permit_test_filter(
+            HistoryWalkLimits {
+                max_concurrent: std::num::NonZeroUsize::new(1).unwrap(),
+                timeout: std::time::Duration::from_millis(10),
+            },
+            std::time::Duration::from_millis(200),
+            Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        );
+
+        let (first, second) = futures::future::join(
+            warp::test::request().method("GET").path("/").reply(&api),
+            warp::test::request().method("GET").path("/").reply(&api),
+        )
+        .await;
+        assert_response(&first, StatusCode::OK, |_| {});
+        assert_response(&second, StatusCode::SERVICE_UNAVAILABLE, |have| {
+            assert_eq!(have["variant"], "TOO_MANY_HISTORY_WALKS");
+        });
+    }
+
+    #[test]
+    fn prefers_plain_text_negotiation() {
+        assert!(prefers_plain_text("text/plain"));
+        assert!(prefers_plain_text("text/plain; charset=utf-8"));
+        assert!(prefers_plain_text("application/json;q=0.5, text/plain"));
+        assert!(!prefers_plain_text("application/json"));
+        assert!(!prefers_plain_text("*/*"));
+        assert!(!prefers_plain_text("text/plain;q=0.5, application/json"));
+        assert!(!prefers_plain_text("text/plain, application/json"));
+    }
+
+    #[tokio::test]
+    async fn with_accept_plain_text_defaults_to_json() {
+        let filter = with_accept_plain_text();
+
+        let absent = warp::test::request()
+            .filter(&filter)
+            .await
+            .expect("filter rejected request");
+        assert!(!absent);
+
+        let json = warp::test::request()
+            .header("accept", "application/json")
+            .filter(&filter)
+            .await
+            .expect("filter rejected request");
+        assert!(!json);
+
+        let text = warp::test::request()
+            .header("accept", "text/plain")
+            .filter(&filter)
+            .await
+            .expect("filter rejected request");
+        assert!(text);
+    }
+
+    fn with_etag_test_filter() -> BoxedFilter<(impl Reply,)> {
+        with_if_none_match()
+            .map(|if_none_match: Option<String>| {
+                with_etag(
+                    warp::reply::json(&"content"),
+                    &etag(&[b"revision", b"path"]),
+                    if_none_match.as_deref(),
+                )
+            })
+            .boxed()
+    }
+
+    fn with_last_modified_test_filter() -> BoxedFilter<(impl Reply,)> {
+        with_if_modified_since()
+            .map(|if_modified_since: Option<headers::IfModifiedSince>| {
+                with_last_modified(
+                    warp::reply::json(&"content").into_response(),
+                    Some(git2::Time::new(1_600_000_000, 120)),
+                    if_modified_since.as_ref(),
+                )
+            })
+            .boxed()
+    }
+
+    #[tokio::test]
+    async fn with_last_modified_not_modified() {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/")
+            .reply(&with_last_modified_test_filter())
+            .await;
+
+        assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, serde_json::json!("content"))
+        });
+        assert_eq!(
+            res.headers()
+                .get(warp::http::header::LAST_MODIFIED)
+                .unwrap(),
+            "Sun, 13 Sep 2020 12:26:40 GMT"
+        );
+
+        for since in [
+            "Sun, 13 Sep 2020 12:26:40 GMT",
+            "Fri, 01 Jan 2100 00:00:00 GMT",
+        ] {
+            let res = warp::test::request()
+                .method("GET")
+                .path("/")
+                .header("if-modified-since", since)
+                .reply(&with_last_modified_test_filter())
+                .await;
+
+            assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+            assert!(res.body().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn with_last_modified_modified() {
+        for since in ["Sun, 13 Sep 2020 12:26:39 GMT", "not a date"] {
+            let res = warp::test::request()
+                .method("GET")
+                .path("/")
+                .header("if-modified-since", since)
+                .reply(&with_last_modified_test_filter())
+                .await;
+
+            assert_response(&res, StatusCode::OK, |have| {
+                assert_eq!(have, serde_json::json!("content"))
+            });
+        }
+    }
+
+    #[test]
+    fn etag_is_stable() {
+        assert_eq!(
+            etag(&[b"revision", b"path"]),
+            "\"1b82140e799f571fe4733c716331454aed3c30f4f392338decf430e3a22eb4ec\""
+        );
+        assert_ne!(etag(&[b"revision", b"path"]), etag(&[b"revisionpath"]));
+    }
+
+    #[tokio::test]
+    async fn with_etag_not_modified() {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/")
+            .reply(&with_etag_test_filter())
+            .await;
+
+        assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, serde_json::json!("content"))
+        });
+        let etag = res
+            .headers()
+            .get(warp::http::header::ETAG)
+            .expect("missing ETag header")
+            .clone();
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/")
+            .header("if-none-match", etag.clone())
+            .reply(&with_etag_test_filter())
+            .await;
+
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(warp::http::header::ETAG), Some(&etag));
+        assert!(res.body().is_empty());
+    }
+
+    #[tokio::test]
+    async fn with_etag_modified() {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/")
+            .header("if-none-match", "\"stale\"")
+            .reply(&with_etag_test_filter())
+            .await;
+
+        assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, serde_json::json!("content"))
+        });
+    }
 }
@@ -48,6 +48,17 @@ impl Response {
 
 impl warp::reject::Reject for Response {}
 
+impl warp::Reply for Response {
+    fn into_response(self) -> warp::reply::Response {
+        let body = serde_json::json!({
+            "message": self.message,
+            "variant": self.variant,
+        });
+
+        warp::reply::with_status(warp::reply::json(&body), self.status_code).into_response()
+    }
+}
+
 pub async fn recover(err: warp::Rejection) -> Result<impl warp::Reply, Infallible> {
     let error_response = if err.is_not_found() {
         Response {
@@ -55,6 +66,18 @@ pub async fn recover(err: warp::Rejection) -> Result<impl warp::Reply, Infallibl
             variant: "NOT_FOUND",
             message: "Resource not found".to_string(),
         }
+    } else if err.find::<warp::reject::PayloadTooLarge>().is_some() {
+        Response {
+            status_code: StatusCode::PAYLOAD_TOO_LARGE,
+            variant: "PAYLOAD_TOO_LARGE",
+            message: "Request body is too large".to_string(),
+        }
+    } else if err.find::<warp::reject::LengthRequired>().is_some() {
+        Response {
+            status_code: StatusCode::LENGTH_REQUIRED,
+            variant: "LENGTH_REQUIRED",
+            message: "Request is missing the Content-Length header".to_string(),
+        }
     } else if let Some(err) = err.find::<error::Error>() {
         Response::from(err)
     } else if let Some(err) = err.find::<Response>() {
@@ -71,20 +94,17 @@ pub async fn recover(err: warp::Rejection) -> Result<impl warp::Reply, Infallibl
         tracing::error!(message = %error_response.message, variant = %error_response.variant, ?err, "internal server error");
     }
 
-    let body = serde_json::json!({
-        "message": error_response.message,
-        "variant": error_response.variant,
-    });
-
-    Ok(warp::reply::with_status(
-        warp::reply::json(&body),
-        error_response.status_code,
-    ))
+    Ok(error_response)
 }
 
 impl From<&radicle_source::error::Error> for Response {
     fn from(err: &radicle_source::error::Error) -> Self {
         let (status_code, variant) = match err {
+            radicle_source::error::Error::Git(radicle_source::surf::git::error::Error::Git(
+                git_error,
+            )) if git_error.code() == git2::ErrorCode::Ambiguous => {
+                (StatusCode::BAD_REQUEST, "AMBIGUOUS_REVISION")
+            },
             radicle_source::error::Error::Git(_) => (StatusCode::BAD_REQUEST, "GIT_ERROR"),
             radicle_source::error::Error::NoBranches => (StatusCode::BAD_REQUEST, "NO_BRANCHES"),
             radicle_source::error::Error::PathNotFound(_) => (StatusCode::NOT_FOUND, "NOT_FOUND"),
@@ -107,6 +127,26 @@ impl From<&crate::daemon::state::Error> for Response {
                     "PATH_EXISTS",
                     checkout_error.to_string(),
                 ),
+                crate::daemon::project::checkout::Error::InvalidDirectoryName(_) => (
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_DIRECTORY_NAME",
+                    checkout_error.to_string(),
+                ),
+                crate::daemon::project::checkout::Error::BranchNotFound(_) => (
+                    StatusCode::NOT_FOUND,
+                    "BRANCH_NOT_FOUND",
+                    checkout_error.to_string(),
+                ),
+                crate::daemon::project::checkout::Error::IncludeNotWritable(_) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "INCLUDE_NOT_WRITABLE",
+                    checkout_error.to_string(),
+                ),
+                crate::daemon::project::checkout::Error::PostCheckout { .. } => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "POST_CHECKOUT_FAILED",
+                    checkout_error.to_string(),
+                ),
                 crate::daemon::project::checkout::Error::Git(git_error) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "GIT_ERROR",
@@ -197,9 +237,20 @@ impl From<&crate::daemon::state::Error> for Response {
                 "NOT_FOUND",
                 "entity not found".to_string(),
             ),
+            crate::daemon::state::Error::ProjectVerification { .. } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "PROJECT_VERIFICATION_FAILED",
+                err.to_string(),
+            ),
             crate::daemon::state::Error::IdentityExists(_) => {
                 (StatusCode::CONFLICT, "IDENTITY_EXISTS", err.to_string())
             },
+            crate::daemon::state::Error::Timeout(_) => {
+                (StatusCode::GATEWAY_TIMEOUT, "TIMEOUT", err.to_string())
+            },
+            crate::daemon::state::Error::ForkOwnProject(_) => {
+                (StatusCode::BAD_REQUEST, "FORK_OWN_PROJECT", err.to_string())
+            },
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "INTERNAL_SERVER_ERROR",
@@ -229,11 +280,71 @@ impl From<&error::Error> for Response {
                 variant: "PROJECT_NOT_FOUND",
                 message: "Project not found".to_string(),
             },
+            error::Error::InvalidId { .. } => Self {
+                status_code: StatusCode::BAD_REQUEST,
+                variant: "INVALID_ID",
+                message: err.to_string(),
+            },
+            error::Error::IdentityNotFound(_) => Self {
+                status_code: StatusCode::NOT_FOUND,
+                variant: "IDENTITY_NOT_FOUND",
+                message: err.to_string(),
+            },
+            error::Error::IdentifierNotFound(_) => Self {
+                status_code: StatusCode::NOT_FOUND,
+                variant: "IDENTIFIER_NOT_FOUND",
+                message: err.to_string(),
+            },
+            error::Error::NotAPerson(_) => Self {
+                status_code: StatusCode::BAD_REQUEST,
+                variant: "NOT_A_PERSON",
+                message: err.to_string(),
+            },
+            error::Error::InvalidProjectName(_) => Self {
+                status_code: StatusCode::BAD_REQUEST,
+                variant: "INVALID_PROJECT_NAME",
+                message: err.to_string(),
+            },
+            error::Error::RevisionNotFound(_) => Self {
+                status_code: StatusCode::NOT_FOUND,
+                variant: "REVISION_NOT_FOUND",
+                message: err.to_string(),
+            },
+            error::Error::NotADirectory(_) => Self {
+                status_code: StatusCode::BAD_REQUEST,
+                variant: "NOT_A_DIRECTORY",
+                message: err.to_string(),
+            },
+            error::Error::UnrelatedHistories { .. } => Self {
+                status_code: StatusCode::CONFLICT,
+                variant: "UNRELATED_HISTORIES",
+                message: err.to_string(),
+            },
             error::Error::MissingDefaultBranch => Self {
                 status_code: StatusCode::INTERNAL_SERVER_ERROR,
                 variant: "MISSING_DEFAULT_BRANCH",
                 message: "Default branch for project is missing".to_string(),
             },
+            error::Error::EmptyDefaultBranch(_) => Self {
+                status_code: StatusCode::CONFLICT,
+                variant: "EMPTY_DEFAULT_BRANCH",
+                message: err.to_string(),
+            },
+            error::Error::IdempotencyKeyMismatch(_) => Self {
+                status_code: StatusCode::UNPROCESSABLE_ENTITY,
+                variant: "IDEMPOTENCY_KEY_MISMATCH",
+                message: err.to_string(),
+            },
+            error::Error::IdempotencyKeyPending(_) => Self {
+                status_code: StatusCode::CONFLICT,
+                variant: "IDEMPOTENCY_KEY_PENDING",
+                message: err.to_string(),
+            },
+            error::Error::Cancelled => Self {
+                status_code: StatusCode::SERVICE_UNAVAILABLE,
+                variant: "CANCELLED",
+                message: err.to_string(),
+            },
             error::Error::OpenReadOnlyGitStorage(_)
             | error::Error::Peer(_)
             | error::Error::Io(_)
@@ -19,20 +19,34 @@ use crate::{context, http};
 mod request;
 
 /// Combination of all routes.
-pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
-    checkout_filter(ctx.clone())
-        .or(create_filter(ctx.clone()))
+pub fn filters(
+    ctx: context::Context,
+    body_limits: http::BodyLimits,
+    post_checkout: Option<Vec<String>>,
+) -> BoxedFilter<(impl Reply,)> {
+    archive_filter(ctx.clone())
+        .or(bundle_filter(ctx.clone()))
+        .or(checkout_filter(ctx.clone(), body_limits, post_checkout))
+        .or(checkout_validate_filter(ctx.clone(), body_limits))
+        .or(create_filter(ctx.clone(), body_limits))
         .or(failed_filter(ctx.clone()))
-        .or(get_filter(ctx.clone()))
+        .or(fork_filter(ctx.clone()))
+        .or(import_filter(ctx.clone(), body_limits))
+        .or(owner_contributed_filter(ctx.clone()))
         // Sprinkle random `.boxed()` in here so warp doesn’t generate stack overflows
         .boxed()
-        .or(owner_contributed_filter(ctx.clone()))
         .or(owner_tracked_filter(ctx.clone()))
+        .or(owned_branches_filter(ctx.clone()))
+        .or(owned_filter(ctx.clone()))
+        // Must come after the other single segment routes since it accepts any string.
+        .or(get_filter(ctx.clone()))
+        .or(metadata_filter(ctx.clone()))
         .or(peers_filter(ctx.clone()))
         .or(path("requests").and(request::filters(ctx.clone())))
         // Sprinkle random `.boxed()` in here so warp doesn’t generate stack overflows
         .boxed()
         .or(track_filter(ctx.clone()))
+        .or(track_all_filter(ctx.clone(), body_limits))
         .or(patches_filter(ctx.clone()))
         // Sprinkle random `.boxed()` in here so warp doesn’t generate stack overflows
         .boxed()
@@ -41,31 +55,118 @@ pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+/// `GET /<urn>/archive?revision=<revision>&format=<targz|zip>`
+///
+/// Streams the tree of `revision`, or of the default branch if absent, as an archive.
+fn archive_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<Urn>()
+        .and(path("archive"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<ArchiveQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::archive)
+}
+
+/// `GET /<urn>/bundle`
+///
+/// Streams the branches and tags of the project as a git bundle.
+fn bundle_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<Urn>()
+        .and(path("bundle"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::bundle)
+}
+
 /// `POST /<urn>/checkout`
+///
+/// Runs `post_checkout` in the working copy once it was created.
 fn checkout_filter(
     ctx: context::Context,
+    body_limits: http::BodyLimits,
+    post_checkout: Option<Vec<String>>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     path::param::<Urn>()
         .and(path("checkout"))
         .and(path::end())
         .and(warp::post())
         .and(http::with_context_unsealed(ctx))
-        .and(warp::body::json())
+        .and(http::with_json_body(body_limits.json))
+        .and(warp::any().map(move || post_checkout.clone()))
         .and_then(handler::checkout)
 }
 
+/// `POST /<urn>/checkout/validate`
+///
+/// Checks the input of a checkout without creating the working copy.
+fn checkout_validate_filter(
+    ctx: context::Context,
+    body_limits: http::BodyLimits,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<Urn>()
+        .and(path("checkout"))
+        .and(path("validate"))
+        .and(path::end())
+        .and(warp::post())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_json_body(body_limits.json))
+        .and_then(handler::checkout_validate)
+}
+
 /// `POST /`
+///
+/// Requests carrying an `Idempotency-Key` header that was already used return the project
+/// created by the first request. Reusing a key for a different body is rejected.
 fn create_filter(
     ctx: context::Context,
+    body_limits: http::BodyLimits,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     path::end()
         .and(warp::post())
         .and(http::with_context_unsealed(ctx.clone()))
         .and(http::with_owner_guard(ctx))
-        .and(warp::body::json())
+        .and(warp::header::optional::<String>("idempotency-key"))
+        .and(http::with_json_body(body_limits.json))
         .and_then(handler::create)
 }
 
+/// `POST /<urn>/fork`
+fn fork_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<Urn>()
+        .and(path("fork"))
+        .and(path::end())
+        .and(warp::post())
+        .and(http::with_context_unsealed(ctx.clone()))
+        .and(http::with_owner_guard(ctx))
+        .and_then(handler::fork)
+}
+
+/// `POST /import?name=<name>&defaultBranch=<branch>&description=<description>`
+///
+/// Creates a project from the git bundle sent as the request body. No working copy is kept, the
+/// project has to be checked out to work on it.
+fn import_filter(
+    ctx: context::Context,
+    body_limits: http::BodyLimits,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("import")
+        .and(path::end())
+        .and(warp::post())
+        .and(http::with_context_unsealed(ctx.clone()))
+        .and(http::with_owner_guard(ctx))
+        .and(warp::query::<ImportQuery>())
+        .and(http::with_limited_body(body_limits.bundle))
+        .and_then(handler::import)
+}
+
 /// `GET /failed`
 fn failed_filter(
     ctx: context::Context,
@@ -77,17 +178,34 @@ fn failed_filter(
         .and_then(handler::list_failed)
 }
 
-/// `GET /<urn>`
+/// `GET /<id>?verify=<bool>`
+///
+/// If `verify` is set, the identity history of the project is verified first.
 fn get_filter(
     ctx: context::Context,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    path::param::<Urn>()
+    path::param::<String>()
         .and(path::end())
         .and(warp::get())
+        .and(warp::query::<GetQuery>())
         .and(http::with_context_unsealed(ctx))
         .and_then(handler::get)
 }
 
+/// `GET /<urn>/metadata`
+///
+/// Cheap counterpart to `GET /<id>` that doesn't compute the project stats.
+fn metadata_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<Urn>()
+        .and(path("metadata"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::get_metadata)
+}
+
 /// `GET /contributed`
 fn owner_contributed_filter(
     ctx: context::Context,
@@ -110,6 +228,35 @@ fn owner_tracked_filter(
         .and_then(handler::list_owner_tracked)
 }
 
+/// `GET /owned?owner=<owner_urn>&offset=<offset>&limit=<limit>`
+///
+/// Lists the projects owned by `owner`, or by the default owner if the parameter is absent. The
+/// total number of projects is returned in the `X-Total-Count` header.
+fn owned_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("owned")
+        .and(path::end())
+        .and(warp::get())
+        .and(warp::query::<OwnedQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::list_owned)
+}
+
+/// `GET /owned/branches`
+///
+/// Lists the local branches of every project owned by the default owner.
+fn owned_branches_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("owned")
+        .and(path("branches"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::list_owned_branches)
+}
+
 /// `GET /<urn>/peers`
 fn peers_filter(
     ctx: context::Context,
@@ -135,6 +282,20 @@ fn track_filter(
         .and_then(handler::track)
 }
 
+/// `PUT /<urn>/track`
+fn track_all_filter(
+    ctx: context::Context,
+    body_limits: http::BodyLimits,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<Urn>()
+        .and(path("track"))
+        .and(path::end())
+        .and(warp::put())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_json_body(body_limits.json))
+        .and_then(handler::track_all)
+}
+
 /// `PUT /<urn>/untrack/<peer_id>`
 fn untrack_filter(
     ctx: context::Context,
@@ -177,7 +338,7 @@ fn patches_filter(
 /// Project handlers to implement conversion and translation between core domain and http request
 /// fullfilment.
 mod handler {
-    use std::convert::TryFrom;
+    use std::{convert::TryFrom, time::SystemTime};
 
     use warp::{http::StatusCode, reply, Rejection, Reply};
 
@@ -186,29 +347,203 @@ mod handler {
 
     use crate::{browser, context, error::Error, http, patch, project};
 
+    /// Export the tree of a revision of a [`project::Project`] as an archive, see
+    /// [`project::export_archive`].
+    pub async fn archive(
+        urn: Urn,
+        super::ArchiveQuery { revision, format }: super::ArchiveQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let metadata = project::get_metadata(&ctx.peer, urn).await?;
+        let rev = revision.unwrap_or_else(|| metadata.default_branch.clone());
+        let oid = browser::using(&ctx.peer, metadata.default_branch, |browser| {
+            crate::source::resolve_rev(browser, &rev)
+        })
+        .map_err(|err| crate::source::revision_not_found(err, Some(rev.clone())))?;
+
+        let name = format!("{}-{}", metadata.name, rev.replace('/', "-"));
+        let dir = tempfile::tempdir().map_err(Error::from)?;
+        let path = dir.path().join(format!("project.{}", format.extension()));
+        project::export_archive(&ctx.peer, oid, &name, format, &path).await?;
+
+        Ok(reply::with_header(
+            reply::with_header(
+                warp::reply::Response::new(stream_file(dir, &path)?),
+                warp::http::header::CONTENT_TYPE,
+                format.content_type(),
+            ),
+            warp::http::header::CONTENT_DISPOSITION,
+            http::attachment(&format!("{}.{}", name, format.extension())),
+        ))
+    }
+
+    /// Export a [`project::Project`] as a git bundle, see [`project::export_bundle`].
+    pub async fn bundle(urn: Urn, ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        ensure_project_exists(&ctx, &urn).await?;
+        let dir = tempfile::tempdir().map_err(Error::from)?;
+        let path = dir.path().join("project.bundle");
+        project::export_bundle(&ctx.peer, urn.clone(), &path).await?;
+
+        Ok(reply::with_header(
+            reply::with_header(
+                warp::reply::Response::new(stream_file(dir, &path)?),
+                warp::http::header::CONTENT_TYPE,
+                "application/x-git-bundle",
+            ),
+            warp::http::header::CONTENT_DISPOSITION,
+            http::attachment(&format!("{}.bundle", urn.encode_id())),
+        ))
+    }
+
+    /// Streams the file at `path` in chunks. The file is removed together with `dir` once it
+    /// has been sent.
+    fn stream_file(
+        dir: tempfile::TempDir,
+        path: &std::path::Path,
+    ) -> Result<warp::hyper::Body, Error> {
+        let mut file = std::fs::File::open(path)?;
+
+        let (chunks_tx, mut chunks_rx) =
+            tokio::sync::mpsc::channel::<Result<Vec<u8>, std::io::Error>>(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            let _dir = dir;
+            loop {
+                let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+                let chunk = match std::io::Read::read(&mut file, &mut chunk) {
+                    Ok(0) => break,
+                    Ok(len) => {
+                        chunk.truncate(len);
+                        Ok(chunk)
+                    },
+                    Err(err) => Err(err),
+                };
+                let failed = chunk.is_err();
+                if chunks_tx.blocking_send(chunk).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(warp::hyper::Body::wrap_stream(async_stream::stream! {
+            while let Some(chunk) = chunks_rx.recv().await {
+                yield chunk;
+            }
+        }))
+    }
+
+    /// Number of chunks buffered by [`stream_file`] before reading the file waits for the client.
+    const STREAM_BUFFER: usize = 16;
+
+    /// Size of the chunks [`stream_file`] sends files in.
+    const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
     /// Checkout a [`project::Project`]'s source code.
     pub async fn checkout(
         urn: Urn,
         ctx: context::Unsealed,
-        super::CheckoutInput { path, peer_id }: super::CheckoutInput,
+        super::CheckoutInput {
+            path,
+            peer_id,
+            layout,
+        }: super::CheckoutInput,
+        post_checkout: Option<Vec<String>>,
     ) -> Result<impl Reply, Rejection> {
         let peer_id = http::guard_self_peer_id(&ctx.peer, peer_id);
-        let path = crate::daemon::state::checkout(ctx.peer.librad_peer(), urn, peer_id, path)
-            .await
-            .map_err(Error::from)?;
+        // The command is configured on the command line, API clients can't choose it.
+        let path = crate::daemon::state::checkout(
+            ctx.peer.librad_peer(),
+            urn,
+            peer_id,
+            path,
+            layout,
+            post_checkout,
+        )
+        .await
+        .map_err(Error::from)?;
         Ok(reply::with_status(reply::json(&path), StatusCode::CREATED))
     }
 
+    /// Check whether a [`project::Project`] can be checked out with the given input. Responds with
+    /// the path the working copy would be created at.
+    pub async fn checkout_validate(
+        urn: Urn,
+        ctx: context::Unsealed,
+        super::CheckoutInput {
+            path,
+            peer_id,
+            layout,
+        }: super::CheckoutInput,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = http::guard_self_peer_id(&ctx.peer, peer_id);
+        let path = crate::daemon::state::validate_checkout(
+            ctx.peer.librad_peer(),
+            urn,
+            peer_id,
+            path,
+            layout,
+        )
+        .await
+        .map_err(Error::from)?;
+        Ok(reply::json(&path))
+    }
+
     /// Create a new [`project::Project`].
     pub async fn create(
         ctx: context::Unsealed,
         owner: crate::daemon::LocalIdentity,
+        idempotency_key: Option<String>,
         input: crate::daemon::project::Create,
     ) -> Result<impl Reply, Rejection> {
-        let project = crate::daemon::state::init_project(ctx.peer.librad_peer(), &owner, input)
-            .await
+        use project::idempotency::Reservation;
+
+        let request_digest = project::idempotency::request_digest(&input);
+        if let Some(key) = &idempotency_key {
+            let reservation = project::idempotency::reserve(
+                &ctx.rest.store,
+                key,
+                &request_digest,
+                SystemTime::now(),
+            )
             .map_err(Error::from)?;
+            match reservation {
+                Reservation::Reserved => {},
+                Reservation::Created(urn) => {
+                    let project = project::get(&ctx.peer, urn, None).await?;
+                    return Ok(reply::with_status(
+                        reply::json(&project),
+                        StatusCode::CREATED,
+                    ));
+                },
+                Reservation::Pending => {
+                    return Err(Error::IdempotencyKeyPending(key.clone()).into())
+                },
+                Reservation::Mismatch => {
+                    return Err(Error::IdempotencyKeyMismatch(key.clone()).into())
+                },
+            }
+        }
+
+        let project =
+            match crate::daemon::state::init_project(ctx.peer.librad_peer(), &owner, input).await {
+                Ok(project) => project,
+                Err(err) => {
+                    if let Some(key) = &idempotency_key {
+                        project::idempotency::release(&ctx.rest.store, key).map_err(Error::from)?;
+                    }
+                    return Err(Error::from(err).into());
+                },
+            };
         let urn = project.urn();
+        if let Some(key) = &idempotency_key {
+            project::idempotency::complete(
+                &ctx.rest.store,
+                key,
+                &request_digest,
+                urn.clone(),
+                SystemTime::now(),
+            )
+            .map_err(Error::from)?;
+        }
 
         let branch = crate::daemon::state::get_branch(
             ctx.peer.librad_peer(),
@@ -222,7 +557,64 @@ mod handler {
             browser.get_stats().map_err(radicle_source::Error::from)
         })
         .map_err(Error::from)?;
-        let project = project::Project::try_from((project, stats, None))?;
+        let project =
+            project::Project::try_from((project, crate::source::Stats::from(stats), None))?;
+
+        Ok(reply::with_status(
+            reply::json(&project),
+            StatusCode::CREATED,
+        ))
+    }
+
+    /// Fork the project `urn` so that the local owner becomes its delegate.
+    pub async fn fork(
+        urn: Urn,
+        ctx: context::Unsealed,
+        owner: crate::daemon::LocalIdentity,
+    ) -> Result<impl Reply, Rejection> {
+        let fork = crate::daemon::state::fork_project(ctx.peer.librad_peer(), &owner, urn)
+            .await
+            .map_err(Error::from)?;
+        let project = project::get(&ctx.peer, fork.urn(), None).await?;
+
+        Ok(reply::with_status(
+            reply::json(&project),
+            StatusCode::CREATED,
+        ))
+    }
+
+    /// Create a [`project::Project`] from a git bundle, see [`project::import_bundle`].
+    pub async fn import(
+        ctx: context::Unsealed,
+        owner: crate::daemon::LocalIdentity,
+        super::ImportQuery {
+            name,
+            description,
+            default_branch,
+        }: super::ImportQuery,
+        mut bundle: http::BodyStream,
+    ) -> Result<impl Reply, Rejection> {
+        use futures::TryStreamExt as _;
+        use tokio::io::AsyncWriteExt as _;
+
+        let dir = tempfile::tempdir().map_err(Error::from)?;
+        let path = dir.path().join("import.bundle");
+        let mut file = tokio::fs::File::create(&path).await.map_err(Error::from)?;
+        while let Some(chunk) = bundle.try_next().await? {
+            file.write_all(&chunk).await.map_err(Error::from)?;
+        }
+        file.flush().await.map_err(Error::from)?;
+
+        let imported = project::import_bundle(
+            &ctx.peer,
+            &owner,
+            &path,
+            &name,
+            description.unwrap_or_default(),
+            default_branch,
+        )
+        .await?;
+        let project = project::get(&ctx.peer, imported.urn(), None).await?;
 
         Ok(reply::with_status(
             reply::json(&project),
@@ -230,12 +622,28 @@ mod handler {
         ))
     }
 
-    /// Get the [`project::Project`] for the given `id`.
-    pub async fn get(urn: Urn, ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+    /// Get the [`project::Project`] for the given `id`. The `id` may be a bare project URN or
+    /// the shareable `%<urn>` form.
+    pub async fn get(
+        id: String,
+        super::GetQuery { verify }: super::GetQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let urn = project::parse_shareable_id(&id)?;
+        if verify {
+            crate::daemon::state::verify_project(ctx.peer.librad_peer(), urn.clone())
+                .await
+                .map_err(Error::from)?;
+        }
         let seed = ctx.git_fetch.get_seed(urn.id);
         Ok(reply::json(&project::get(&ctx.peer, urn, seed).await?))
     }
 
+    /// Get the [`project::Metadata`] of the project `urn`.
+    pub async fn get_metadata(urn: Urn, ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&project::get_metadata(&ctx.peer, urn).await?))
+    }
+
     /// List all failed projects.
     pub async fn list_failed(ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
         let projects = project::Projects::list(&ctx.peer).await?;
@@ -257,6 +665,41 @@ mod handler {
         Ok(reply::json(&projects))
     }
 
+    /// List all projects owned by the given owner, defaulting to the current user.
+    ///
+    /// See [`project::list_owned`] for more information.
+    pub async fn list_owned(
+        super::OwnedQuery {
+            owner,
+            offset,
+            limit,
+        }: super::OwnedQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let (projects, total) = project::list_owned(
+            &ctx.peer,
+            owner,
+            offset.unwrap_or_default(),
+            limit.unwrap_or(usize::MAX),
+        )
+        .await?;
+
+        Ok(reply::with_header(
+            reply::json(&projects),
+            "x-total-count",
+            total.to_string(),
+        ))
+    }
+
+    /// List the local branches of all projects owned by the current user.
+    ///
+    /// See [`project::list_owned_branches`] for more information.
+    pub async fn list_owned_branches(ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        let branches = project::list_owned_branches(&ctx.peer).await?;
+
+        Ok(reply::json(&branches))
+    }
+
     /// This lists all the projects for a given `user`. This `user` should not be your particular
     /// `user` (i.e. the "default user"), but rather should be another user that you are tracking.
     ///
@@ -293,6 +736,28 @@ mod handler {
         Ok(reply::json(&true))
     }
 
+    /// Track all given peers for the provided project and report the outcome for each of them.
+    pub async fn track_all(
+        urn: Urn,
+        ctx: context::Unsealed,
+        super::TrackAllInput { peer_ids }: super::TrackAllInput,
+    ) -> Result<impl Reply, Rejection> {
+        let outcomes =
+            crate::daemon::state::track_all(ctx.peer.librad_peer(), urn.clone(), peer_ids)
+                .await
+                .map_err(Error::from)?;
+        ctx.git_fetch.add(urn.id).await;
+
+        let outcomes = outcomes
+            .into_iter()
+            .map(|(peer_id, result)| super::TrackOutcome {
+                peer_id,
+                error: result.err().map(|err| err.to_string()),
+            })
+            .collect::<Vec<_>>();
+        Ok(reply::json(&outcomes))
+    }
+
     /// Untrack the peer for the provided project.
     pub async fn untrack(
         urn: Urn,
@@ -310,12 +775,23 @@ mod handler {
         project_urn: Urn,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
+        ensure_project_exists(&ctx, &project_urn).await?;
         let patches = patch::list(&ctx.peer, project_urn)
             .await
             .map_err(Error::from)?;
 
         Ok(reply::json(&patches))
     }
+
+    /// Fails with [`Error::ProjectNotFound`] if there is no project for `urn`, so handlers can
+    /// respond with `404` before doing any expensive work.
+    async fn ensure_project_exists(ctx: &context::Unsealed, urn: &Urn) -> Result<(), Error> {
+        if crate::daemon::state::project_exists(ctx.peer.librad_peer(), urn.clone()).await? {
+            Ok(())
+        } else {
+            Err(Error::ProjectNotFound)
+        }
+    }
 }
 
 /// Bundled input data for project creation.
@@ -337,6 +813,28 @@ pub struct CheckoutInput {
     path: PathBuf,
     /// Which peer are we checking out from. If it's `None`, we're checking out our own project.
     peer_id: Option<PeerId>,
+    /// How the directory of the working copy is derived from `path`. Defaults to placing it in
+    /// `path` directly.
+    #[serde(default)]
+    layout: crate::daemon::project::checkout::PathLayout,
+}
+
+/// Bundled input data for tracking multiple peers.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackAllInput {
+    /// Peers to track.
+    peer_ids: Vec<PeerId>,
+}
+
+/// The outcome of tracking a single peer in [`handler::track_all`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackOutcome {
+    /// The peer that was tracked.
+    peer_id: PeerId,
+    /// Why tracking the peer failed, if it did.
+    error: Option<String>,
 }
 
 /// User provided metadata for project manipulation.
@@ -350,3 +848,44 @@ pub struct MetadataInput {
     /// Configured default branch.
     default_branch: String,
 }
+
+/// Query params for [`handler::list_owned`].
+#[derive(Deserialize)]
+pub struct OwnedQuery {
+    /// The owner whose projects are listed. Defaults to the current user.
+    owner: Option<Urn>,
+    /// Number of projects to skip. Defaults to `0`.
+    offset: Option<usize>,
+    /// Maximum number of projects to return. Defaults to all projects.
+    limit: Option<usize>,
+}
+
+/// Query params for [`handler::archive`].
+#[derive(Deserialize)]
+pub struct ArchiveQuery {
+    /// The branch, tag or commit to archive. Defaults to the default branch of the project.
+    revision: Option<String>,
+    /// The format of the archive.
+    #[serde(default)]
+    format: crate::project::ArchiveFormat,
+}
+
+/// Query params for [`handler::get`].
+#[derive(Deserialize)]
+pub struct GetQuery {
+    /// Verify the identity history of the project before returning it.
+    #[serde(default)]
+    verify: bool,
+}
+
+/// Query params for [`handler::import`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQuery {
+    /// Name of the imported project.
+    name: String,
+    /// Long form outline.
+    description: Option<String>,
+    /// Branch of the bundle that becomes the default branch of the project.
+    default_branch: librad::git_ext::OneLevel,
+}
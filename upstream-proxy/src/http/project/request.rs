@@ -14,7 +14,9 @@ use crate::{context, http};
 
 /// Combination of all routes.
 pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
-    cancel_filter(ctx.clone())
+    metrics_filter(ctx.clone())
+        .or(history_filter(ctx.clone()))
+        .or(cancel_filter(ctx.clone()))
         .or(create_filter(ctx.clone()))
         .or(list_filter(ctx))
         .boxed()
@@ -42,6 +44,28 @@ fn create_filter(
         .and_then(handler::create)
 }
 
+/// `GET /metrics`
+fn metrics_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("metrics")
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::metrics)
+}
+
+/// `GET /history`
+fn history_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("history")
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::history)
+}
+
 /// `GET /`
 fn list_filter(
     ctx: context::Context,
@@ -95,4 +119,23 @@ mod handler {
 
         Ok(reply::json(&requests))
     }
+
+    /// Get the number of project requests in each state.
+    pub async fn metrics(mut ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        let metrics = ctx
+            .peer
+            .daemon_control()
+            .get_project_request_metrics()
+            .await;
+
+        Ok(reply::json(&metrics))
+    }
+
+    /// List the outcomes of all finished project requests, oldest first.
+    pub async fn history(ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        let history = crate::daemon::peer::request_history::list(&ctx.rest.store)
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&history))
+    }
 }
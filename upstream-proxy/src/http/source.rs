@@ -11,23 +11,46 @@ use warp::{filters::BoxedFilter, path, Filter, Rejection, Reply};
 
 use link_crypto::PeerId;
 use link_identities::git::Urn;
-use radicle_git_ext::Oid;
 
 use crate::{context, http};
 
 /// Combination of all source filters.
-pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
+pub fn filters(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+    debug_endpoints: bool,
+) -> BoxedFilter<(impl Reply,)> {
     blob_filter(ctx.clone())
+        .or(branch_exists_filter(ctx.clone()))
         .or(branches_filter(ctx.clone()))
         .or(commit_filter(ctx.clone()))
-        .or(commits_filter(ctx.clone()))
+        .or(commits_search_filter(ctx.clone(), walks.clone()))
+        .or(commits_page_filter(ctx.clone(), walks.clone()))
+        .or(commits_filter(ctx.clone(), walks.clone()))
+        .or(contributors_filter(ctx.clone(), walks.clone()))
+        .or(graph_filter(ctx.clone(), walks.clone()))
+        .or(languages_filter(ctx.clone(), walks.clone()))
+        .or(merge_preview_filter(ctx.clone(), walks.clone()))
+        .or(objects_filter(ctx.clone(), walks.clone(), debug_endpoints))
         .or(local_state_filter())
+        .or(local_blob_filter())
+        .or(local_tree_filter())
+        .or(local_diff_filter())
+        .or(readme_filter(ctx.clone()))
+        .or(rev_parse_filter(ctx.clone()))
+        .or(source_config_filter(ctx.clone()))
+        .or(stats_filter(ctx.clone(), walks))
         .or(tags_filter(ctx.clone()))
+        .or(tree_recursive_filter(ctx.clone()))
+        .or(tree_summary_filter(ctx.clone()))
         .or(tree_filter(ctx))
         .boxed()
 }
 
-/// `GET /blob/<project_urn>?revision=<revision>&path=<path>`
+/// `GET /blob/<project_urn>?revision=<revision>&path=<path>&startLine=<line>&endLine=<line>`
+///
+/// Responds with the bare content of text blobs if the request sets `Accept: text/plain`. All
+/// responses carry `Vary: Accept` as their representation depends on it.
 fn blob_filter(
     ctx: context::Context,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
@@ -35,8 +58,26 @@ fn blob_filter(
         .and(path::param::<Urn>())
         .and(path::end())
         .and(http::with_qs::<BlobQuery>())
+        .and(http::with_if_none_match())
+        .and(http::with_if_modified_since())
+        .and(http::with_accept_plain_text())
         .and(http::with_context_unsealed(ctx))
         .and_then(handler::blob)
+        .map(|reply| warp::reply::with_header(reply, warp::http::header::VARY, "Accept"))
+}
+
+/// `HEAD /branches/<project_urn>/<branch>`
+///
+/// Responds with `200` if the local branch exists and `404` otherwise.
+fn branch_exists_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("branches")
+        .and(path::param::<Urn>())
+        .and(path::tail())
+        .and(warp::head())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::branch_exists)
 }
 
 /// `GET /branches/<project_urn>?peerId=<peer_id>`
@@ -53,30 +94,151 @@ fn branches_filter(
 }
 
 /// `GET /commit/<project_urn>/<sha1>`
+///
+/// `sha1` may be abbreviated.
 fn commit_filter(
     ctx: context::Context,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     path("commit")
         .and(path::param::<Urn>())
-        .and(path::param::<Oid>())
+        .and(path::param::<String>())
         .and(path::end())
         .and(warp::get())
+        .and(http::with_if_none_match())
+        .and(http::with_if_modified_since())
         .and(http::with_context_unsealed(ctx))
         .and_then(handler::commit)
 }
 
-/// `GET /commits/<project_urn>?revision=<revision>`
+/// `GET /commits/<project_urn>?revision=<revision>&headersOnly=<bool>&page=<page>&
+/// perPage=<per_page>&mailmap=<bool>&summaryMaxLen=<len>&descriptionMaxLen=<len>&since=<time>&
+/// until=<time>`
 fn commits_filter(
     ctx: context::Context,
+    walks: http::HistoryWalks,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     path("commits")
         .and(warp::get())
         .and(http::with_context_unsealed(ctx))
         .and(path::param::<Urn>())
         .and(http::with_qs::<CommitsQuery>())
+        .and(http::with_history_walk_permit(walks))
         .and_then(handler::commits)
 }
 
+/// `GET /commits/<project_urn>/search?query=<query>&field=<field>&limit=<limit>`
+fn commits_search_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("commits")
+        .and(path::param::<Urn>())
+        .and(path("search"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<CommitsSearchQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::commits_search)
+}
+
+/// `GET /commits/<project_urn>/page?revision=<revision>&after=<sha1>&perPage=<per_page>`
+fn commits_page_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("commits")
+        .and(path::param::<Urn>())
+        .and(path("page"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<CommitsPageQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::commits_page)
+}
+
+/// `GET /contributors/<project_urn>?revision=<revision>&limit=<limit>&mailmap=<bool>`
+fn contributors_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("contributors")
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<ContributorsQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::contributors)
+}
+
+/// `GET /languages/<project_urn>?revision=<revision>`
+fn languages_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("languages")
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<LanguagesQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::languages)
+}
+
+/// `GET /graph/<project_urn>?revision=<revision>&limit=<limit>`
+fn graph_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("graph")
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<GraphQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::graph)
+}
+
+/// `GET /merge-preview/<project_urn>?base=<base>&head=<head>`
+///
+/// `base` and `head` may be branch or tag names or commit hashes.
+fn merge_preview_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("merge-preview")
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<MergePreviewQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::merge_preview)
+}
+
+/// `GET /objects/<project_urn>?type=<commit|tree|blob>&limit=<limit>`
+///
+/// Only served with [`http::Config::debug_endpoints`].
+fn objects_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+    debug_endpoints: bool,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    http::with_debug_guard(debug_endpoints)
+        .and(path("objects"))
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<ObjectsQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::objects)
+}
+
 /// `GET /local-state?path=<path>`
 fn local_state_filter() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     path("local-state")
@@ -85,7 +247,93 @@ fn local_state_filter() -> impl Filter<Extract = impl Reply, Error = Rejection>
         .and_then(handler::local_state)
 }
 
-/// `GET /tags/<project_urn>?peer_id=<peer_id>`
+/// `GET /local-source/blob?repoPath=<repo_path>&path=<path>&revision=<revision>`
+fn local_blob_filter() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("local-source")
+        .and(path("blob"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<LocalBlobQuery>())
+        .and_then(handler::local_blob)
+}
+
+/// `GET /local-source/tree?repoPath=<repo_path>&prefix=<prefix>&revision=<revision>`
+fn local_tree_filter() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("local-source")
+        .and(path("tree"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<LocalTreeQuery>())
+        .and_then(handler::local_tree)
+}
+
+/// `GET /local-source/diff?repoPath=<repo_path>`
+fn local_diff_filter() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("local-source")
+        .and(path("diff"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<LocalDiffQuery>())
+        .and_then(handler::local_diff)
+}
+
+/// `GET /readme/<project_urn>?peerId=<peer_id>&prefix=<prefix>&revision=<revision>&
+/// highlight=<theme>`
+///
+/// Responds with `204` if the directory at `prefix` has no README.
+fn readme_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("readme")
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<ReadmeQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::readme)
+}
+
+/// `GET /rev-parse/<project_urn>/<rev>`
+fn rev_parse_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("rev-parse")
+        .and(path::param::<Urn>())
+        .and(path::param::<String>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::rev_parse)
+}
+
+/// `GET /source-config/<project_urn>`
+fn source_config_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("source-config")
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::source_config)
+}
+
+/// `GET /stats/<project_urn>?branch=<branch>`
+fn stats_filter(
+    ctx: context::Context,
+    walks: http::HistoryWalks,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("stats")
+        .and(path::param::<Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<StatsQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and(http::with_history_walk_permit(walks))
+        .and_then(handler::stats)
+}
+
+/// `GET /tags/<project_urn>?peer_id=<peer_id>&sort=<semver|lexical|name>`
 fn tags_filter(
     ctx: context::Context,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
@@ -107,21 +355,57 @@ fn tree_filter(
         .and(path::end())
         .and(warp::get())
         .and(http::with_qs::<TreeQuery>())
+        .and(http::with_if_none_match())
         .and(http::with_context_unsealed(ctx))
         .and_then(handler::tree)
 }
 
+/// `GET /tree/<project_urn>/recursive?peerId=<peer_id>&prefix=<prefix>&revision=<revision>`
+///
+/// Streams every entry below `prefix` as newline-delimited JSON.
+fn tree_recursive_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("tree")
+        .and(path::param::<Urn>())
+        .and(path("recursive"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<TreeQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::tree_recursive)
+}
+
+/// `GET /tree/<project_urn>/summary?peerId=<peer_id>&prefix=<prefix>&revision=<revision>`
+fn tree_summary_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("tree")
+        .and(path::param::<Urn>())
+        .and(path("summary"))
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<TreeQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::tree_summary)
+}
+
 /// Source handlers for conversion between core domain and http request fullfilment.
 mod handler {
     use warp::{reply, Rejection, Reply};
 
     use link_identities::git::Urn;
-    use radicle_git_ext::Oid;
     use radicle_source::surf::vcs::git::RefScope;
 
-    use crate::{browser, context, error};
+    use crate::{browser, context, error, project};
 
     /// Fetch a [`radicle_source::Blob`].
+    ///
+    /// The `ETag` of the response is derived from the resolved commit, the path, the
+    /// highlighting theme, the selected lines and the representation. Its `Last-Modified` date is
+    /// the committer time of the last commit that touched the blob. If `plain_text` is set, the
+    /// content of text blobs is returned without highlighting, while other blobs are answered
+    /// with `406 Not Acceptable`.
     pub async fn blob(
         project_urn: Urn,
         super::BlobQuery {
@@ -129,28 +413,217 @@ mod handler {
             peer_id,
             revision,
             highlight,
+            max_highlight_bytes,
+            start_line,
+            end_line,
         }: super::BlobQuery,
+        if_none_match: Option<String>,
+        if_modified_since: Option<headers::IfModifiedSince>,
+        plain_text: bool,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
         let peer_id = super::http::guard_self_peer_id(&ctx.peer, peer_id);
         let revision = super::http::guard_self_revision(&ctx.peer, revision);
 
-        let theme = highlight.map(|theme| match theme {
-            super::HighlightTheme::Dark => "base16-ocean.dark",
-            super::HighlightTheme::Light => "base16-ocean.light",
-            super::HighlightTheme::H4x0r => "base16-ocean.h4x0r",
-        });
+        let theme = highlight.filter(|_| !plain_text).map(|theme| theme.name());
+
+        let branch = crate::daemon::state::resolve_default_branch(
+            ctx.peer.librad_peer(),
+            project_urn,
+            peer_id,
+        )
+        .await
+        .map_err(error::Error::from)?;
+        let revision = resolve_revision(&ctx.peer, &branch, revision)?;
+        let max_highlight_bytes = resolve_max_highlight_bytes(&ctx, max_highlight_bytes)?;
+        let highlighted = browser::using(&ctx.peer, branch, |browser| {
+            if start_line.is_none() && end_line.is_none() {
+                return crate::source::highlighted_blob(
+                    browser,
+                    revision,
+                    &path,
+                    theme,
+                    max_highlight_bytes,
+                );
+            }
+
+            // Only the selected lines are highlighted.
+            let blob = crate::source::highlighted_blob(
+                browser,
+                revision,
+                &path,
+                None,
+                max_highlight_bytes,
+            )?;
+            Ok(crate::source::blob_lines(
+                blob,
+                start_line,
+                end_line,
+                theme,
+                max_highlight_bytes,
+            ))
+        })?;
+
+        // The tag only depends on what is part of the response, so it stays valid when commits
+        // that don't touch the blob are added to the branch.
+        let format: &[u8] = if plain_text { b"text" } else { b"json" };
+        let last_commit = highlighted
+            .blob
+            .info
+            .last_commit
+            .as_ref()
+            .map(|header| header.sha1.to_string())
+            .unwrap_or_default();
+        let lines = format!(
+            "{}-{}",
+            start_line.map(|line| line.to_string()).unwrap_or_default(),
+            end_line.map(|line| line.to_string()).unwrap_or_default()
+        );
+        let etag = super::http::etag(&[
+            crate::source::blob_content_hash(&highlighted.blob).as_bytes(),
+            last_commit.as_bytes(),
+            path.as_bytes(),
+            theme.unwrap_or_default().as_bytes(),
+            lines.as_bytes(),
+            format,
+        ]);
+        let committer_time = highlighted
+            .blob
+            .info
+            .last_commit
+            .as_ref()
+            .map(|header| header.committer_time);
+        let if_modified_since = if_modified_since.filter(|_| if_none_match.is_none());
+
+        if !plain_text {
+            return Ok(super::http::with_last_modified(
+                super::http::with_etag(reply::json(&highlighted), &etag, if_none_match.as_deref()),
+                committer_time,
+                if_modified_since.as_ref(),
+            ));
+        }
+
+        match highlighted.blob.content {
+            radicle_source::BlobContent::Plain(content) => Ok(super::http::with_last_modified(
+                super::http::with_etag(
+                    reply::with_header(
+                        content,
+                        warp::http::header::CONTENT_TYPE,
+                        "text/plain; charset=utf-8",
+                    ),
+                    &etag,
+                    if_none_match.as_deref(),
+                ),
+                committer_time,
+                if_modified_since.as_ref(),
+            )),
+            // Answered directly instead of rejected, so that the response varies on `Accept`
+            // like the others.
+            radicle_source::BlobContent::Html(_) => Ok(crate::http::error::Response {
+                status_code: warp::http::StatusCode::NOT_ACCEPTABLE,
+                variant: "NOT_ACCEPTABLE",
+                message: format!("blob '{}' is only available as highlighted HTML", path),
+            }
+            .into_response()),
+            radicle_source::BlobContent::Binary(_) => Ok(crate::http::error::Response {
+                status_code: warp::http::StatusCode::NOT_ACCEPTABLE,
+                variant: "NOT_ACCEPTABLE",
+                message: format!("blob '{}' is binary and has no text representation", path),
+            }
+            .into_response()),
+        }
+    }
+
+    /// Resolves `revision` in the namespace of `reference` to the commit it points to, see
+    /// [`crate::source::resolve_revision`].
+    ///
+    /// Only a revision that doesn't exist is reported as [`error::Error::RevisionNotFound`].
+    /// Browsing the resolved commit afterwards reports other missing objects, e.g. paths, as
+    /// they are.
+    fn resolve_revision(
+        peer: &crate::peer::Peer,
+        reference: &librad::git::types::Reference<librad::git::types::Single>,
+        revision: Option<radicle_source::Revision<link_crypto::PeerId>>,
+    ) -> Result<Option<radicle_source::Revision<link_crypto::PeerId>>, error::Error> {
+        revision
+            .map(|revision| {
+                browser::using_commit(peer, reference.clone(), Some(revision), |_, head| {
+                    Ok(radicle_source::Revision::Sha {
+                        sha: radicle_source::Oid(head),
+                    })
+                })
+            })
+            .transpose()
+    }
+
+    /// Returns `max_highlight_bytes` if it is set and the limit from the session settings
+    /// otherwise.
+    fn resolve_max_highlight_bytes(
+        ctx: &context::Unsealed,
+        max_highlight_bytes: Option<usize>,
+    ) -> Result<usize, error::Error> {
+        match max_highlight_bytes {
+            Some(max_highlight_bytes) => Ok(max_highlight_bytes),
+            None => Ok(crate::session::get_current(&ctx.rest.store)?.map_or(
+                crate::session::settings::DEFAULT_MAX_HIGHLIGHT_BYTES,
+                |session| session.settings.source.max_highlight_bytes,
+            )),
+        }
+    }
 
-        let branch =
-            crate::daemon::state::get_branch(ctx.peer.librad_peer(), project_urn, peer_id, None)
+    /// Fetch the README of a directory, see [`crate::source::find_readme`].
+    pub async fn readme(
+        project_urn: Urn,
+        super::ReadmeQuery {
+            prefix,
+            peer_id,
+            revision,
+            highlight,
+        }: super::ReadmeQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = super::http::guard_self_peer_id(&ctx.peer, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+        let theme = highlight.map(|theme| theme.name());
+        let branch = crate::daemon::state::resolve_default_branch(
+            ctx.peer.librad_peer(),
+            project_urn,
+            peer_id,
+        )
+        .await
+        .map_err(error::Error::from)?;
+        let revision = resolve_revision(&ctx.peer, &branch, revision)?;
+        let max_highlight_bytes = resolve_max_highlight_bytes(&ctx, None)?;
+        let readme = browser::using(&ctx.peer, branch, |browser| {
+            crate::source::find_readme(browser, revision, prefix, theme, max_highlight_bytes)
+        })?;
+
+        Ok(match readme {
+            Some(readme) => reply::json(&readme).into_response(),
+            None => warp::http::StatusCode::NO_CONTENT.into_response(),
+        })
+    }
+
+    /// Check whether the local branch `branch` of the project exists.
+    pub async fn branch_exists(
+        project_urn: Urn,
+        branch: warp::path::Tail,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
                 .await
                 .map_err(error::Error::from)?;
-        let blob = browser::using(&ctx.peer, branch, |browser| {
-            radicle_source::blob::highlighting::blob(browser, revision, &path, theme)
+        let exists = browser::using(&ctx.peer, default_branch, |browser| {
+            crate::source::branch_exists(browser, branch.as_str())
         })
         .map_err(error::Error::from)?;
 
-        Ok(reply::json(&blob))
+        Ok(if exists {
+            warp::http::StatusCode::OK
+        } else {
+            warp::http::StatusCode::NOT_FOUND
+        })
     }
 
     /// Fetch the list [`radicle_source::Branch`].
@@ -160,22 +633,32 @@ mod handler {
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
         let peer_id = super::http::guard_self_peer_id(&ctx.peer, peer_id);
-        let default_branch =
-            crate::daemon::state::get_branch(ctx.peer.librad_peer(), project_urn, peer_id, None)
-                .await
-                .map_err(error::Error::from)?;
+        let default_branch = crate::daemon::state::resolve_default_branch(
+            ctx.peer.librad_peer(),
+            project_urn,
+            peer_id,
+        )
+        .await
+        .map_err(error::Error::from)?;
         let branches = browser::using(&ctx.peer, default_branch, |browser| {
             radicle_source::branches(browser, RefScope::from(peer_id))
         })
         .map_err(error::Error::from)?;
 
-        Ok(reply::json(&branches))
+        Ok(reply::json(&crate::source::ScopedRef::all(
+            branches, peer_id,
+        )))
     }
 
-    /// Fetch a [`radicle_source::Commit`].
+    /// Fetch a [`radicle_source::Commit`] together with the headers of its parents.
+    ///
+    /// The `ETag` of the response is the commit's full `sha1` and its `Last-Modified` date is the
+    /// committer time.
     pub async fn commit(
         project_urn: Urn,
-        sha1: Oid,
+        sha1: String,
+        if_none_match: Option<String>,
+        if_modified_since: Option<headers::IfModifiedSince>,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
         let default_branch =
@@ -183,18 +666,188 @@ mod handler {
                 .await
                 .map_err(error::Error::from)?;
         let commit = browser::using(&ctx.peer, default_branch, |browser| {
-            radicle_source::commit(browser, *sha1)
+            let sha1 = crate::source::resolve_rev(browser, &sha1)?;
+            crate::source::commit_with_parents(browser, sha1)
         })
         .map_err(error::Error::from)?;
 
-        Ok(reply::json(&commit))
+        let if_modified_since = if_modified_since.filter(|_| if_none_match.is_none());
+        Ok(super::http::with_last_modified(
+            super::http::with_etag(
+                reply::json(&commit),
+                &format!("\"{}\"", commit.header.header.sha1),
+                if_none_match.as_deref(),
+            ),
+            Some(commit.header.header.committer_time),
+            if_modified_since.as_ref(),
+        ))
     }
 
     /// Fetch the list of [`radicle_source::Commit`] from a branch.
     pub async fn commits(
         ctx: context::Unsealed,
         project_urn: Urn,
-        super::CommitsQuery { revision }: super::CommitsQuery,
+        super::CommitsQuery {
+            revision,
+            headers_only,
+            page,
+            per_page,
+            mailmap,
+            summary_max_len,
+            description_max_len,
+            since,
+            until,
+        }: super::CommitsQuery,
+        walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+                .await
+                .map_err(error::Error::from)?;
+        let revision = resolve_revision(&ctx.peer, &default_branch, revision)?;
+        let window = crate::source::TimeWindow {
+            since: since.map(|super::Timestamp(seconds)| seconds),
+            until: until.map(|super::Timestamp(seconds)| seconds),
+        };
+
+        if headers_only {
+            let headers = browser::using(&ctx.peer, default_branch, |browser| {
+                let mut headers = crate::source::commit_headers(
+                    browser,
+                    revision,
+                    window,
+                    page.unwrap_or(0),
+                    per_page.unwrap_or(super::DEFAULT_COMMITS_PER_PAGE),
+                )?;
+                apply_mailmap(browser, mailmap, &mut headers)?;
+                for header in &mut headers {
+                    header.truncate(summary_max_len, description_max_len);
+                }
+                Ok(headers)
+            })?;
+
+            return Ok(reply::json(&headers));
+        }
+
+        // Walking the complete history is given up once the client disconnects. The permit is
+        // only released once the walk noticed.
+        let commits = browser::using_cancellable(
+            &ctx.peer,
+            default_branch,
+            revision,
+            move |repo, reference, head, cancel| {
+                let _walk = walk;
+                let mut commits = crate::source::commits(repo, reference, head, window, cancel)?;
+                if mailmap {
+                    let mailmap = crate::source::commit_mailmap(repo, head)?;
+                    for header in &mut commits.headers {
+                        header.apply_mailmap(&mailmap);
+                    }
+                }
+                for header in &mut commits.headers {
+                    header.truncate(summary_max_len, description_max_len);
+                }
+                Ok(commits)
+            },
+        )
+        .await?;
+
+        Ok(reply::json(&commits))
+    }
+
+    /// Resolves the authors and committers of `headers` with the `.mailmap` of the revision the
+    /// `browser` is pointed at, if `enabled`.
+    fn apply_mailmap(
+        browser: &radicle_source::surf::vcs::git::Browser<'_>,
+        enabled: bool,
+        headers: &mut [crate::source::CommitHeader],
+    ) -> Result<(), radicle_source::Error> {
+        if enabled {
+            let mailmap = crate::source::mailmap(browser)?;
+            for header in headers {
+                header.apply_mailmap(&mailmap);
+            }
+        }
+        Ok(())
+    }
+
+    /// Search the history of a branch for commits matching a query.
+    pub async fn commits_search(
+        project_urn: Urn,
+        super::CommitsSearchQuery {
+            query,
+            field,
+            revision,
+            limit,
+        }: super::CommitsSearchQuery,
+        ctx: context::Unsealed,
+        _walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+                .await
+                .map_err(error::Error::from)?;
+        let headers = browser::using_commit(&ctx.peer, default_branch, revision, |repo, head| {
+            crate::source::search_commits(
+                repo,
+                head,
+                &query,
+                field.unwrap_or_default(),
+                limit.unwrap_or(super::DEFAULT_SEARCH_LIMIT),
+            )
+        })?;
+
+        Ok(reply::json(&headers))
+    }
+
+    /// Fetch a page of the history of a branch, see [`crate::source::commits_after`].
+    pub async fn commits_page(
+        project_urn: Urn,
+        super::CommitsPageQuery {
+            revision,
+            after,
+            per_page,
+        }: super::CommitsPageQuery,
+        ctx: context::Unsealed,
+        _walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+                .await
+                .map_err(error::Error::from)?;
+        let after = after
+            .map(|after| {
+                git2::Oid::from_str(&after).map_err(|_| error::Error::RevisionNotFound(after))
+            })
+            .transpose()?;
+        let revision = resolve_revision(&ctx.peer, &default_branch, revision)?;
+        // A missing cursor is reported through the inner result as it is not a
+        // `radicle_source::Error`.
+        let page = browser::using(&ctx.peer, default_branch, |browser| {
+            Ok(crate::source::commits_after(
+                browser,
+                revision,
+                after,
+                per_page.unwrap_or(super::DEFAULT_COMMITS_PAGE_SIZE),
+            ))
+        })
+        .and_then(std::convert::identity)?;
+
+        Ok(reply::json(&page))
+    }
+
+    /// Fetch the commit graph of a revision, see [`crate::source::commit_graph`].
+    pub async fn graph(
+        project_urn: Urn,
+        super::GraphQuery { revision, limit }: super::GraphQuery,
+        ctx: context::Unsealed,
+        _walk: super::http::HistoryWalkPermit,
     ) -> Result<impl Reply, Rejection> {
         let revision = super::http::guard_self_revision(&ctx.peer, revision);
 
@@ -202,12 +855,166 @@ mod handler {
             crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
                 .await
                 .map_err(error::Error::from)?;
-        let commits = browser::using(&ctx.peer, default_branch, |browser| {
-            radicle_source::commits(browser, revision)
+        let nodes = browser::using(&ctx.peer, default_branch, |browser| {
+            crate::source::commit_graph(
+                browser,
+                revision,
+                limit.unwrap_or(super::DEFAULT_GRAPH_LIMIT),
+            )
         })
         .map_err(error::Error::from)?;
 
-        Ok(reply::json(&commits))
+        Ok(reply::json(&nodes))
+    }
+
+    /// List the authors of the history of a revision, see [`crate::source::contributors`].
+    pub async fn contributors(
+        project_urn: Urn,
+        super::ContributorsQuery {
+            revision,
+            limit,
+            mailmap,
+        }: super::ContributorsQuery,
+        ctx: context::Unsealed,
+        _walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+                .await
+                .map_err(error::Error::from)?;
+        let contributors =
+            browser::using_commit(&ctx.peer, default_branch, revision, |repo, head| {
+                crate::source::contributors(
+                    repo,
+                    head,
+                    limit.unwrap_or(super::DEFAULT_CONTRIBUTORS_LIMIT),
+                    mailmap,
+                )
+            })?;
+
+        Ok(reply::json(&contributors))
+    }
+
+    /// Breakdown of the files of a revision by language, see [`crate::source::languages`]. The
+    /// result is cached by the commit the revision points to.
+    pub async fn languages(
+        project_urn: Urn,
+        super::LanguagesQuery { revision }: super::LanguagesQuery,
+        ctx: context::Unsealed,
+        walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn.clone())
+                .await
+                .map_err(error::Error::from)?;
+        let head = match revision {
+            None => crate::daemon::state::head_oid(
+                ctx.peer.librad_peer(),
+                project_urn,
+                default_branch.remote,
+            )
+            .await
+            .map_err(error::Error::from)?,
+            revision => {
+                browser::using_commit(&ctx.peer, default_branch.clone(), revision, |_, head| {
+                    Ok(head)
+                })?
+            },
+        };
+
+        if let Some(languages) = crate::source::cached_languages(&ctx.rest.store, head)? {
+            return Ok(reply::json(&languages));
+        }
+
+        let languages = browser::using_cancellable(
+            &ctx.peer,
+            default_branch,
+            Some(radicle_source::Revision::Sha {
+                sha: radicle_source::Oid(head),
+            }),
+            move |repo, _, head, cancel| {
+                let _walk = walk;
+                crate::source::languages(repo, head, cancel)
+            },
+        )
+        .await?;
+        crate::source::cache_languages(&ctx.rest.store, head, &languages)?;
+
+        Ok(reply::json(&languages))
+    }
+
+    /// Preview merging `head` into `base`, see [`crate::source::merge_preview`].
+    pub async fn merge_preview(
+        project_urn: Urn,
+        super::MergePreviewQuery { base, head }: super::MergePreviewQuery,
+        ctx: context::Unsealed,
+        _walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+                .await
+                .map_err(error::Error::from)?;
+        let (base_oid, head_oid) = browser::using(&ctx.peer, default_branch, |browser| {
+            Ok((
+                crate::source::resolve_rev(browser, &base),
+                crate::source::resolve_rev(browser, &head),
+            ))
+        })
+        .map_err(error::Error::from)?;
+        let base_oid =
+            base_oid.map_err(|err| crate::source::revision_not_found(err.into(), Some(base)))?;
+        let head_oid =
+            head_oid.map_err(|err| crate::source::revision_not_found(err.into(), Some(head)))?;
+
+        let preview = ctx
+            .peer
+            .monorepo_unblock(move |repo| {
+                Ok(crate::source::merge_preview(&repo, base_oid, head_oid))
+            })
+            .await
+            .map_err(error::Error::from)??;
+
+        Ok(reply::json(&preview))
+    }
+
+    /// List the oids of objects reachable from the head of the default branch, see
+    /// [`crate::source::list_objects`].
+    pub async fn objects(
+        project_urn: Urn,
+        super::ObjectsQuery { kind, limit }: super::ObjectsQuery,
+        ctx: context::Unsealed,
+        _walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn.clone())
+                .await
+                .map_err(error::Error::from)?;
+        let head = crate::daemon::state::head_oid(
+            ctx.peer.librad_peer(),
+            project_urn,
+            default_branch.remote,
+        )
+        .await
+        .map_err(error::Error::from)?;
+
+        let objects = ctx
+            .peer
+            .monorepo_unblock(move |repo| {
+                Ok(crate::source::list_objects(
+                    &repo,
+                    head,
+                    kind,
+                    limit.unwrap_or(super::DEFAULT_OBJECTS_LIMIT),
+                ))
+            })
+            .await
+            .map_err(error::Error::from)??;
+
+        Ok(reply::json(&objects))
     }
 
     /// Fetch the list [`radicle_source::Branch`] for a local repository.
@@ -220,22 +1027,136 @@ mod handler {
         Ok(reply::json(&state))
     }
 
+    /// Fetch a [`radicle_source::Blob`] from a repository on disk that is not necessarily a
+    /// project.
+    pub async fn local_blob(
+        super::LocalBlobQuery {
+            repo_path,
+            path,
+            revision,
+        }: super::LocalBlobQuery,
+    ) -> Result<impl Reply, Rejection> {
+        let blob =
+            crate::source::local_blob(&repo_path, revision, &path).map_err(error::Error::from)?;
+
+        Ok(reply::json(&blob))
+    }
+
+    /// Fetch a [`radicle_source::Tree`] from a repository on disk that is not necessarily a
+    /// project.
+    pub async fn local_tree(
+        super::LocalTreeQuery {
+            repo_path,
+            prefix,
+            revision,
+        }: super::LocalTreeQuery,
+    ) -> Result<impl Reply, Rejection> {
+        let tree =
+            crate::source::local_tree(&repo_path, revision, prefix).map_err(error::Error::from)?;
+
+        Ok(reply::json(&tree))
+    }
+
+    /// Fetch the uncommitted changes of a working copy, see
+    /// [`crate::source::working_tree_diff`].
+    pub async fn local_diff(
+        super::LocalDiffQuery { repo_path }: super::LocalDiffQuery,
+    ) -> Result<impl Reply, Rejection> {
+        let diff = crate::source::working_tree_diff(&repo_path).map_err(error::Error::from)?;
+
+        Ok(reply::json(&diff))
+    }
+
+    /// Fetch the default branch of the project together with its branches and tags.
+    pub async fn source_config(
+        project_urn: Urn,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let project =
+            crate::daemon::state::get_project(ctx.peer.librad_peer(), project_urn.clone())
+                .await
+                .map_err(error::Error::from)?
+                .ok_or(error::Error::ProjectNotFound)?;
+        let metadata = project::Metadata::try_from(project)?;
+        let branch = crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+            .await
+            .map_err(error::Error::from)?;
+        let config = browser::using(&ctx.peer, branch, |browser| {
+            crate::source::source_config(browser, metadata.default_branch)
+        })
+        .map_err(error::Error::from)?;
+
+        Ok(reply::json(&config))
+    }
+
+    /// Resolve an abbreviated commit hash, branch or tag to the full commit hash.
+    pub async fn rev_parse(
+        project_urn: Urn,
+        rev: String,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+                .await
+                .map_err(error::Error::from)?;
+        let sha1 = browser::using(&ctx.peer, default_branch, |browser| {
+            crate::source::resolve_rev(browser, &rev)
+        })
+        .map_err(error::Error::from)?;
+
+        Ok(reply::json(
+            &serde_json::json!({ "sha1": sha1.to_string() }),
+        ))
+    }
+
+    /// Stats of the history of a branch, see [`crate::source::stats_bounded`].
+    pub async fn stats(
+        project_urn: Urn,
+        super::StatsQuery { branch }: super::StatsQuery,
+        ctx: context::Unsealed,
+        _walk: super::http::HistoryWalkPermit,
+    ) -> Result<impl Reply, Rejection> {
+        let default_branch =
+            crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
+                .await
+                .map_err(error::Error::from)?;
+        let revision = branch.map(|name| radicle_source::Revision::Branch {
+            name,
+            peer_id: None,
+        });
+        let stats =
+            browser::using_commit(&ctx.peer, default_branch.clone(), revision, |repo, head| {
+                crate::source::stats_bounded(repo, &default_branch, head, usize::MAX)
+            })?;
+
+        Ok(reply::json(&stats))
+    }
+
     /// Fetch the list [`radicle_source::Tag`].
     pub async fn tags(
         project_urn: Urn,
-        _query: super::TagQuery,
+        super::TagQuery { peer_id, sort }: super::TagQuery,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
+        let peer_id = super::http::guard_self_peer_id(&ctx.peer, peer_id);
         let branch = crate::daemon::state::find_default_branch(ctx.peer.librad_peer(), project_urn)
             .await
             .map_err(error::Error::from)?;
-        let tags = browser::using(&ctx.peer, branch, |browser| radicle_source::tags(browser))
-            .map_err(error::Error::from)?;
+        let mut tags = browser::using(&ctx.peer, branch, |browser| {
+            crate::source::tags(browser, peer_id)
+        })
+        .map_err(error::Error::from)?;
+        crate::source::sort_tags(&mut tags, sort.unwrap_or_default());
 
-        Ok(reply::json(&tags))
+        Ok(reply::json(&crate::source::ScopedRef::all(tags, peer_id)))
     }
 
-    /// Fetch a [`radicle_source::Tree`].
+    /// Fetch a [`radicle_source::Tree`] together with the submodules in it, see
+    /// [`crate::source::TreeWithSubmodules`].
+    ///
+    /// The `ETag` of the response is derived from the resolved commit and the prefix. Requests for
+    /// the default branch are answered with `304 Not Modified` before the tree is read if the tag
+    /// matches.
     pub async fn tree(
         project_urn: Urn,
         super::TreeQuery {
@@ -243,21 +1164,169 @@ mod handler {
             peer_id,
             revision,
         }: super::TreeQuery,
+        if_none_match: Option<String>,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
         let peer_id = super::http::guard_self_peer_id(&ctx.peer, peer_id);
         let revision = super::http::guard_self_revision(&ctx.peer, revision);
-        let branch =
-            crate::daemon::state::get_branch(ctx.peer.librad_peer(), project_urn, peer_id, None)
-                .await
-                .map_err(error::Error::from)?;
-        let tree = browser::using(&ctx.peer, branch, |browser| {
-            radicle_source::tree(browser, revision, prefix)
-        })
+        let etag_prefix = prefix.clone().unwrap_or_default();
+        if revision.is_none() {
+            let head = crate::daemon::state::head_oid(
+                ctx.peer.librad_peer(),
+                project_urn.clone(),
+                peer_id,
+            )
+            .await
+            .map_err(error::Error::from)?;
+            let etag = super::http::etag(&[head.as_bytes(), etag_prefix.as_bytes()]);
+            if super::http::is_not_modified(&etag, if_none_match.as_deref()) {
+                return Ok(super::http::with_etag(
+                    warp::http::StatusCode::NOT_MODIFIED,
+                    &etag,
+                    if_none_match.as_deref(),
+                ));
+            }
+        }
+
+        let branch = crate::daemon::state::resolve_default_branch(
+            ctx.peer.librad_peer(),
+            project_urn,
+            peer_id,
+        )
+        .await
         .map_err(error::Error::from)?;
+        let revision = resolve_revision(&ctx.peer, &branch, revision)?;
+        let submodule_prefix = prefix.clone();
+        let (tree, head) = browser::using(&ctx.peer, branch, |browser| {
+            let tree = crate::source::tree(browser, revision, prefix);
+            Ok((tree, browser.get().first().id))
+        })?;
+        let tree = tree?;
 
-        Ok(reply::json(&tree))
+        let submodules = ctx
+            .peer
+            .monorepo_unblock(move |repo| {
+                Ok(crate::source::submodules(
+                    &repo,
+                    head,
+                    submodule_prefix.as_deref(),
+                ))
+            })
+            .await
+            .map_err(error::Error::from)?
+            .map_err(error::Error::from)?;
+
+        let etag = super::http::etag(&[head.as_bytes(), etag_prefix.as_bytes()]);
+        Ok(super::http::with_etag(
+            reply::json(&crate::source::TreeWithSubmodules { tree, submodules }),
+            &etag,
+            if_none_match.as_deref(),
+        ))
     }
+
+    /// Stream the entries of a tree recursively as newline-delimited JSON, see
+    /// [`crate::source::walk_tree`].
+    ///
+    /// The entries are sent as they are visited. A missing revision or prefix is rejected before
+    /// the response starts. If walking the tree fails after that, the response is aborted.
+    pub async fn tree_recursive(
+        project_urn: Urn,
+        super::TreeQuery {
+            prefix,
+            peer_id,
+            revision,
+        }: super::TreeQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = super::http::guard_self_peer_id(&ctx.peer, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+        let branch = crate::daemon::state::resolve_default_branch(
+            ctx.peer.librad_peer(),
+            project_urn,
+            peer_id,
+        )
+        .await
+        .map_err(error::Error::from)?;
+
+        // Resolve the revision and prefix up front, so that they can be rejected with a proper
+        // status instead of aborting the response.
+        let prefix = prefix.unwrap_or_default().trim_matches('/').to_string();
+        let tree = browser::using_commit(&ctx.peer, branch, revision, |repo, head| {
+            Ok(crate::source::prefix_tree(repo, head, &prefix)?.id())
+        })?;
+
+        let (lines_tx, mut lines_rx) =
+            tokio::sync::mpsc::channel::<Result<Vec<u8>, std::io::Error>>(TREE_STREAM_BUFFER);
+        let peer = ctx.peer.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = browser::monorepo(&peer).and_then(|repo| {
+                crate::source::walk_tree(&repo, tree, &prefix, |entry| {
+                    let line = serde_json::to_vec(&entry).map(|mut line| {
+                        line.push(b'\n');
+                        line
+                    });
+                    lines_tx
+                        .blocking_send(line.map_err(std::io::Error::from))
+                        .is_ok()
+                })
+            });
+            if let Err(err) = result {
+                tracing::warn!(?err, "failed to walk tree");
+                let err = std::io::Error::new(std::io::ErrorKind::Other, err.to_string());
+                lines_tx.blocking_send(Err(err)).ok();
+            }
+        });
+
+        let body = warp::hyper::Body::wrap_stream(async_stream::stream! {
+            while let Some(line) = lines_rx.recv().await {
+                yield line;
+            }
+        });
+        Ok(reply::with_header(
+            warp::reply::Response::new(body),
+            warp::http::header::CONTENT_TYPE,
+            "application/x-ndjson",
+        ))
+    }
+
+    /// Count the files and directories of a tree, see [`crate::source::tree_summary`]. The
+    /// result is cached by the commit the revision points to and the prefix.
+    pub async fn tree_summary(
+        project_urn: Urn,
+        super::TreeQuery {
+            prefix,
+            peer_id,
+            revision,
+        }: super::TreeQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = super::http::guard_self_peer_id(&ctx.peer, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.peer, revision);
+        let branch = crate::daemon::state::resolve_default_branch(
+            ctx.peer.librad_peer(),
+            project_urn,
+            peer_id,
+        )
+        .await
+        .map_err(error::Error::from)?;
+        let head = browser::using_commit(&ctx.peer, branch, revision, |_, head| Ok(head))?;
+
+        if let Some(summary) =
+            crate::source::cached_tree_summary(&ctx.rest.store, head, prefix.as_deref())?
+        {
+            return Ok(reply::json(&summary));
+        }
+
+        let repo = browser::monorepo(&ctx.peer)?;
+        let summary =
+            crate::source::tree_summary(&repo, head, prefix.as_deref().unwrap_or_default())?;
+        crate::source::cache_tree_summary(&ctx.rest.store, head, prefix.as_deref(), summary)?;
+
+        Ok(reply::json(&summary))
+    }
+
+    /// Number of serialized tree entries buffered before walking the tree waits for the client.
+    const TREE_STREAM_BUFFER: usize = 64;
 }
 
 /// Query parameters for [`handler::local_state`]
@@ -268,12 +1337,197 @@ pub struct LocalStateQuery {
     path: String,
 }
 
+/// Query parameters for [`handler::local_blob`].
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalBlobQuery {
+    /// Path to the repository.
+    repo_path: String,
+    /// Location of the blob in the tree.
+    path: String,
+    /// Revision to query at. Defaults to `HEAD`.
+    revision: Option<radicle_source::Revision<PeerId>>,
+}
+
+/// Query parameters for [`handler::local_tree`].
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalTreeQuery {
+    /// Path to the repository.
+    repo_path: String,
+    /// Prefix to filter files and folders by.
+    prefix: Option<String>,
+    /// Revision to query at. Defaults to `HEAD`.
+    revision: Option<radicle_source::Revision<PeerId>>,
+}
+
+/// Query parameters for [`handler::local_diff`].
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalDiffQuery {
+    /// Path to the working copy.
+    repo_path: String,
+}
+
 /// Bundled query params to pass to the commits handler.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommitsQuery {
     /// Revision to query at.
     revision: Option<radicle_source::Revision<PeerId>>,
+    /// Only return a page of commit headers and skip computing the repository statistics.
+    #[serde(default)]
+    headers_only: bool,
+    /// Page of commit headers to return when `headers_only` is set. Defaults to the first page.
+    page: Option<usize>,
+    /// Number of commit headers per page when `headers_only` is set.
+    per_page: Option<usize>,
+    /// Resolve authors and committers with the `.mailmap` of the revision.
+    #[serde(default)]
+    mailmap: bool,
+    /// Maximum number of characters of the commit summaries.
+    summary_max_len: Option<usize>,
+    /// Maximum number of characters of the commit descriptions.
+    description_max_len: Option<usize>,
+    /// Only return commits committed at or after this time.
+    since: Option<Timestamp>,
+    /// Only return commits committed at or before this time.
+    until: Option<Timestamp>,
+}
+
+/// A point in time given either as seconds since the epoch or as an RFC 3339 date, e.g.
+/// `1650000000` or `2022-04-15T05:20:00Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "i64")]
+pub struct Timestamp(i64);
+
+impl TryFrom<String> for Timestamp {
+    type Error = chrono::ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.parse::<i64>() {
+            Ok(seconds) => Ok(Self(seconds)),
+            Err(_) => Ok(Self(
+                chrono::DateTime::parse_from_rfc3339(&value)?.timestamp(),
+            )),
+        }
+    }
+}
+
+impl From<Timestamp> for i64 {
+    fn from(Timestamp(seconds): Timestamp) -> Self {
+        seconds
+    }
+}
+
+/// Number of commit headers returned by [`handler::commits`] if no page size is given.
+const DEFAULT_COMMITS_PER_PAGE: usize = 100;
+
+/// Bundled query params to pass to the commits page handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitsPageQuery {
+    /// Revision to page through the history of.
+    revision: Option<radicle_source::Revision<PeerId>>,
+    /// Full hash of the last commit of the previous page. Starts at the latest commit if not
+    /// given.
+    after: Option<String>,
+    /// Maximum number of commits to return. Must not be `0`, as an empty page has no commit to
+    /// continue from.
+    per_page: Option<std::num::NonZeroUsize>,
+}
+
+/// Number of commit headers returned by [`handler::commits_page`] if no page size is given.
+const DEFAULT_COMMITS_PAGE_SIZE: std::num::NonZeroUsize =
+    match std::num::NonZeroUsize::new(DEFAULT_COMMITS_PER_PAGE) {
+        Some(size) => size,
+        None => panic!("the default page size must not be 0"),
+    };
+
+/// Maximum number of commits returned by [`handler::commits_search`] if no limit is given.
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+/// Bundled query params to pass to the commits search handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitsSearchQuery {
+    /// Text to search for.
+    query: String,
+    /// Part of the commit to match the query against. Defaults to the commit message.
+    field: Option<crate::source::SearchField>,
+    /// Revision to search the history of.
+    revision: Option<radicle_source::Revision<PeerId>>,
+    /// Maximum number of commits to return.
+    limit: Option<usize>,
+}
+
+/// Maximum number of commits returned by [`handler::graph`] if no limit is given.
+const DEFAULT_GRAPH_LIMIT: usize = 500;
+
+/// Bundled query params to pass to the graph handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQuery {
+    /// Revision to walk the history of. Defaults to the default branch.
+    revision: Option<radicle_source::Revision<PeerId>>,
+    /// Maximum number of commits to return.
+    limit: Option<usize>,
+}
+
+/// Maximum number of commits walked by [`handler::contributors`] if no limit is given.
+const DEFAULT_CONTRIBUTORS_LIMIT: usize = 10_000;
+
+/// Bundled query params to pass to the stats handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsQuery {
+    /// Local branch to compute the stats of. Defaults to the default branch.
+    branch: Option<String>,
+}
+
+/// Bundled query params to pass to the contributors handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributorsQuery {
+    /// Revision to walk the history of. Defaults to the default branch.
+    revision: Option<radicle_source::Revision<PeerId>>,
+    /// Maximum number of commits to walk.
+    limit: Option<usize>,
+    /// Resolve authors with the `.mailmap` of the revision.
+    #[serde(default)]
+    mailmap: bool,
+}
+
+/// Bundled query params to pass to the languages handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguagesQuery {
+    /// Revision to break down. Defaults to the default branch.
+    revision: Option<radicle_source::Revision<PeerId>>,
+}
+
+/// Bundled query params to pass to the objects handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectsQuery {
+    /// Type of the objects to list.
+    #[serde(rename = "type")]
+    kind: crate::source::ObjectKind,
+    /// Maximum number of objects to return.
+    limit: Option<usize>,
+}
+
+/// Maximum number of objects listed by [`handler::objects`] if no limit is given.
+const DEFAULT_OBJECTS_LIMIT: usize = 100;
+
+/// Bundled query params to pass to the merge preview handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePreviewQuery {
+    /// Revision to merge into.
+    base: String,
+    /// Revision to merge.
+    head: String,
 }
 
 /// Bundled query params to pass to the blob handler.
@@ -288,6 +1542,13 @@ pub struct BlobQuery {
     revision: Option<radicle_source::Revision<PeerId>>,
     /// Whether or not to syntax highlight the blob.
     highlight: Option<HighlightTheme>,
+    /// Blobs larger than this many bytes are not highlighted. Defaults to the limit in the
+    /// session settings.
+    max_highlight_bytes: Option<usize>,
+    /// Only return the lines of a text blob starting at this line, numbered from 1.
+    start_line: Option<usize>,
+    /// Only return the lines of a text blob up to and including this line.
+    end_line: Option<usize>,
 }
 
 /// A query param for [`handler::branches`].
@@ -310,12 +1571,28 @@ pub struct TreeQuery {
     revision: Option<radicle_source::Revision<PeerId>>,
 }
 
+/// Bundled query params to pass to the readme handler.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadmeQuery {
+    /// Path of the directory to look for a README in.
+    prefix: Option<String>,
+    /// PeerId to scope the query by.
+    peer_id: Option<PeerId>,
+    /// Revision to query at.
+    revision: Option<radicle_source::Revision<PeerId>>,
+    /// Whether or not to syntax highlight the README.
+    highlight: Option<HighlightTheme>,
+}
+
 /// A query param for [`handler::tags`].
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TagQuery {
     /// PeerId to scope the query by.
     pub peer_id: Option<PeerId>,
+    /// Order of the returned tags. Defaults to [`crate::source::TagSort::Lexical`].
+    pub sort: Option<crate::source::TagSort>,
 }
 
 /// Syntax highlighting theme
@@ -329,3 +1606,14 @@ pub enum HighlightTheme {
     /// A h4x0r theme.
     H4x0r,
 }
+
+impl HighlightTheme {
+    /// The name of the theme in [`radicle_source::syntax`].
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Dark => "base16-ocean.dark",
+            Self::Light => "base16-ocean.light",
+            Self::H4x0r => "base16-ocean.h4x0r",
+        }
+    }
+}
@@ -4,6 +4,7 @@
 // with Radicle Linking Exception. For full terms see the included
 // LICENSE file.
 
+use anyhow::Context as _;
 use futures::prelude::*;
 
 mod diagnostics;
@@ -11,10 +12,32 @@ mod identity;
 mod keystore;
 mod project;
 mod session;
+mod version;
+
+/// Configuration for the CORS policy of the HTTP API.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// Origins that are allowed to make cross-origin requests. If `None`, the origin of any
+    /// request is allowed.
+    pub allowed_origins: Option<Vec<http::HeaderValue>>,
+    /// Whether cross-origin requests may include credentials.
+    pub allow_credentials: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: None,
+            allow_credentials: true,
+        }
+    }
+}
 
 pub fn serve(
     ctx: crate::context::Context,
     listen_addr: std::net::SocketAddr,
+    cors: CorsConfig,
+    config: crate::http::Config,
     restart_signal: impl Future<Output = ()> + Send + 'static,
 ) -> impl Future<Output = anyhow::Result<()>> {
     let ctx_shutdown = match &ctx {
@@ -23,9 +46,10 @@ pub fn serve(
     };
 
     async move {
-        let router = make_router(ctx);
-        let server = hyper::Server::bind(&listen_addr)
+        let router = make_router(ctx, &cors, config);
+        let server = bind(listen_addr)?
             .serve(router.into_make_service_with_connect_info::<std::net::SocketAddr>());
+        tracing::info!(addr = %server.local_addr(), "serving HTTP API");
 
         server
             .with_graceful_shutdown({
@@ -39,8 +63,23 @@ pub fn serve(
     }
 }
 
-fn make_router(ctx: crate::context::Context) -> axum::Router {
-    let legacy_api = crate::http::api(ctx.clone());
+/// Binds the HTTP API to `listen_addr`. If the port of `listen_addr` is `0` an ephemeral port is
+/// picked, which the returned builder reports through its `local_addr`.
+fn bind(
+    listen_addr: std::net::SocketAddr,
+) -> anyhow::Result<hyper::server::Builder<hyper::server::conn::AddrIncoming>> {
+    hyper::Server::try_bind(&listen_addr)
+        .with_context(|| format!("failed to bind HTTP API to {}", listen_addr))
+}
+
+fn make_router(
+    ctx: crate::context::Context,
+    cors: &CorsConfig,
+    config: crate::http::Config,
+) -> axum::Router {
+    let read_only = config.read_only;
+    let json_limit = config.body_limits.json;
+    let legacy_api = crate::http::api(ctx.clone(), config);
     let warp_service = tower::util::MapResponse::new(
         warp::service(legacy_api),
         |response: hyper::Response<hyper::Body>| {
@@ -51,36 +90,166 @@ fn make_router(ctx: crate::context::Context) -> axum::Router {
         },
     );
 
-    let cors = tower_http::cors::CorsLayer::new()
-        .allow_origin(tower_http::cors::Any)
-        .allow_origin(tower_http::cors::Origin::predicate(|_, _| true))
-        .allow_credentials(true)
-        .allow_headers([http::header::CONTENT_TYPE])
-        .allow_methods([
-            http::Method::DELETE,
-            http::Method::GET,
-            http::Method::POST,
-            http::Method::PUT,
-            http::Method::OPTIONS,
-        ]);
-
+    // Every event that is emitted while handling a request, including those of the peer, is
+    // part of this span and carries the request ID.
     let trace_layer = tower_http::trace::TraceLayer::new_for_http()
-        .make_span_with(tower_http::trace::DefaultMakeSpan::new().level(tracing::Level::INFO))
+        .make_span_with(|request: &http::Request<_>| {
+            let request_id = request
+                .headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default();
+            tracing::info_span!(
+                "request",
+                method = %request.method(),
+                uri = %request.uri(),
+                version = ?request.version(),
+                request_id,
+            )
+        })
         .on_response(tower_http::trace::DefaultOnResponse::new().level(tracing::Level::INFO));
 
     let handlers = axum::Router::new()
-        .merge(keystore::router())
         .merge(diagnostics::router())
         .merge(identity::router())
+        .merge(keystore::router())
         .merge(session::router())
         .merge(project::router())
+        .merge(version::router());
+    let handlers = if read_only {
+        handlers.layer(axum::middleware::from_fn(reject_mutations))
+    } else {
+        handlers
+    };
+    // The keystore has to be unsealed in read-only mode too.
+    let handlers = handlers
+        .merge(keystore::unseal_router())
+        .layer(axum::middleware::from_fn(move |request, next| {
+            limit_body(json_limit, request, next)
+        }))
         .layer(axum::Extension(ctx));
 
-    axum::Router::new()
+    let router = axum::Router::new()
         .nest("/v1", handlers)
         .fallback(warp_service)
-        .layer(trace_layer)
-        .layer(cors)
+        .layer(trace_layer);
+
+    with_request_id(router).layer(cors_layer(cors))
+}
+
+/// Header that carries the ID of a request, see [`with_request_id`].
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Makes sure every request handled by `router` has an `X-Request-Id` header and returns the
+/// header on the response. Requests without the header are assigned a random ID.
+fn with_request_id(router: axum::Router) -> axum::Router {
+    router
+        .layer(tower_http::request_id::PropagateRequestIdLayer::x_request_id())
+        .layer(tower_http::request_id::SetRequestIdLayer::x_request_id(
+            RandomRequestId,
+        ))
+}
+
+/// Generates random request IDs of 32 hex digits.
+#[derive(Clone, Copy)]
+struct RandomRequestId;
+
+impl tower_http::request_id::MakeRequestId for RandomRequestId {
+    fn make_request_id<B>(
+        &mut self,
+        _request: &http::Request<B>,
+    ) -> Option<tower_http::request_id::RequestId> {
+        let id = format!("{:032x}", rand::random::<u128>());
+        http::HeaderValue::from_str(&id)
+            .ok()
+            .map(tower_http::request_id::RequestId::new)
+    }
+}
+
+/// Middleware that rejects requests that may modify state with `405 Method Not Allowed`, see
+/// [`crate::http::with_read_only_guard`].
+async fn reject_mutations<B>(
+    request: http::Request<B>,
+    next: axum::middleware::Next<B>,
+) -> Result<axum::response::Response, Error> {
+    if crate::http::is_safe_method(request.method()) {
+        Ok(next.run(request).await)
+    } else {
+        Err(Error::Custom {
+            status_code: http::StatusCode::METHOD_NOT_ALLOWED,
+            variant: "READ_ONLY",
+            message: "the API is in read-only mode".to_string(),
+            details: None,
+        })
+    }
+}
+
+/// Middleware that rejects requests with a body of more than `limit` bytes with `413 Payload Too
+/// Large`, see [`crate::http::with_json_body`].
+///
+/// The body is buffered so that requests without a `Content-Length` header can be checked too.
+async fn limit_body(
+    limit: u64,
+    request: http::Request<axum::body::Body>,
+    next: axum::middleware::Next<axum::body::Body>,
+) -> Result<axum::response::Response, Error> {
+    use hyper::body::HttpBody as _;
+
+    let payload_too_large = || Error::Custom {
+        status_code: http::StatusCode::PAYLOAD_TOO_LARGE,
+        variant: "PAYLOAD_TOO_LARGE",
+        message: "Request body is too large".to_string(),
+        details: None,
+    };
+
+    let (parts, mut body) = request.into_parts();
+    if body.size_hint().lower() > limit {
+        return Err(payload_too_large());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|err| Error::Custom {
+            status_code: http::StatusCode::BAD_REQUEST,
+            variant: "INVALID_BODY",
+            message: err.to_string(),
+            details: None,
+        })?;
+        if (bytes.len() + chunk.len()) as u64 > limit {
+            return Err(payload_too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let request = http::Request::from_parts(parts, axum::body::Body::from(bytes));
+    Ok(next.run(request).await)
+}
+
+/// Builds the CORS layer for `config`.
+///
+/// Allowed origins are reflected in the `Access-Control-Allow-Origin` header instead of using a
+/// wildcard because browsers reject wildcards for requests with credentials.
+fn cors_layer(config: &CorsConfig) -> tower_http::cors::CorsLayer {
+    let allow_origin = match &config.allowed_origins {
+        Some(origins) => tower_http::cors::Origin::list(origins.iter().cloned()),
+        None => tower_http::cors::Origin::predicate(|_, _| true),
+    };
+
+    tower_http::cors::CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_credentials(config.allow_credentials)
+        .allow_headers([
+            http::header::CONTENT_TYPE,
+            http::HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+        .expose_headers([http::HeaderName::from_static(REQUEST_ID_HEADER)])
+        .allow_methods([
+            http::Method::DELETE,
+            http::Method::GET,
+            http::Method::POST,
+            http::Method::PUT,
+            http::Method::OPTIONS,
+        ])
 }
 
 /// Error type for request handlers that return [`Result`].
@@ -241,3 +410,225 @@ mod extract {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt as _;
+
+    async fn allowed_origin(
+        config: &CorsConfig,
+        origin: &'static str,
+    ) -> Option<http::HeaderValue> {
+        let router = axum::Router::new()
+            .route("/", axum::routing::get(|| async {}))
+            .layer(cors_layer(config));
+        let request = http::Request::builder()
+            .uri("/")
+            .header(http::header::ORIGIN, origin)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+
+        response
+            .headers()
+            .get(http::header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    #[tokio::test]
+    async fn bind_picks_ephemeral_port() {
+        let server = bind("127.0.0.1:0".parse().unwrap())
+            .unwrap()
+            .serve(axum::Router::new().into_make_service());
+        let addr = server.local_addr();
+        assert_ne!(addr.port(), 0);
+
+        // The address is taken while `server` is alive.
+        assert!(bind(addr).is_err());
+    }
+
+    #[tokio::test]
+    async fn reject_mutations_allows_reads() {
+        let router = axum::Router::new()
+            .route("/", axum::routing::get(|| async {}).post(|| async {}))
+            .layer(axum::middleware::from_fn(reject_mutations));
+        let request = |method| {
+            http::Request::builder()
+                .method(method)
+                .uri("/")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = router
+            .clone()
+            .oneshot(request(http::Method::GET))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+
+        let response = router.oneshot(request(http::Method::POST)).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    fn sealed_context(dir: &std::path::Path) -> crate::context::Context {
+        crate::context::Sealed {
+            store: kv::Store::new(kv::Config::new(dir.join("store"))).unwrap(),
+            service_handle: crate::service::Handle::detached(),
+            keystore: std::sync::Arc::new(crate::keystore::unsafe_fast_file(
+                dir.join("librad.key"),
+            )),
+            paths: librad::paths::Paths::from_root(dir.join("paths")).unwrap(),
+            shutdown: std::sync::Arc::new(tokio::sync::Notify::new()),
+        }
+        .into()
+    }
+
+    #[tokio::test]
+    async fn read_only_router_only_allows_unsealing() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = make_router(
+            sealed_context(dir.path()),
+            &CorsConfig::default(),
+            crate::http::Config {
+                read_only: true,
+                ..crate::http::Config::default()
+            },
+        );
+        let post = |uri| {
+            http::Request::builder()
+                .method(http::Method::POST)
+                .uri(uri)
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(r#"{"passphrase": "asdf"}"#))
+                .unwrap()
+        };
+
+        for uri in ["/v1/keystore", "/v1/identities", "/v1/projects"] {
+            let response = router.clone().oneshot(post(uri)).await.unwrap();
+            assert_eq!(
+                response.status(),
+                http::StatusCode::METHOD_NOT_ALLOWED,
+                "POST {}",
+                uri
+            );
+        }
+
+        let response = router.oneshot(post("/v1/keystore/unseal")).await.unwrap();
+        assert_ne!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn limit_body_rejects_large_bodies() {
+        let router = axum::Router::new()
+            .route("/", axum::routing::post(|body: String| async move { body }))
+            .layer(axum::middleware::from_fn(|request, next| {
+                limit_body(4, request, next)
+            }));
+        let request = |body| {
+            http::Request::builder()
+                .method(http::Method::POST)
+                .uri("/")
+                .body(body)
+                .unwrap()
+        };
+
+        let response = router
+            .clone()
+            .oneshot(request(axum::body::Body::from("four")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, "four");
+
+        let response = router
+            .clone()
+            .oneshot(request(axum::body::Body::from("five!")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Chunked bodies have no `Content-Length` and are counted while they are read.
+        let chunks = vec!["fo", "ur", "!"]
+            .into_iter()
+            .map(Ok::<_, std::io::Error>);
+        let response = router
+            .oneshot(request(axum::body::Body::wrap_stream(
+                futures::stream::iter(chunks),
+            )))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn request_id_is_echoed_or_generated() {
+        let router = with_request_id(axum::Router::new().route(
+            "/",
+            axum::routing::get(|headers: http::HeaderMap| async move {
+                headers
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string()
+            }),
+        ));
+
+        let request = http::Request::builder()
+            .uri("/")
+            .header(REQUEST_ID_HEADER, "my-request")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(
+            response.headers().get(REQUEST_ID_HEADER),
+            Some(&http::HeaderValue::from_static("my-request"))
+        );
+
+        let request = http::Request::builder()
+            .uri("/")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let request_id = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .expect("missing request ID")
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(request_id.len(), 32);
+        // The handler sees the generated ID.
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, request_id.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn cors_allows_any_origin_by_default() {
+        let config = CorsConfig::default();
+
+        assert_eq!(
+            allowed_origin(&config, "http://example.com").await,
+            Some(http::HeaderValue::from_static("http://example.com"))
+        );
+    }
+
+    #[tokio::test]
+    async fn cors_reflects_allowed_origin() {
+        let config = CorsConfig {
+            allowed_origins: Some(vec![http::HeaderValue::from_static(
+                "http://localhost:3000",
+            )]),
+            ..CorsConfig::default()
+        };
+
+        assert_eq!(
+            allowed_origin(&config, "http://localhost:3000").await,
+            Some(http::HeaderValue::from_static("http://localhost:3000"))
+        );
+        assert_eq!(allowed_origin(&config, "http://example.com").await, None);
+    }
+}
@@ -4,9 +4,26 @@
 // with Radicle Linking Exception. For full terms see the included
 // LICENSE file.
 
-/// Provides `GET /diagnostics` endpoint that returns diagnostics data.
+use anyhow::Context as _;
+
+/// Provides the following endpoints:
+///
+/// * `GET /diagnostics` to get diagnostics data
+/// * `GET /storage/stats` to get the size and contents of the monorepo
 pub fn router() -> axum::Router {
-    axum::Router::new().route("/diagnostics", axum::routing::get(get))
+    axum::Router::new()
+        .route("/diagnostics", axum::routing::get(get))
+        .route("/storage/stats", axum::routing::get(storage_stats))
+}
+
+async fn storage_stats(
+    super::extract::UnsealedContext(ctx): super::extract::UnsealedContext,
+) -> Result<axum::response::Json<crate::daemon::state::StorageStats>, super::Error> {
+    let stats = crate::daemon::state::storage_stats(ctx.peer.librad_peer())
+        .await
+        .context("failed to compute storage stats")?;
+
+    Ok(axum::response::Json(stats))
 }
 
 async fn get(
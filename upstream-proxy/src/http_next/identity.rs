@@ -8,16 +8,98 @@ use anyhow::Context as _;
 
 /// Provides the following endpoints:
 ///
+/// * `GET /identities?handlePrefix=<prefix>` to list all known persons
 /// * `POST /identities` to create local identity
 /// * `PUT /identities` to update local identity metadata
+/// * `GET /identities/:urn` to get a person by URN
 /// * `GET /identities/remote/:urn` to get information about a replicated identity
+/// * `GET /resolve?id=<id>` to normalize a URN, `%<urn>` or `<handle>@<peer_id>`
 pub fn router() -> axum::Router {
     axum::Router::new()
         .route(
             "/identities",
-            axum::routing::post(create_local).put(update_local),
+            axum::routing::get(list)
+                .post(create_local)
+                .put(update_local),
         )
+        .route("/identities/:urn", axum::routing::get(get))
         .route("/identities/remote/:urn", axum::routing::get(get_person))
+        .route("/resolve", axum::routing::get(resolve))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListQuery {
+    /// Only list persons whose handle starts with this prefix.
+    handle_prefix: Option<String>,
+}
+
+async fn list(
+    super::extract::UnsealedContext(ctx): super::extract::UnsealedContext,
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
+) -> Result<axum::response::Json<Vec<crate::identity::Person>>, super::Error> {
+    let persons = crate::identity::list(ctx.peer.librad_peer(), query.handle_prefix.as_deref())
+        .await
+        .context("failed to list identities")?;
+
+    Ok(axum::response::Json(persons))
+}
+
+async fn get(
+    super::extract::UnsealedContext(ctx): super::extract::UnsealedContext,
+    path: super::extract::Path<super::extract::Urn>,
+) -> Result<axum::response::Json<crate::identity::Person>, super::Error> {
+    let urn = path.0 .0;
+    match crate::identity::get(ctx.peer.librad_peer(), urn).await {
+        Ok(person) => Ok(axum::response::Json(person)),
+        Err(err @ crate::error::Error::IdentityNotFound(_)) => Err(super::Error::Custom {
+            status_code: http::StatusCode::NOT_FOUND,
+            variant: "IDENTITY_NOT_FOUND",
+            message: err.to_string(),
+            details: None,
+        }),
+        Err(err @ crate::error::Error::NotAPerson(_)) => Err(super::Error::Custom {
+            status_code: http::StatusCode::BAD_REQUEST,
+            variant: "NOT_A_PERSON",
+            message: err.to_string(),
+            details: None,
+        }),
+        Err(err) => Err(super::Error::internal(err)),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ResolveQuery {
+    /// A URN, a `%`-prefixed URN or a `<handle>@<peer_id>` identifier.
+    id: String,
+}
+
+async fn resolve(
+    super::extract::UnsealedContext(ctx): super::extract::UnsealedContext,
+    axum::extract::Query(query): axum::extract::Query<ResolveQuery>,
+) -> Result<axum::response::Json<crate::identity::Resolved>, super::Error> {
+    match crate::identity::resolve(ctx.peer.librad_peer(), &query.id).await {
+        Ok(resolved) => Ok(axum::response::Json(resolved)),
+        Err(err @ crate::error::Error::InvalidId { .. }) => Err(super::Error::Custom {
+            status_code: http::StatusCode::BAD_REQUEST,
+            variant: "INVALID_ID",
+            message: err.to_string(),
+            details: None,
+        }),
+        Err(err @ crate::error::Error::IdentityNotFound(_)) => Err(super::Error::Custom {
+            status_code: http::StatusCode::NOT_FOUND,
+            variant: "IDENTITY_NOT_FOUND",
+            message: err.to_string(),
+            details: None,
+        }),
+        Err(err @ crate::error::Error::IdentifierNotFound(_)) => Err(super::Error::Custom {
+            status_code: http::StatusCode::NOT_FOUND,
+            variant: "IDENTIFIER_NOT_FOUND",
+            message: err.to_string(),
+            details: None,
+        }),
+        Err(err) => Err(super::Error::internal(err)),
+    }
 }
 
 async fn create_local(
@@ -7,12 +7,17 @@
 use axum::response::IntoResponse as _;
 
 /// Provides the following endpoints
-/// * `POST /keystore/unseal
 /// * `POST /keystore
 pub fn router() -> axum::Router {
-    axum::Router::new()
-        .route("/keystore", axum::routing::post(create))
-        .route("/keystore/unseal", axum::routing::post(unseal))
+    axum::Router::new().route("/keystore", axum::routing::post(create))
+}
+
+/// Provides the following endpoints
+/// * `POST /keystore/unseal
+///
+/// Unsealing does not modify state and is available in read-only mode, see [`router`].
+pub fn unseal_router() -> axum::Router {
+    axum::Router::new().route("/keystore/unseal", axum::routing::post(unseal))
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
@@ -6,10 +6,15 @@
 
 use anyhow::Context as _;
 
-/// Provides the following endpoint:
+/// Provides the following endpoints:
 /// * `GET /session` Returns information about the current identity if initialized.
+/// * `PATCH /session/settings` Partially updates the settings and returns the result.
+/// * `GET /self` Returns the identity of the local peer, see [`crate::identity::local_summary`].
 pub fn router() -> axum::Router {
-    axum::Router::new().route("/session", axum::routing::get(get))
+    axum::Router::new()
+        .route("/session", axum::routing::get(get))
+        .route("/session/settings", axum::routing::patch(patch_settings))
+        .route("/self", axum::routing::get(get_self))
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -58,3 +63,29 @@ async fn get(
         },
     }
 }
+
+async fn get_self(
+    super::extract::UnsealedContext(ctx): super::extract::UnsealedContext,
+) -> Result<impl axum::response::IntoResponse, super::Error> {
+    let summary = crate::identity::local_summary(ctx.peer.librad_peer(), &ctx.rest.store)
+        .await
+        .context("failed to summarise local peer")?;
+
+    Ok(axum::response::Json(summary))
+}
+
+async fn patch_settings(
+    super::extract::UnsealedContext(ctx): super::extract::UnsealedContext,
+    patch: axum::extract::Json<crate::session::settings::Patch>,
+) -> Result<impl axum::response::IntoResponse, super::Error> {
+    let settings = crate::session::patch_settings(&ctx.rest.store, patch.0)
+        .context("failed to patch settings")?
+        .ok_or_else(|| super::Error::Custom {
+            status_code: http::StatusCode::NOT_FOUND,
+            variant: "NOT_FOUND",
+            message: "no session found".to_string(),
+            details: None,
+        })?;
+
+    Ok(axum::response::Json(settings))
+}
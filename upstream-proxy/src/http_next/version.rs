@@ -0,0 +1,47 @@
+// Copyright © 2022 The Radicle Upstream Contributors
+//
+// This file is part of radicle-upstream, distributed under the GPLv3
+// with Radicle Linking Exception. For full terms see the included
+// LICENSE file.
+
+/// Provides `GET /version` endpoint that returns the version of the running proxy.
+pub fn router() -> axum::Router {
+    axum::Router::new().route("/version", axum::routing::get(get))
+}
+
+async fn get() -> impl axum::response::IntoResponse {
+    axum::response::Json(serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "gitSha": env!("GIT_HEAD"),
+        "libradVersion": env!("LIBRAD_VERSION"),
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt as _;
+
+    #[tokio::test]
+    async fn get_version() {
+        let request = http::Request::builder()
+            .uri("/version")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let have: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            have["version"],
+            serde_json::json!(env!("CARGO_PKG_VERSION"))
+        );
+        assert!(!have["gitSha"].as_str().unwrap().is_empty());
+        assert_eq!(
+            have["libradVersion"],
+            serde_json::json!(env!("LIBRAD_VERSION"))
+        );
+    }
+}
@@ -181,3 +181,353 @@ pub async fn update(
         .ok_or(crate::daemon::state::Error::MissingOwner)?;
     Ok((peer.peer_id(), user.into_inner().into_inner()).into())
 }
+
+/// Returns the person identity for `urn`.
+///
+/// # Errors
+///
+///   * There is no identity for `urn`, see [`error::Error::IdentityNotFound`].
+///   * The identity is not a person, see [`error::Error::NotAPerson`].
+///   * The identity can't be read from the monorepo.
+pub async fn get(
+    peer: &crate::daemon::net::peer::Peer<BoxedSigner>,
+    urn: Urn,
+) -> Result<Person, error::Error> {
+    match crate::daemon::state::get_identity(peer, urn.clone()).await? {
+        Some(crate::daemon::identities::SomeIdentity::Person(person)) => Ok(Person::from(person)),
+        Some(_) => Err(error::Error::NotAPerson(urn)),
+        None => Err(error::Error::IdentityNotFound(urn)),
+    }
+}
+
+/// Lists all person identities in the monorepo whose handle starts with `handle_prefix`,
+/// ordered by handle. All persons are returned if `handle_prefix` is `None`.
+///
+/// # Errors
+///
+/// Errors if the identities can't be read from the monorepo.
+pub async fn list(
+    peer: &crate::daemon::net::peer::Peer<BoxedSigner>,
+    handle_prefix: Option<&str>,
+) -> Result<Vec<Person>, error::Error> {
+    let mut persons = crate::daemon::state::list_identities(peer)
+        .await?
+        .into_iter()
+        .filter_map(|identity| match identity {
+            crate::daemon::identities::SomeIdentity::Person(person) => Some(Person::from(person)),
+            _ => None,
+        })
+        .filter(|person| {
+            handle_prefix.map_or(true, |prefix| person.metadata.handle.starts_with(prefix))
+        })
+        .collect::<Vec<_>>();
+    persons.sort_by_cached_key(|person| (person.metadata.handle.clone(), person.urn.to_string()));
+
+    Ok(persons)
+}
+
+/// The kind of identity an id refers to, see [`resolve`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Kind {
+    /// A project identity.
+    Project,
+    /// A person identity.
+    User,
+}
+
+/// An id in any of its accepted forms, normalized by [`resolve`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resolved {
+    /// The URN of the identity.
+    pub urn: Urn,
+    /// Whether the identity is a project or a person.
+    pub kind: Kind,
+    /// The form used for sharing the identity: `%<urn>` for projects and `<handle>@<peer_id>`
+    /// for persons.
+    pub shareable_id: String,
+}
+
+/// Resolves `id` to the identity it refers to. `id` may be a bare URN, a `%`-prefixed URN or a
+/// `<handle>@<peer_id>` [`crate::identifier::Identifier`] of a person.
+///
+/// # Errors
+///
+///   * `id` is malformed, see [`error::Error::InvalidId`].
+///   * There is no identity for the URN, see [`error::Error::IdentityNotFound`].
+///   * No known person matches the identifier, see [`error::Error::IdentifierNotFound`].
+///   * The identities can't be read from the monorepo.
+pub async fn resolve(
+    peer: &crate::daemon::net::peer::Peer<BoxedSigner>,
+    id: &str,
+) -> Result<Resolved, error::Error> {
+    if id.contains('@') {
+        let identifier =
+            id.parse::<crate::identifier::Identifier>()
+                .map_err(|err| error::Error::InvalidId {
+                    id: id.to_string(),
+                    reason: err.to_string(),
+                })?;
+        let person = list(peer, Some(&identifier.handle))
+            .await?
+            .into_iter()
+            .find(|person| {
+                person.metadata.handle == identifier.handle
+                    && person.peer_ids.contains(&identifier.peer_id)
+            })
+            .ok_or_else(|| error::Error::IdentifierNotFound(identifier.clone()))?;
+
+        return Ok(Resolved {
+            urn: person.urn,
+            kind: Kind::User,
+            shareable_id: identifier.to_string(),
+        });
+    }
+
+    let urn = crate::project::parse_shareable_id(id)?;
+    match crate::daemon::state::get_identity(peer, urn.clone()).await? {
+        Some(crate::daemon::identities::SomeIdentity::Project(_)) => Ok(Resolved {
+            shareable_id: format!("%{}", urn),
+            urn,
+            kind: Kind::Project,
+        }),
+        Some(crate::daemon::identities::SomeIdentity::Person(person)) => {
+            let person = Person::from(person);
+            let shareable_id = person.peer_ids.first().map_or_else(
+                || urn.to_string(),
+                |peer_id| {
+                    crate::identifier::Identifier {
+                        handle: person.metadata.handle.clone(),
+                        peer_id: *peer_id,
+                    }
+                    .to_string()
+                },
+            );
+            Ok(Resolved {
+                urn,
+                kind: Kind::User,
+                shareable_id,
+            })
+        },
+        _ => Err(error::Error::IdentityNotFound(urn)),
+    }
+}
+
+/// The identity and state of the local peer, see [`local_summary`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSummary {
+    /// The Peer ID of the local peer.
+    pub peer_id: PeerId,
+    /// The default owner of the local peer, `None` until an identity was created.
+    pub owner: Option<Identity>,
+    /// Number of seeds configured in the session settings.
+    pub seed_count: usize,
+    /// Number of projects owned by the default owner.
+    pub project_count: usize,
+}
+
+/// Summarises the identity and state of the local peer, i.e. what is shown for the account of
+/// the user. On a fresh peer without a default owner `owner` is `None` and there are no
+/// projects.
+///
+/// # Errors
+///
+/// Errors if the identities can't be read from the monorepo or the session can't be read from
+/// `store`.
+pub async fn local_summary(
+    peer: &crate::daemon::net::peer::Peer<BoxedSigner>,
+    store: &kv::Store,
+) -> Result<LocalSummary, error::Error> {
+    let owner = crate::daemon::state::default_owner(peer).await?;
+    let project_count = match owner {
+        Some(_) => crate::daemon::state::list_projects(peer).await?.len(),
+        None => 0,
+    };
+    let seed_count =
+        crate::session::get_current(store)?.map_or(0, |session| session.settings.coco.seeds.len());
+
+    Ok(LocalSummary {
+        peer_id: peer.peer_id(),
+        owner: owner.map(|owner| Identity::from((peer.peer_id(), owner.into_inner().into_inner()))),
+        seed_count,
+        project_count,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn local_summary_with_and_without_owner() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        assert_eq!(
+            local_summary(peer, &test_peer.store).await.unwrap(),
+            LocalSummary {
+                peer_id: peer.peer_id(),
+                owner: None,
+                seed_count: 0,
+                project_count: 0,
+            }
+        );
+
+        let owner = crate::daemon::state::init_owner(
+            peer,
+            Metadata {
+                handle: "alice".to_string(),
+                ethereum: None,
+            },
+        )
+        .await
+        .unwrap();
+        crate::peer::test::create_project(peer, &owner, "alpha").await;
+        let owner_urn = owner.urn();
+        crate::session::initialize(&test_peer.store, &["seed.example.com".to_string()]).unwrap();
+
+        let summary = local_summary(peer, &test_peer.store).await.unwrap();
+        let owner = summary.owner.unwrap();
+        assert_eq!(owner.urn, owner_urn);
+        assert_eq!(owner.peer_id, peer.peer_id());
+        assert_eq!(owner.metadata.handle, "alice");
+        assert_eq!(summary.seed_count, 1);
+        assert_eq!(summary.project_count, 1);
+    }
+
+    #[tokio::test]
+    async fn get_person_or_error() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        let owner = crate::daemon::state::init_owner(
+            peer,
+            Metadata {
+                handle: "alice".to_string(),
+                ethereum: None,
+            },
+        )
+        .await
+        .unwrap();
+        let person = get(peer, owner.urn()).await.unwrap();
+        assert_eq!(person.urn, owner.urn());
+        assert_eq!(person.metadata.handle, "alice");
+
+        let project = crate::peer::test::create_project(peer, &owner, "alpha").await;
+        assert!(matches!(
+            get(peer, project.urn()).await,
+            Err(error::Error::NotAPerson(_))
+        ));
+
+        let missing = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        assert!(matches!(
+            get(peer, missing).await,
+            Err(error::Error::IdentityNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn list_filters_by_handle_prefix() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        for handle in ["bob", "alice", "alfred"] {
+            crate::daemon::state::set_default_owner(peer, None::<crate::daemon::LocalIdentity>)
+                .await
+                .unwrap();
+            create(
+                peer,
+                Metadata {
+                    handle: handle.to_string(),
+                    ethereum: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let handles = |persons: Vec<Person>| {
+            persons
+                .into_iter()
+                .map(|person| person.metadata.handle)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            handles(list(peer, None).await.unwrap()),
+            vec!["alfred", "alice", "bob"]
+        );
+        assert_eq!(
+            handles(list(peer, Some("al")).await.unwrap()),
+            vec!["alfred", "alice"]
+        );
+        assert!(list(peer, Some("carol")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn resolve_every_form() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+
+        let owner = crate::daemon::state::init_owner(
+            peer,
+            Metadata {
+                handle: "alice".to_string(),
+                ethereum: None,
+            },
+        )
+        .await
+        .unwrap();
+        let owner_urn = owner.urn();
+        let project = crate::peer::test::create_project(peer, &owner, "alpha").await;
+
+        let project_resolved = Resolved {
+            urn: project.urn(),
+            kind: Kind::Project,
+            shareable_id: format!("%{}", project.urn()),
+        };
+        assert_eq!(
+            resolve(peer, &project.urn().to_string()).await.unwrap(),
+            project_resolved
+        );
+        assert_eq!(
+            resolve(peer, &format!("%{}", project.urn())).await.unwrap(),
+            project_resolved
+        );
+
+        let user_resolved = Resolved {
+            urn: owner_urn.clone(),
+            kind: Kind::User,
+            shareable_id: format!("alice@{}", peer.peer_id()),
+        };
+        assert_eq!(
+            resolve(peer, &owner_urn.to_string()).await.unwrap(),
+            user_resolved
+        );
+        assert_eq!(
+            resolve(peer, &format!("alice@{}", peer.peer_id()))
+                .await
+                .unwrap(),
+            user_resolved
+        );
+
+        assert!(matches!(
+            resolve(peer, &format!("bob@{}", peer.peer_id())).await,
+            Err(error::Error::IdentifierNotFound(_))
+        ));
+        let missing = Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()));
+        assert!(matches!(
+            resolve(peer, &missing.to_string()).await,
+            Err(error::Error::IdentityNotFound(_))
+        ));
+        for id in ["rad:git:nope", "%", "alice@nope", "alice@bob@carol"] {
+            assert!(
+                matches!(resolve(peer, id).await, Err(error::Error::InvalidId { .. })),
+                "{}",
+                id
+            );
+        }
+    }
+}
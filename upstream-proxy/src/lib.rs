@@ -36,12 +36,15 @@ mod http;
 mod identifier;
 mod identity;
 mod keystore;
+mod logging;
 mod notification;
 mod patch;
 mod process;
 mod project;
 mod service;
 mod session;
+mod signed_commit;
+mod source;
 mod watch_monorepo;
 
 pub use cli::Args;
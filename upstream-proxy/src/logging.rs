@@ -0,0 +1,152 @@
+// Copyright © 2022 The Radicle Upstream Contributors
+//
+// This file is part of radicle-upstream, distributed under the GPLv3
+// with Radicle Linking Exception. For full terms see the included
+// LICENSE file.
+
+//! Setup of the [`tracing`] subscriber that writes the log records of the process.
+
+use tracing_subscriber::{filter::LevelFilter, fmt::MakeWriter, EnvFilter};
+
+/// Output format of log records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum LogFormat {
+    /// One line per record with all fields.
+    Full,
+    /// One line per record, leaving out the fields of spans.
+    Compact,
+    /// Several lines per record, meant for humans.
+    Pretty,
+    /// One JSON object per line. The message and the fields of a record are nested in `fields`.
+    Json,
+}
+
+impl LogFormat {
+    /// The format named by the `TRACING_FMT` environment variable. Unknown values are ignored
+    /// instead of failing startup, as they were before the format could be set on the command
+    /// line.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        Self::named(&std::env::var("TRACING_FMT").ok()?)
+    }
+
+    /// The format called `name`, ignoring case.
+    fn named(name: &str) -> Option<Self> {
+        <Self as clap::ArgEnum>::from_str(name, true).ok()
+    }
+}
+
+/// Installs the global subscriber, which writes records of at least `level` to stderr in the
+/// given `format`. If `dev` is set, debug records of the proxy are included as well.
+///
+/// The `RUST_LOG` environment variable overrides `level` and `dev` if it is set.
+pub fn init(format: LogFormat, level: LevelFilter, dev: bool) {
+    use tracing_subscriber::util::SubscriberInitExt as _;
+
+    subscriber(format, env_filter(level, dev), std::io::stderr).init();
+}
+
+/// Builds the filter for [`init`].
+fn env_filter(level: LevelFilter, dev: bool) -> EnvFilter {
+    if let Ok(value) = std::env::var("RUST_LOG") {
+        return EnvFilter::new(value);
+    }
+
+    let mut env_filter = EnvFilter::default().add_directive(level.into());
+
+    let mut directives = vec![
+        "quinn=warn",
+        // Silence some noisy debug statements.
+        "librad::net::protocol::io::streams=warn",
+        "librad::net::protocol::io::recv::git=warn",
+    ];
+
+    if dev {
+        directives.extend(["upstream_proxy=debug", "crate::daemon=debug"])
+    }
+
+    for directive in directives {
+        env_filter = env_filter.add_directive(directive.parse().expect("invalid log directive"))
+    }
+
+    env_filter
+}
+
+/// Builds a subscriber that writes the records passing `filter` to `writer` in `format`.
+fn subscriber<W>(
+    format: LogFormat,
+    filter: EnvFilter,
+    writer: W,
+) -> Box<dyn tracing::Subscriber + Send + Sync>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_env_filter(filter);
+
+    match format {
+        LogFormat::Full => Box::new(builder.finish()),
+        LogFormat::Compact => Box::new(builder.compact().finish()),
+        LogFormat::Pretty => Box::new(builder.pretty().finish()),
+        LogFormat::Json => Box::new(builder.json().finish()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects everything that is written into a shared buffer.
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn unknown_format_names_are_ignored() {
+        assert_eq!(LogFormat::named("json"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::named("Pretty"), Some(LogFormat::Pretty));
+        assert_eq!(LogFormat::named("yaml"), None);
+        assert_eq!(LogFormat::named(""), None);
+    }
+
+    #[test]
+    fn json_records_contain_fields() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = subscriber(
+            LogFormat::Json,
+            EnvFilter::default().add_directive(LevelFilter::INFO.into()),
+            {
+                let buffer = buffer.clone();
+                move || Buffer(buffer.clone())
+            },
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(peer_id = "hyb", urn = "rad:git:hnrk", "cloned project");
+            tracing::debug!("filtered out");
+        });
+
+        let output = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let records = output
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["level"], "INFO");
+        assert_eq!(records[0]["fields"]["message"], "cloned project");
+        assert_eq!(records[0]["fields"]["peer_id"], "hyb");
+        assert_eq!(records[0]["fields"]["urn"], "rad:git:hnrk");
+    }
+}
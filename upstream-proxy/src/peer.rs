@@ -36,6 +36,9 @@ impl Peer {
 
     /// Run a blocking function that requires access to the monorepo.
     ///
+    /// `f` runs in the current [`tracing::Span`], so its events are attributed to the request
+    /// that caused them.
+    ///
     /// Panics if the async task cannot be spawned.
     pub async fn monorepo_unblock<T: Send + 'static>(
         &self,
@@ -47,7 +50,9 @@ impl Peer {
             .paths
             .git_dir()
             .to_owned();
+        let span = tracing::Span::current();
         tokio::task::spawn_blocking(move || {
+            let _span = span.enter();
             let repo = git2::Repository::open(monorepo_path).context("failed to open monorepo")?;
             f(repo)
         })
@@ -60,7 +65,9 @@ pub struct Config {
     pub signer: link_crypto::BoxedSigner,
     pub paths: librad::paths::Paths,
     pub listen: std::net::SocketAddr,
+    pub gossip: crate::daemon::config::GossipConfig,
     pub store: kv::Store,
+    pub startup_requests: Vec<librad::git::Urn>,
 }
 
 pub struct Runner {
@@ -90,13 +97,24 @@ impl Runner {
 }
 
 pub fn create(config: Config) -> anyhow::Result<(Peer, Runner)> {
-    let daemon_config =
-        crate::daemon::config::configure(config.paths.clone(), config.signer, config.listen);
+    let daemon_config = crate::daemon::config::configure(
+        config.paths.clone(),
+        config.signer,
+        config.listen,
+        config.gossip,
+    )
+    .context("invalid peer configuration")?;
     let daemon_peer = crate::daemon::Peer::new(
         daemon_config,
         crate::daemon::config::NoDiscovery,
         config.store,
-        crate::daemon::RunConfig::default(),
+        crate::daemon::RunConfig {
+            waiting_room: crate::daemon::peer::run_config::WaitingRoom {
+                startup_requests: config.startup_requests,
+                ..crate::daemon::peer::run_config::WaitingRoom::default()
+            },
+            ..crate::daemon::RunConfig::default()
+        },
     )
     .context("failed to initialize crate::daemon peer")?;
 
@@ -165,23 +183,174 @@ pub mod test {
     impl TestPeer {
         pub fn new() -> Self {
             let temp_dir = tempfile::TempDir::new().unwrap();
-            let signer = link_crypto::BoxedSigner::from(link_crypto::SecretKey::new());
-            let profile =
-                librad::profile::Profile::from_root(&temp_dir.path().join("lnk_home"), None)
-                    .unwrap();
             let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
-            let (peer, _) = create(Config {
-                signer,
-                paths: profile.paths().clone(),
-                listen: "127.0.0.1:0".parse().unwrap(),
-                store: store.clone(),
-            })
-            .unwrap();
+            let (peer, _) = create(config(&temp_dir, store.clone())).unwrap();
             TestPeer {
                 peer,
                 temp_dir,
                 store,
             }
         }
+
+        /// Like [`TestPeer::new`] but also runs the peer until the returned sender is dropped,
+        /// so that it can replicate from and to other peers. Resolves once the peer listens on
+        /// the returned addresses.
+        pub async fn running() -> (
+            Self,
+            Vec<std::net::SocketAddr>,
+            futures::channel::oneshot::Sender<()>,
+        ) {
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+            let (mut peer, runner) = create(config(&temp_dir, store.clone())).unwrap();
+
+            let (shutdown_tx, shutdown_rx) = futures::channel::oneshot::channel::<()>();
+            tokio::spawn(runner.run(shutdown_rx.map(|_| ()).boxed()));
+            let listen_addrs = listen_addrs(&mut peer).await;
+
+            (
+                TestPeer {
+                    peer,
+                    temp_dir,
+                    store,
+                },
+                listen_addrs,
+                shutdown_tx,
+            )
+        }
+    }
+
+    /// Creates the identity of a project called `name` with the default branch `main` whose
+    /// `rad/self` points to `owner`. The project has no working copy and no branches.
+    pub async fn create_project(
+        peer: &librad::net::peer::Peer<link_crypto::BoxedSigner>,
+        owner: &crate::daemon::LocalIdentity,
+        name: &str,
+    ) -> link_identities::Project {
+        use librad::{canonical::Cstring, identities::payload};
+
+        let owner = owner.clone();
+        let payload = payload::Project {
+            default_branch: Some(Cstring::from("main".to_string())),
+            description: Some(Cstring::from("A project".to_string())),
+            name: Cstring::from(name.to_string()),
+        };
+        let delegations =
+            librad::identities::delegation::Indirect::from(owner.clone().into_inner().into_inner());
+        peer.using_storage(move |store| {
+            librad::git::identities::project::create(store, owner, payload, delegations)
+        })
+        .await
+        .unwrap()
+        .unwrap()
+    }
+
+    /// Commits an empty tree onto the branch `name` of the project `urn` in the monorepo of
+    /// `peer` and returns the commit. The branch must not exist yet.
+    pub async fn commit_empty(
+        peer: &librad::net::peer::Peer<link_crypto::BoxedSigner>,
+        urn: &librad::git::Urn,
+        name: &str,
+    ) -> git2::Oid {
+        let branch = format!("refs/namespaces/{}/refs/heads/{}", urn.encode_id(), name);
+        peer.using_storage(move |store| {
+            let repo = store.as_raw();
+            let tree = repo.find_tree(repo.treebuilder(None)?.write()?)?;
+            let signature = git2::Signature::now("alice", "alice@example.com")?;
+            repo.commit(
+                Some(&branch),
+                &signature,
+                &signature,
+                "Initial commit",
+                &tree,
+                &[],
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap()
+    }
+
+    /// Waits until the running `peer` listens and returns its addresses.
+    async fn listen_addrs(peer: &mut Peer) -> Vec<std::net::SocketAddr> {
+        // Control requests are only answered by a running peer.
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                let addrs = peer.daemon_control().listen_addrs().await;
+                if !addrs.is_empty() {
+                    break addrs;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("peer did not start listening")
+    }
+
+    /// Peer [`Config`] with a new key that stores its state in `temp_dir`.
+    fn config(temp_dir: &tempfile::TempDir, store: kv::Store) -> Config {
+        let signer = link_crypto::BoxedSigner::from(link_crypto::SecretKey::new());
+        let profile =
+            librad::profile::Profile::from_root(&temp_dir.path().join("lnk_home"), None).unwrap();
+        Config {
+            signer,
+            paths: profile.paths().clone(),
+            listen: "127.0.0.1:0".parse().unwrap(),
+            gossip: crate::daemon::config::GossipConfig::default(),
+            store,
+            startup_requests: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn runner_drives_peer() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+        let (mut peer, runner) = create(config(&temp_dir, store)).unwrap();
+
+        let (shutdown_tx, shutdown_rx) = futures::channel::oneshot::channel::<()>();
+        let run = tokio::spawn(runner.run(shutdown_rx.map(|_| ()).boxed()));
+
+        let listen_addrs = listen_addrs(&mut peer).await;
+        assert!(listen_addrs.iter().all(|addr| addr.ip().is_loopback()));
+
+        drop(shutdown_tx);
+        run.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn runner_uses_gossip_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+        let gossip = crate::daemon::config::GossipConfig {
+            max_active: Some(2),
+            max_passive: Some(4),
+            shuffle_interval: Some(std::time::Duration::from_secs(120)),
+            promote_interval: Some(std::time::Duration::from_secs(30)),
+        };
+        let (mut peer, runner) = create(Config {
+            gossip,
+            ..config(&temp_dir, store)
+        })
+        .unwrap();
+
+        let (shutdown_tx, shutdown_rx) = futures::channel::oneshot::channel::<()>();
+        let run = tokio::spawn(runner.run(shutdown_rx.map(|_| ()).boxed()));
+        listen_addrs(&mut peer).await;
+
+        let membership = &peer.librad_peer().protocol_config().membership;
+        assert_eq!(membership.max_active, 2);
+        assert_eq!(membership.max_passive, 4);
+        assert_eq!(
+            membership.shuffle_interval,
+            std::time::Duration::from_secs(120)
+        );
+        assert_eq!(
+            membership.promote_interval,
+            std::time::Duration::from_secs(30)
+        );
+
+        drop(shutdown_tx);
+        run.await.unwrap().unwrap();
     }
 }
@@ -106,7 +106,7 @@ async fn add_key_to_ssh_agent(paths: &librad::paths::Paths, key: link_crypto::Se
     .await)
         .is_err()
     {
-        tracing::warn!("could not add ssh key, is ssh-agent running?");
+        tracing::warn!(%peer_id, "could not add ssh key, is ssh-agent running?");
     }
 }
 
@@ -154,12 +154,27 @@ async fn run_session(
         })
     };
 
+    let startup_requests = crate::session::get_current(&store)?
+        .map(|session| session.settings.coco.startup_requests)
+        .unwrap_or_default();
+
     let ctx = if let Some(signer) = maybe_signer {
         let (peer, peer_runner) = crate::peer::create(crate::peer::Config {
             paths: paths.clone(),
             signer,
             store: store.clone(),
             listen: args.peer_listen,
+            gossip: crate::daemon::config::GossipConfig {
+                max_active: args.gossip_max_active,
+                max_passive: args.gossip_max_passive,
+                shuffle_interval: args
+                    .gossip_shuffle_interval
+                    .map(std::time::Duration::from_secs),
+                promote_interval: args
+                    .gossip_promote_interval
+                    .map(std::time::Duration::from_secs),
+            },
+            startup_requests: startup_requests.clone(),
         })?;
 
         let (git_fetch, git_fetch_runner) = crate::git_fetch::create(
@@ -169,6 +184,9 @@ async fn run_session(
             &store,
         )
         .await?;
+        for urn in &startup_requests {
+            git_fetch.add(urn.id).await;
+        }
 
         let (watch_monorepo, watch_monorepo_runner) = crate::watch_monorepo::create(peer.clone());
 
@@ -206,8 +224,25 @@ async fn run_session(
     shutdown_runner.add_with_shutdown({
         let ctx = ctx.clone();
         let http_listen_addr = args.http_listen;
+        let cors = crate::http_next::CorsConfig {
+            allowed_origins: args.http_allowed_origins.clone(),
+            allow_credentials: !args.http_disallow_credentials,
+        };
+        let config = crate::http::Config {
+            body_limits: crate::http::BodyLimits {
+                json: args.http_max_json_body_bytes,
+                bundle: args.http_max_bundle_body_bytes,
+            },
+            history_walks: crate::http::HistoryWalkLimits {
+                max_concurrent: args.http_max_concurrent_history_walks,
+                timeout: std::time::Duration::from_secs(args.http_history_walk_timeout_secs),
+            },
+            read_only: args.read_only,
+            debug_endpoints: args.http_debug_endpoints,
+            post_checkout: args.post_checkout.clone(),
+        };
         move |shutdown_signal| {
-            crate::http_next::serve(ctx, http_listen_addr, shutdown_signal)
+            crate::http_next::serve(ctx, http_listen_addr, cors, config, shutdown_signal)
                 .map_err(|e| e.context("server failed"))
                 .boxed()
         }
@@ -215,6 +250,10 @@ async fn run_session(
 
     let results = shutdown_runner.run().await;
 
+    // The store only flushes periodically, so changes made right before the shutdown would be
+    // lost if the process exits.
+    flush_store(&store)?;
+
     for result in results {
         result?
     }
@@ -222,6 +261,13 @@ async fn run_session(
     Ok(())
 }
 
+/// Writes all pending changes of `store` to disk.
+fn flush_store(store: &kv::Store) -> Result<(), kv::Error> {
+    let flushed = store.bucket::<kv::Raw, kv::Raw>(None)?.flush()?;
+    tracing::debug!(bytes = flushed, "flushed store");
+    Ok(())
+}
+
 async fn log_daemon_peer_events(events: impl Stream<Item = crate::daemon::peer::Event>) {
     events
         .for_each(|event| {
@@ -312,44 +358,13 @@ fn setup_logging(args: &Args) {
         std::env::set_var("RUST_BACKTRACE", "full");
     }
 
-    let env_filter = if let Ok(value) = std::env::var("RUST_LOG") {
-        tracing_subscriber::EnvFilter::new(value)
-    } else {
-        let mut env_filter = tracing_subscriber::EnvFilter::default();
-
-        let mut directives = vec![
-            "info",
-            "quinn=warn",
-            // Silence some noisy debug statements.
-            "librad::net::protocol::io::streams=warn",
-            "librad::net::protocol::io::recv::git=warn",
-        ];
-
-        if args.dev_log {
-            directives.extend(["upstream_proxy=debug", "crate::daemon=debug"])
-        }
-
-        for directive in directives {
-            env_filter = env_filter.add_directive(directive.parse().expect("invalid log directive"))
-        }
-
-        env_filter
-    };
-
-    let builder = tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .with_env_filter(env_filter);
-
-    match std::env::var("TRACING_FMT").as_deref() {
-        Ok("pretty") => builder.pretty().init(),
-        Ok("compact") => builder.compact().init(),
-        Ok("json") => builder.json().init(),
-        _ => {
-            if args.dev_log {
-                builder.pretty().init()
-            } else {
-                builder.init()
-            }
-        },
-    };
+    let format = args
+        .log_format
+        .or_else(crate::logging::LogFormat::from_env)
+        .unwrap_or(if args.dev_log {
+            crate::logging::LogFormat::Pretty
+        } else {
+            crate::logging::LogFormat::Full
+        });
+    crate::logging::init(format, args.log_level, args.dev_log);
 }
@@ -7,17 +7,19 @@
 //! Combine the domain `CoCo` domain specific understanding of a Project into a single
 //! abstraction.
 
-use std::{collections::HashMap, convert::TryFrom, ops::Deref};
+use std::{collections::HashMap, convert::TryFrom, ffi::OsStr, ops::Deref, path::Path};
 
 use anyhow::Context;
 use librad::PeerId;
 use serde::{Deserialize, Serialize};
 
 use link_identities::{git::Urn, Person, Project as LinkProject};
-use radicle_source::surf::vcs::git::Stats;
+use radicle_source::surf::vcs::git::RefScope;
 
 use crate::{browser, error, identity};
 
+pub mod idempotency;
+
 /// Object encapsulating project metadata.
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -83,19 +85,19 @@ pub struct Project {
     /// Attached metadata, mostly for human pleasure.
     pub metadata: Metadata,
     /// High-level statistics about the project.
-    pub stats: Stats,
+    pub stats: crate::source::Stats,
     /// The seed URL where the project is hosted.
     pub seed: Option<rad_common::Url>,
 }
 
 /// Construct a Project from its metadata and stats
-impl TryFrom<(LinkProject, Stats, Option<rad_common::Url>)> for Project {
+impl TryFrom<(LinkProject, crate::source::Stats, Option<rad_common::Url>)> for Project {
     type Error = error::Error;
 
-    /// Create a `Project` given a [`LinkProject`] and the [`Stats`]
+    /// Create a `Project` given a [`LinkProject`] and the [`crate::source::Stats`]
     /// for the repository.
     fn try_from(
-        (project, stats, seed): (LinkProject, Stats, Option<rad_common::Url>),
+        (project, stats, seed): (LinkProject, crate::source::Stats, Option<rad_common::Url>),
     ) -> Result<Self, Self::Error> {
         let urn = project.urn();
         let metadata = Metadata::try_from(project)?;
@@ -182,6 +184,10 @@ pub async fn list_link(
         .context("failed to list project identities")
 }
 
+/// Maximum number of commits taken into account for the stats of each project in
+/// [`Projects::list`] and [`list_owned`].
+const LIST_STATS_MAX_COMMITS: usize = 1000;
+
 impl Projects {
     /// List all the projects that are located on your device. These projects could either be
     /// "tracked" or "contributed".
@@ -220,7 +226,14 @@ impl Projects {
                 };
 
             let stats =
-                match browser::using(peer, default_branch, |browser| Ok(browser.get_stats()?)) {
+                match browser::using_commit(peer, default_branch.clone(), None, |repo, head| {
+                    crate::source::stats_bounded(
+                        repo,
+                        &default_branch,
+                        head,
+                        LIST_STATS_MAX_COMMITS,
+                    )
+                }) {
                     Err(err) => {
                         tracing::warn!(project_urn = %urn, ?err, "cannot get project stats");
                         projects.failures.push(Failure::Stats { urn, metadata });
@@ -266,6 +279,20 @@ impl Projects {
     }
 }
 
+/// Parses a shareable project id into a [`Urn`]. Both the `%`-prefixed form used when sharing
+/// a project (`%rad:git:<hash>`) and the bare URN are accepted.
+///
+/// # Errors
+///
+///   * [`error::Error::InvalidId`] if `id` is not a valid project URN.
+pub fn parse_shareable_id(id: &str) -> Result<Urn, error::Error> {
+    let urn = id.strip_prefix('%').unwrap_or(id);
+    urn.parse::<Urn>().map_err(|err| error::Error::InvalidId {
+        id: id.to_string(),
+        reason: err.to_string(),
+    })
+}
+
 /// Fetch the project with a given urn from a peer
 ///
 /// # Errors
@@ -285,7 +312,340 @@ pub async fn get(
         crate::daemon::state::find_default_branch(peer.librad_peer(), project_urn.clone()).await?;
     let project_stats = browser::using(peer, branch, |browser| Ok(browser.get_stats()?))?;
 
-    Project::try_from((project, project_stats, seed))
+    Project::try_from((project, crate::source::Stats::from(project_stats), seed))
+}
+
+/// Fetch the [`Metadata`] of the project with the given urn. Unlike [`get`] this doesn't walk
+/// the history of the project to compute its stats.
+///
+/// # Errors
+///
+///   * Failed to get the project.
+///   * The project has no default branch.
+pub async fn get_metadata(
+    peer: &crate::peer::Peer,
+    project_urn: Urn,
+) -> Result<Metadata, error::Error> {
+    let project = crate::daemon::state::get_project(peer.librad_peer(), project_urn)
+        .await?
+        .ok_or(crate::error::Error::ProjectNotFound)?;
+
+    Metadata::try_from(project)
+}
+
+/// Writes the branches and tags of the project `urn` into the git bundle `out`, with `HEAD`
+/// pointing to the default branch, so the bundle can be cloned with `git clone <out>`.
+///
+/// The refs are copied into a temporary repository first since the bundle would otherwise
+/// carry the namespaced ref names of the monorepo.
+///
+/// # Errors
+///
+///   * The project doesn't exist or has no default branch.
+///   * The default branch has no commits yet.
+///   * Running `git` failed.
+pub async fn export_bundle(
+    peer: &crate::peer::Peer,
+    urn: Urn,
+    out: &Path,
+) -> Result<(), error::Error> {
+    let project = crate::daemon::state::get_project(peer.librad_peer(), urn.clone())
+        .await?
+        .ok_or(crate::error::Error::ProjectNotFound)?;
+    let default_branch = project
+        .subject()
+        .default_branch
+        .clone()
+        .ok_or(error::Error::MissingDefaultBranch)?;
+
+    let id = urn.encode_id();
+    let monorepo_path = peer.paths().git_dir().to_owned();
+    let staging = tempfile::tempdir()?;
+    let heads = format!("+refs/namespaces/{id}/refs/heads/*:refs/heads/*");
+    let tags = format!("+refs/namespaces/{id}/refs/tags/*:refs/tags/*");
+    let head = format!("refs/heads/{default_branch}");
+
+    let has_default_branch = git2::Repository::open(&monorepo_path)
+        .context("failed to open monorepo")?
+        .find_reference(&format!("refs/namespaces/{id}/{head}"))
+        .is_ok();
+    if !has_default_branch {
+        return Err(error::Error::EmptyDefaultBranch(default_branch.to_string()));
+    }
+
+    git(staging.path(), &["init".as_ref(), "--bare".as_ref()]).await?;
+    git(
+        staging.path(),
+        &[
+            "fetch".as_ref(),
+            monorepo_path.as_os_str(),
+            heads.as_ref(),
+            tags.as_ref(),
+        ],
+    )
+    .await?;
+    git(
+        staging.path(),
+        &["symbolic-ref".as_ref(), "HEAD".as_ref(), head.as_ref()],
+    )
+    .await?;
+    git(
+        staging.path(),
+        &[
+            "bundle".as_ref(),
+            "create".as_ref(),
+            out.as_os_str(),
+            "--all".as_ref(),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Format of a source archive written by [`export_archive`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    /// A gzip compressed tarball.
+    TarGz,
+    /// A zip archive.
+    Zip,
+}
+
+impl Default for ArchiveFormat {
+    fn default() -> Self {
+        Self::TarGz
+    }
+}
+
+impl ArchiveFormat {
+    /// The file extension of archives in this format, without the leading dot.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+
+    /// The media type of archives in this format.
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::TarGz => "application/gzip",
+            Self::Zip => "application/zip",
+        }
+    }
+}
+
+/// Writes the tree of the commit `oid` into the archive `out`, with every path below the
+/// directory `prefix`. Unlike [`export_bundle`] the archive carries no history and can be
+/// unpacked without git.
+///
+/// # Errors
+///
+///   * `prefix` is not a single directory name, see
+///     [`crate::daemon::project::checkout::directory_name`].
+///   * Running `git` failed, e.g. because `oid` is not a commit in the monorepo.
+pub async fn export_archive(
+    peer: &crate::peer::Peer,
+    oid: git2::Oid,
+    prefix: &str,
+    format: ArchiveFormat,
+    out: &Path,
+) -> Result<(), error::Error> {
+    crate::daemon::project::checkout::directory_name(prefix)
+        .map_err(crate::daemon::state::Error::from)?;
+
+    let monorepo_path = peer.paths().git_dir().to_owned();
+    let format = format!("--format={}", format.extension());
+    let prefix = format!("--prefix={prefix}/");
+    let oid = oid.to_string();
+
+    git(
+        &monorepo_path,
+        &[
+            "archive".as_ref(),
+            format.as_ref(),
+            prefix.as_ref(),
+            "--output".as_ref(),
+            out.as_os_str(),
+            oid.as_ref(),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Creates a new project called `name` owned by `owner` from the branches of the git bundle
+/// `bundle`, e.g. one written by [`export_bundle`].
+///
+/// The bundle is unpacked into a temporary working copy, which is then set up like any existing
+/// repository passed to [`crate::daemon::state::init_project`]. The working copy, together with
+/// the `rad` remote that `init_project` adds to it, is removed once the project has been created,
+/// so the imported project only lives in the monorepo. Use
+/// [`crate::daemon::state::checkout`] to get a working copy of it.
+///
+/// # Errors
+///
+///   * `name` is not a valid directory name.
+///   * The bundle could not be unpacked.
+///   * The bundle doesn't contain `default_branch`.
+///   * Creating the project failed.
+pub async fn import_bundle(
+    peer: &crate::peer::Peer,
+    owner: &crate::daemon::LocalIdentity,
+    bundle: &Path,
+    name: &str,
+    description: String,
+    default_branch: librad::git_ext::OneLevel,
+) -> Result<LinkProject, error::Error> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(std::path::is_separator) {
+        return Err(error::Error::InvalidProjectName(name.to_string()));
+    }
+
+    let staging = tempfile::tempdir()?;
+    let path = staging.path().join(name);
+    git(
+        staging.path(),
+        &["init".as_ref(), "--quiet".as_ref(), name.as_ref()],
+    )
+    .await?;
+    git(
+        &path,
+        &[
+            "fetch".as_ref(),
+            "--quiet".as_ref(),
+            "--update-head-ok".as_ref(),
+            bundle.as_os_str(),
+            "+refs/heads/*:refs/heads/*".as_ref(),
+            "+refs/tags/*:refs/tags/*".as_ref(),
+        ],
+    )
+    .await?;
+
+    let has_default_branch = git2::Repository::open(&path)
+        .context("failed to open unpacked bundle")?
+        .find_reference(&format!("refs/heads/{}", default_branch))
+        .is_ok();
+    if !has_default_branch {
+        return Err(crate::daemon::state::Error::from(
+            crate::daemon::project::create::Error::from(
+                crate::daemon::project::create::validation::Error::MissingDefaultBranch {
+                    repo_path: path,
+                    branch: default_branch.to_string(),
+                },
+            ),
+        )
+        .into());
+    }
+
+    let project = crate::daemon::state::init_project(
+        peer.librad_peer(),
+        owner,
+        crate::daemon::project::Create {
+            description,
+            default_branch,
+            repo: crate::daemon::project::Repo::Existing { path },
+            sign: false,
+        },
+    )
+    .await?;
+
+    Ok(project)
+}
+
+/// Runs `git` with `args` in `dir` and fails if it exits unsuccessfully.
+async fn git(dir: &Path, args: &[&OsStr]) -> anyhow::Result<()> {
+    let output = tokio::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await
+        .context("failed to spawn git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`git {:?}` failed with {}: {}",
+            args,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+/// This lists all the projects whose `rad/self` points to `owner`, or to the default owner of
+/// the local peer if `owner` is `None`.
+///
+/// Only the page of at most `limit` projects starting at `offset` is returned, together with the
+/// total number of projects. Stats are computed for the projects on the page only and take at
+/// most [`LIST_STATS_MAX_COMMITS`] commits of each history into account.
+///
+/// # Errors
+///
+/// * We couldn't get a project list.
+/// * We couldn't get project stats.
+pub async fn list_owned(
+    peer: &crate::peer::Peer,
+    owner: Option<Urn>,
+    offset: usize,
+    limit: usize,
+) -> Result<(Vec<Project>, usize), error::Error> {
+    let mut projects = vec![];
+
+    let (page, total) =
+        crate::daemon::state::list_projects_paged(peer.librad_peer(), owner, offset, limit).await?;
+    for project in page {
+        let branch =
+            crate::daemon::state::find_default_branch(peer.librad_peer(), project.urn()).await?;
+        let stats = browser::using_commit(peer, branch.clone(), None, |repo, head| {
+            crate::source::stats_bounded(repo, &branch, head, LIST_STATS_MAX_COMMITS)
+        })?;
+        projects.push(Project::try_from((project, stats, None))?);
+    }
+
+    Ok((projects, total))
+}
+
+/// The local branches of a project, see [`list_owned_branches`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBranches {
+    /// Unique identifier of the project in the network.
+    pub urn: Urn,
+    /// The local branches of the project, ordered by name.
+    pub branches: Vec<radicle_source::Branch>,
+}
+
+/// This lists the local branches of all the projects owned by the default owner of the local
+/// peer, ordered like [`crate::daemon::state::list_projects`].
+///
+/// # Errors
+///
+/// * We couldn't get a project list.
+/// * We couldn't list the branches of a project.
+pub async fn list_owned_branches(
+    peer: &crate::peer::Peer,
+) -> Result<Vec<ProjectBranches>, error::Error> {
+    let mut projects = vec![];
+
+    for project in crate::daemon::state::list_projects(peer.librad_peer()).await? {
+        let branch =
+            crate::daemon::state::find_default_branch(peer.librad_peer(), project.urn()).await?;
+        let branches = browser::using(peer, branch, |browser| {
+            radicle_source::branches(browser, RefScope::Local)
+        })?;
+        projects.push(ProjectBranches {
+            urn: project.urn(),
+            branches,
+        });
+    }
+
+    Ok(projects)
 }
 
 /// This lists all the projects for a given `user`. This `user` should not be your particular
@@ -325,10 +685,375 @@ pub async fn list_for_user(
             )
             .await?;
             let stats = browser::using(peer, branch, |browser| Ok(browser.get_stats()?))?;
-            let full = Project::try_from((project, stats, None))?;
+            let full = Project::try_from((project, crate::source::Stats::from(stats), None))?;
 
             projects.push(full);
         }
     }
     Ok(projects)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn urn() -> Urn {
+        Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()))
+    }
+
+    /// Creates the identity of the project "alpha" owned by "alice", see
+    /// [`crate::peer::test::create_project`].
+    async fn create_project(test_peer: &crate::peer::test::TestPeer) -> LinkProject {
+        let peer = test_peer.peer.librad_peer();
+        let owner = crate::daemon::state::init_owner(
+            peer,
+            crate::identity::Metadata {
+                handle: "alice".to_string(),
+                ethereum: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        crate::peer::test::create_project(peer, &owner, "alpha").await
+    }
+
+    #[tokio::test]
+    async fn get_metadata_without_history() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        // The project has no branches, so computing its stats would fail.
+        let project = create_project(&test_peer).await;
+
+        let metadata = get_metadata(&test_peer.peer, project.urn()).await.unwrap();
+        assert_eq!(metadata.name, "alpha");
+        assert_eq!(metadata.description, "A project");
+        assert_eq!(metadata.default_branch, "main");
+
+        assert!(matches!(
+            get_metadata(&test_peer.peer, urn()).await,
+            Err(error::Error::ProjectNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn list_owned_only_computes_stats_of_page() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let peer = test_peer.peer.librad_peer();
+        let owner = crate::daemon::state::init_owner(
+            peer,
+            crate::identity::Metadata {
+                handle: "alice".to_string(),
+                ethereum: None,
+            },
+        )
+        .await
+        .unwrap();
+        let alpha = crate::peer::test::create_project(peer, &owner, "alpha").await;
+        let beta = crate::peer::test::create_project(peer, &owner, "beta").await;
+        // `gamma` has no branches, so computing its stats fails.
+        crate::peer::test::create_project(peer, &owner, "gamma").await;
+        crate::peer::test::commit_empty(test_peer.peer.librad_peer(), &alpha.urn(), "main").await;
+        crate::peer::test::commit_empty(test_peer.peer.librad_peer(), &beta.urn(), "main").await;
+
+        let (projects, total) = list_owned(&test_peer.peer, None, 0, 2).await.unwrap();
+        assert_eq!(
+            projects
+                .iter()
+                .map(|project| project.urn.clone())
+                .collect::<Vec<_>>(),
+            vec![alpha.urn(), beta.urn()]
+        );
+        assert_eq!(total, 3);
+        assert!(projects.iter().all(|project| project.stats.commits == 1));
+
+        assert!(list_owned(&test_peer.peer, None, 1, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn export_bundle_is_clonable() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let project = create_project(&test_peer).await;
+        let out = test_peer.temp_dir.path().join("alpha.bundle");
+        assert!(matches!(
+            export_bundle(&test_peer.peer, project.urn(), &out).await,
+            Err(error::Error::EmptyDefaultBranch(branch)) if branch == "main"
+        ));
+
+        let commit =
+            crate::peer::test::commit_empty(test_peer.peer.librad_peer(), &project.urn(), "main")
+                .await;
+        export_bundle(&test_peer.peer, project.urn(), &out)
+            .await
+            .unwrap();
+        assert!(std::fs::metadata(&out).unwrap().len() > 0);
+
+        // libgit2 can't read bundles, so clone with git itself.
+        let clone = test_peer.temp_dir.path().join("clone");
+        let status = std::process::Command::new("git")
+            .arg("clone")
+            .arg(&out)
+            .arg(&clone)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let repo = git2::Repository::open(&clone).unwrap();
+        assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+        assert_eq!(repo.head().unwrap().target(), Some(commit));
+
+        assert!(matches!(
+            export_bundle(&test_peer.peer, urn(), &out).await,
+            Err(error::Error::ProjectNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn export_archive_contains_tree() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let project = create_project(&test_peer).await;
+        let branch = format!(
+            "refs/namespaces/{}/refs/heads/main",
+            project.urn().encode_id()
+        );
+        let commit = test_peer
+            .peer
+            .monorepo_unblock(move |repo| {
+                let readme = repo.blob(b"# alpha\n")?;
+                let lib = repo.blob(b"pub fn alpha() {}\n")?;
+                let mut src = repo.treebuilder(None)?;
+                src.insert("lib.rs", lib, 0o100_644)?;
+                let src = src.write()?;
+                let mut root = repo.treebuilder(None)?;
+                root.insert("README.md", readme, 0o100_644)?;
+                root.insert("src", src, 0o040_000)?;
+                let tree = repo.find_tree(root.write()?)?;
+                let signature = git2::Signature::now("alice", "alice@example.com")?;
+                let commit = repo.commit(
+                    Some(&branch),
+                    &signature,
+                    &signature,
+                    "Initial commit",
+                    &tree,
+                    &[],
+                )?;
+                Ok(commit)
+            })
+            .await
+            .unwrap();
+
+        let out = test_peer.temp_dir.path().join("alpha-main.tar.gz");
+        export_archive(
+            &test_peer.peer,
+            commit,
+            "alpha-main",
+            ArchiveFormat::TarGz,
+            &out,
+        )
+        .await
+        .unwrap();
+
+        let output = std::process::Command::new("tar")
+            .arg("-tzf")
+            .arg(&out)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let mut paths = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "alpha-main/",
+                "alpha-main/README.md",
+                "alpha-main/src/",
+                "alpha-main/src/lib.rs",
+            ]
+        );
+
+        assert!(export_archive(
+            &test_peer.peer,
+            git2::Oid::zero(),
+            "alpha-main",
+            ArchiveFormat::TarGz,
+            &out,
+        )
+        .await
+        .is_err());
+
+        for prefix in ["", "..", "../alpha", "alpha/main"] {
+            assert!(
+                matches!(
+                    export_archive(&test_peer.peer, commit, prefix, ArchiveFormat::TarGz, &out)
+                        .await,
+                    Err(error::Error::State(crate::daemon::state::Error::Checkout(
+                        crate::daemon::project::checkout::Error::InvalidDirectoryName(_)
+                    )))
+                ),
+                "prefix {:?}",
+                prefix
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn import_bundle_round_trip() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let project = create_project(&test_peer).await;
+        let commit =
+            crate::peer::test::commit_empty(test_peer.peer.librad_peer(), &project.urn(), "main")
+                .await;
+        let owner = crate::daemon::state::default_owner(test_peer.peer.librad_peer())
+            .await
+            .unwrap()
+            .unwrap();
+        let bundle = test_peer.temp_dir.path().join("alpha.bundle");
+        export_bundle(&test_peer.peer, project.urn(), &bundle)
+            .await
+            .unwrap();
+
+        let main = librad::git_ext::OneLevel::from(librad::reflike!("main"));
+        let imported = import_bundle(
+            &test_peer.peer,
+            &owner,
+            &bundle,
+            "beta",
+            "Imported".to_string(),
+            main.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(imported.subject().name.to_string(), "beta");
+
+        let branch = format!(
+            "refs/namespaces/{}/refs/heads/main",
+            imported.urn().encode_id()
+        );
+        let head = test_peer
+            .peer
+            .monorepo_unblock(move |repo| Ok(repo.refname_to_id(&branch)?))
+            .await
+            .unwrap();
+        assert_eq!(head, commit);
+
+        let develop = librad::git_ext::OneLevel::from(librad::reflike!("develop"));
+        assert!(matches!(
+            import_bundle(
+                &test_peer.peer,
+                &owner,
+                &bundle,
+                "gamma",
+                String::new(),
+                develop,
+            )
+            .await,
+            Err(error::Error::State(crate::daemon::state::Error::Create(
+                crate::daemon::project::create::Error::Validation(
+                    crate::daemon::project::create::validation::Error::MissingDefaultBranch { .. }
+                )
+            )))
+        ));
+        assert!(matches!(
+            import_bundle(
+                &test_peer.peer,
+                &owner,
+                &bundle,
+                "../delta",
+                String::new(),
+                main
+            )
+            .await,
+            Err(error::Error::InvalidProjectName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn list_owned_branches_of_every_project() {
+        let test_peer = crate::peer::test::TestPeer::new();
+        let alpha = create_project(&test_peer).await;
+        crate::peer::test::commit_empty(test_peer.peer.librad_peer(), &alpha.urn(), "main").await;
+        let owner = crate::daemon::state::default_owner(test_peer.peer.librad_peer())
+            .await
+            .unwrap()
+            .unwrap();
+        let bundle = test_peer.temp_dir.path().join("alpha.bundle");
+        export_bundle(&test_peer.peer, alpha.urn(), &bundle)
+            .await
+            .unwrap();
+        let beta = import_bundle(
+            &test_peer.peer,
+            &owner,
+            &bundle,
+            "beta",
+            String::new(),
+            librad::git_ext::OneLevel::from(librad::reflike!("main")),
+        )
+        .await
+        .unwrap();
+
+        let feature = format!(
+            "refs/namespaces/{}/refs/heads/feature",
+            alpha.urn().encode_id()
+        );
+        let main = format!(
+            "refs/namespaces/{}/refs/heads/main",
+            alpha.urn().encode_id()
+        );
+        test_peer
+            .peer
+            .monorepo_unblock(move |repo| {
+                let head = repo.refname_to_id(&main)?;
+                repo.reference(&feature, head, false, "create feature branch")?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let branches = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| radicle_source::Branch::from((*name).to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            list_owned_branches(&test_peer.peer).await.unwrap(),
+            vec![
+                ProjectBranches {
+                    urn: alpha.urn(),
+                    branches: branches(&["feature", "main"]),
+                },
+                ProjectBranches {
+                    urn: beta.urn(),
+                    branches: branches(&["main"]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_shareable_id_prefixed() {
+        assert_eq!(parse_shareable_id(&format!("%{}", urn())).unwrap(), urn());
+    }
+
+    #[test]
+    fn parse_shareable_id_bare() {
+        assert_eq!(parse_shareable_id(&urn().to_string()).unwrap(), urn());
+    }
+
+    #[test]
+    fn parse_shareable_id_malformed() {
+        for id in ["", "%", "%%rad:git:hnrk", "not-a-urn", "rad:git:"] {
+            assert!(
+                matches!(
+                    parse_shareable_id(id),
+                    Err(error::Error::InvalidId { id: invalid, .. }) if invalid == id
+                ),
+                "expected `{}` to be rejected",
+                id
+            );
+        }
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright © 2022 The Radicle Upstream Contributors
+//
+// This file is part of radicle-upstream, distributed under the GPLv3
+// with Radicle Linking Exception. For full terms see the included
+// LICENSE file.
+
+//! Remember projects created by requests carrying an `Idempotency-Key` header so that retried
+//! requests return the original project instead of failing.
+//!
+//! A key is reserved with [`reserve`] before the project is created, so concurrent requests with
+//! the same key can't create two projects. The reservation stores a digest of the request body
+//! and reusing the key for a different body is rejected.
+
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use link_identities::git::Urn;
+
+/// Name for the bucket used in [`kv::Store`].
+const BUCKET_NAME: &str = "project_idempotency_keys";
+
+/// Time after which an idempotency key is forgotten.
+pub const TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The request an idempotency key was used for.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    /// Digest of the body of the original request, see [`request_digest`].
+    request_digest: String,
+    /// The project created by the original request, `None` while it is being created.
+    urn: Option<Urn>,
+    /// Time the original request was processed.
+    created_at: SystemTime,
+}
+
+/// Outcome of [`reserve`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reservation {
+    /// The key was not in use and is now reserved for the request. Call [`complete`] once the
+    /// project was created or [`release`] if creating it failed.
+    Reserved,
+    /// A request with the same body already created the project.
+    Created(Urn),
+    /// A request with the same key is still creating its project.
+    Pending,
+    /// The key was used for a request with a different body.
+    Mismatch,
+}
+
+/// Digest of a request body that is stored together with its idempotency key.
+#[must_use]
+pub fn request_digest(body: &impl Serialize) -> String {
+    use sha2::Digest as _;
+
+    let body = serde_json::to_vec(body).expect("request bodies serialize to JSON");
+    data_encoding::HEXLOWER.encode(&sha2::Sha256::digest(&body))
+}
+
+/// Reserves `key` for the request with `request_digest` at `now`, unless it is already used by
+/// another request that didn't expire. Expired keys are replaced.
+///
+/// Reserving is atomic, of two concurrent requests with the same key only one gets
+/// [`Reservation::Reserved`]. The other one is answered as if it arrived after the reservation.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+/// * if the storage of the entry fails
+pub fn reserve(
+    store: &kv::Store,
+    key: &str,
+    request_digest: &str,
+    now: SystemTime,
+) -> Result<Reservation, kv::Error> {
+    let bucket = store.bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))?;
+    let current = bucket.get(key)?;
+    reserve_from(&bucket, key, current, request_digest, now)
+}
+
+/// Like [`reserve`], but starts from `current`, the entry of `key` as it was read before.
+fn reserve_from(
+    bucket: &kv::Bucket<'_, &str, kv::Json<Entry>>,
+    key: &str,
+    mut current: Option<kv::Json<Entry>>,
+    request_digest: &str,
+    now: SystemTime,
+) -> Result<Reservation, kv::Error> {
+    loop {
+        if let Some(kv::Json(entry)) = &current {
+            if entry.created_at + TTL > now {
+                return Ok(if entry.request_digest != request_digest {
+                    Reservation::Mismatch
+                } else {
+                    entry
+                        .urn
+                        .clone()
+                        .map_or(Reservation::Pending, Reservation::Created)
+                });
+            }
+        }
+
+        let reserved = kv::Json(Entry {
+            request_digest: request_digest.to_string(),
+            urn: None,
+            created_at: now,
+        });
+        match bucket.compare_and_swap(key, current, Some(reserved)) {
+            Ok(()) => return Ok(Reservation::Reserved),
+            // Another request changed the entry after we read it, classify the new entry.
+            Err(kv::Error::CompareAndSwap(_)) => current = bucket.get(key)?,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Remembers that the request with `key` and `request_digest` created the project `urn` at
+/// `now`.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+/// * if the storage of the entry fails
+pub fn complete(
+    store: &kv::Store,
+    key: &str,
+    request_digest: &str,
+    urn: Urn,
+    now: SystemTime,
+) -> Result<(), kv::Error> {
+    let bucket = store.bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))?;
+    bucket.set(
+        key,
+        kv::Json(Entry {
+            request_digest: request_digest.to_string(),
+            urn: Some(urn),
+            created_at: now,
+        }),
+    )
+}
+
+/// Frees `key` after the request it was reserved for failed, so that it can be retried.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+pub fn release(store: &kv::Store, key: &str) -> Result<(), kv::Error> {
+    let bucket = store.bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))?;
+    bucket.remove(key)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn store() -> (tempfile::TempDir, kv::Store) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+        (temp_dir, store)
+    }
+
+    fn urn() -> Urn {
+        Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero()))
+    }
+
+    #[test]
+    fn same_key_returns_project() {
+        let (_temp_dir, store) = store();
+        let now = SystemTime::now();
+
+        assert_eq!(
+            reserve(&store, "key-a", "digest", now).unwrap(),
+            Reservation::Reserved
+        );
+        assert_eq!(
+            reserve(&store, "key-a", "digest", now).unwrap(),
+            Reservation::Pending
+        );
+        complete(&store, "key-a", "digest", urn(), now).unwrap();
+        assert_eq!(
+            reserve(&store, "key-a", "digest", now).unwrap(),
+            Reservation::Created(urn())
+        );
+        assert_eq!(
+            reserve(&store, "key-b", "digest", now).unwrap(),
+            Reservation::Reserved
+        );
+    }
+
+    #[test]
+    fn different_request_is_rejected() {
+        let (_temp_dir, store) = store();
+        let now = SystemTime::now();
+
+        reserve(&store, "key-a", "digest", now).unwrap();
+        assert_eq!(
+            reserve(&store, "key-a", "other", now).unwrap(),
+            Reservation::Mismatch
+        );
+        complete(&store, "key-a", "digest", urn(), now).unwrap();
+        assert_eq!(
+            reserve(&store, "key-a", "other", now).unwrap(),
+            Reservation::Mismatch
+        );
+    }
+
+    #[test]
+    fn competing_reservation_is_classified() {
+        let (_temp_dir, store) = store();
+        let bucket = store
+            .bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))
+            .unwrap();
+        let now = SystemTime::now();
+
+        // Both requests read the key before either reserved it.
+        reserve(&store, "key-a", "digest", now).unwrap();
+        assert_eq!(
+            reserve_from(&bucket, "key-a", None, "other", now).unwrap(),
+            Reservation::Mismatch
+        );
+        assert_eq!(
+            reserve_from(&bucket, "key-a", None, "digest", now).unwrap(),
+            Reservation::Pending
+        );
+        complete(&store, "key-a", "digest", urn(), now).unwrap();
+        assert_eq!(
+            reserve_from(&bucket, "key-a", None, "digest", now).unwrap(),
+            Reservation::Created(urn())
+        );
+    }
+
+    #[test]
+    fn released_keys_can_be_reused() {
+        let (_temp_dir, store) = store();
+        let now = SystemTime::now();
+
+        reserve(&store, "key-a", "digest", now).unwrap();
+        release(&store, "key-a").unwrap();
+        assert_eq!(
+            reserve(&store, "key-a", "other", now).unwrap(),
+            Reservation::Reserved
+        );
+    }
+
+    #[test]
+    fn keys_expire() {
+        let (_temp_dir, store) = store();
+        let now = SystemTime::now();
+
+        reserve(&store, "key-a", "digest", now).unwrap();
+        complete(&store, "key-a", "digest", urn(), now).unwrap();
+        assert_eq!(
+            reserve(
+                &store,
+                "key-a",
+                "digest",
+                now + TTL - Duration::from_secs(1)
+            )
+            .unwrap(),
+            Reservation::Created(urn())
+        );
+        assert_eq!(
+            reserve(&store, "key-a", "other", now + TTL).unwrap(),
+            Reservation::Reserved
+        );
+    }
+
+    #[test]
+    fn request_digest_depends_on_body() {
+        assert_eq!(request_digest(&["a", "b"]), request_digest(&["a", "b"]));
+        assert_ne!(request_digest(&["a", "b"]), request_digest(&["a", "c"]));
+    }
+}
@@ -159,6 +159,16 @@ impl Handle {
         self.send_message(Message::Shutdown)
     }
 
+    /// Creates a handle that is not connected to a [`Manager`]. Sending messages through it
+    /// panics.
+    #[cfg(test)]
+    pub fn detached() -> Self {
+        Self {
+            reload_notify: Arc::new(Notify::new()),
+            message_sender: mpsc::channel(1).0,
+        }
+    }
+
     /// Send [`Message`] to [`Manager`]
     fn send_message(&mut self, message: Message) {
         match self.message_sender.try_send(message) {
@@ -53,9 +53,101 @@ pub fn initialize(store: &kv::Store, default_seeds: &[String]) -> Result<Session
     Ok(session)
 }
 
+/// Atomically applies `patch` to the settings of the current session and returns the updated
+/// settings. Returns `None` if there is no current session.
+///
+/// # Errors
+///
+/// Errors if we cannot read or write data to the store.
+pub fn patch_settings(
+    store: &kv::Store,
+    patch: settings::Patch,
+) -> Result<Option<settings::Settings>, error::Error> {
+    let bucket = store.bucket::<&str, kv::Json<Session>>(Some(BUCKET_NAME))?;
+    let settings = bucket.transaction(|txn| {
+        let mut session = match txn.get(KEY_CURRENT)? {
+            Some(json) => json.0,
+            None => return Ok(None),
+        };
+        session.settings.apply(patch.clone());
+        txn.set(KEY_CURRENT, kv::Json(session.clone()))?;
+        Ok::<_, kv::TransactionError<kv::Error>>(Some(session.settings))
+    })?;
+
+    Ok(settings)
+}
+
 /// Stores the session as the current session
 fn set_current(store: &kv::Store, sess: Session) -> Result<(), error::Error> {
     Ok(store
         .bucket::<&str, kv::Json<Session>>(Some(BUCKET_NAME))?
         .set(KEY_CURRENT, kv::Json(sess))?)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn patch_settings_without_session() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+
+        assert_eq!(
+            patch_settings(&store, settings::Patch::default()).unwrap(),
+            None
+        );
+        assert_eq!(get_current(&store).unwrap(), None);
+    }
+
+    #[test]
+    fn patch_settings_updates_set_fields() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+        let session = initialize(&store, &["seed-a".to_string()]).unwrap();
+
+        let unchanged = patch_settings(&store, settings::Patch::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchanged, session.settings);
+
+        let patch = settings::Patch {
+            coco: Some(settings::CoCoPatch {
+                seeds: Some(vec!["seed-b".to_string()]),
+                startup_requests: None,
+            }),
+            source: None,
+        };
+        let patched = patch_settings(&store, patch).unwrap().unwrap();
+        assert_eq!(patched.coco.seeds, vec!["seed-b".to_string()]);
+        assert_eq!(patched.source, session.settings.source);
+
+        let patch = settings::Patch {
+            coco: None,
+            source: Some(settings::SourcePatch {
+                max_highlight_bytes: Some(1024),
+            }),
+        };
+        let patched = patch_settings(&store, patch).unwrap().unwrap();
+        assert_eq!(patched.coco.seeds, vec!["seed-b".to_string()]);
+        assert_eq!(patched.source.max_highlight_bytes, 1024);
+        assert_eq!(get_current(&store).unwrap().unwrap().settings, patched);
+    }
+
+    #[test]
+    fn patch_rejects_unknown_fields() {
+        assert!(serde_json::from_str::<settings::Patch>(r#"{"theme": "dark"}"#).is_err());
+        assert!(
+            serde_json::from_str::<settings::Patch>(r#"{"coco": {"seeds": [], "other": 1}}"#)
+                .is_err()
+        );
+        assert_eq!(
+            serde_json::from_str::<settings::Patch>(r#"{"coco": {}}"#).unwrap(),
+            settings::Patch {
+                coco: Some(settings::CoCoPatch::default()),
+                source: None,
+            }
+        );
+    }
+}
@@ -7,25 +7,117 @@
 //! User controlled parameters for application appearance, behaviour and state.
 use serde::{Deserialize, Serialize};
 
+use link_identities::git::Urn;
+
 /// User controlled parameters for application appearance, behaviour and state.
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
     /// User-determined p2p parameters.
     pub coco: CoCo,
+    /// Parameters for browsing source code.
+    #[serde(default)]
+    pub source: Source,
+}
+
+impl Settings {
+    /// Applies `patch`, leaving all fields that are not set in the patch unchanged.
+    pub fn apply(&mut self, patch: Patch) {
+        if let Some(coco) = patch.coco {
+            self.coco.apply(coco);
+        }
+        if let Some(source) = patch.source {
+            self.source.apply(source);
+        }
+    }
+}
+
+/// Partial update of [`Settings`]. Fields that are not set are left unchanged.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Patch {
+    /// Update of the p2p parameters.
+    pub coco: Option<CoCoPatch>,
+    /// Update of the source browsing parameters.
+    pub source: Option<SourcePatch>,
 }
 
 /// `CoCo` config parameters subject to user preferences
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct CoCo {
     /// Peers to connect to at startup.
     pub seeds: Vec<String>,
+    /// Projects to request from the network at startup.
+    #[serde(default)]
+    pub startup_requests: Vec<Urn>,
 }
 
 /// Default seeds placeholder, don't use this for setting default seeds.
 /// Set the default seeds by passing a "--default-seed" CLI flag.
 impl Default for CoCo {
     fn default() -> Self {
-        Self { seeds: vec![] }
+        Self {
+            seeds: vec![],
+            startup_requests: vec![],
+        }
+    }
+}
+
+impl CoCo {
+    /// Applies `patch`, leaving all fields that are not set in the patch unchanged.
+    fn apply(&mut self, patch: CoCoPatch) {
+        if let Some(seeds) = patch.seeds {
+            self.seeds = seeds;
+        }
+        if let Some(startup_requests) = patch.startup_requests {
+            self.startup_requests = startup_requests;
+        }
+    }
+}
+
+/// Partial update of [`CoCo`]. Fields that are not set are left unchanged.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CoCoPatch {
+    /// Peers to connect to at startup.
+    pub seeds: Option<Vec<String>>,
+    /// Projects to request from the network at startup.
+    pub startup_requests: Option<Vec<Urn>>,
+}
+
+/// Blobs larger than this are not syntax highlighted unless the settings say otherwise.
+pub const DEFAULT_MAX_HIGHLIGHT_BYTES: usize = 256 * 1024;
+
+/// Source browsing parameters subject to user preferences.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Source {
+    /// Blobs larger than this many bytes are returned without syntax highlighting.
+    pub max_highlight_bytes: usize,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self {
+            max_highlight_bytes: DEFAULT_MAX_HIGHLIGHT_BYTES,
+        }
+    }
+}
+
+impl Source {
+    /// Applies `patch`, leaving all fields that are not set in the patch unchanged.
+    fn apply(&mut self, patch: SourcePatch) {
+        if let Some(max_highlight_bytes) = patch.max_highlight_bytes {
+            self.max_highlight_bytes = max_highlight_bytes;
+        }
     }
 }
+
+/// Partial update of [`Source`]. Fields that are not set are left unchanged.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SourcePatch {
+    /// Blobs larger than this many bytes are returned without syntax highlighting.
+    pub max_highlight_bytes: Option<usize>,
+}
@@ -0,0 +1,99 @@
+// Copyright © 2022 The Radicle Upstream Contributors
+//
+// This file is part of radicle-upstream, distributed under the GPLv3
+// with Radicle Linking Exception. For full terms see the included
+// LICENSE file.
+
+//! Create and verify commits signed with the `radicle-ed25519` signature scheme.
+//!
+//! The signature is the base64 encoded CBOR of a [`link_crypto::Signature`] of the commit
+//! buffer, stored in the `radicle-ed25519` header field of the commit.
+
+/// Name of the commit header field that holds the signature.
+const SIGNATURE_FIELD: &str = "radicle-ed25519";
+
+/// Errors that occur when creating or verifying a signed commit.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Internal git error, e.g. the commit has no signature.
+    #[error(transparent)]
+    Git(#[from] git2::Error),
+
+    /// The signer failed to sign the commit.
+    #[error("failed to sign commit: {0}")]
+    Sign(String),
+
+    /// The signature could not be encoded or decoded.
+    #[error("invalid signature encoding: {0}")]
+    Encoding(String),
+
+    /// The signature was not made by the expected key.
+    #[error("signature could not be verified")]
+    Unverified,
+}
+
+/// Creates a commit of `tree` with `parents` and signs it with `signer`. `signature` is used
+/// for the author and the committer. No reference is updated.
+///
+/// # Errors
+///
+///   * The commit could not be written.
+///   * The signer failed or the signature could not be encoded.
+pub fn commit<S>(
+    repo: &git2::Repository,
+    signer: &S,
+    signature: &git2::Signature<'_>,
+    message: &str,
+    tree: &git2::Tree<'_>,
+    parents: &[&git2::Commit<'_>],
+) -> Result<git2::Oid, Error>
+where
+    S: librad::Signer,
+{
+    let commit_buffer = repo.commit_create_buffer(signature, signature, message, tree, parents)?;
+    let commit_buffer = commit_buffer
+        .as_str()
+        .ok_or_else(|| git2::Error::from_str("commit buffer is not valid UTF-8"))?;
+
+    let commit_signature = signer
+        .sign_blocking(commit_buffer.as_bytes())
+        .map_err(|err| Error::Sign(err.to_string()))?;
+    let commit_signature = link_crypto::Signature::from(commit_signature);
+    let signature_bytes =
+        minicbor::to_vec(commit_signature).map_err(|err| Error::Encoding(err.to_string()))?;
+
+    Ok(repo.commit_signed(
+        commit_buffer,
+        &base64::encode(signature_bytes),
+        Some(SIGNATURE_FIELD),
+    )?)
+}
+
+/// Checks that the commit `oid` carries a signature made by `peer_id`.
+///
+/// # Errors
+///
+///   * [`Error::Git`] if the commit has no signature.
+///   * [`Error::Encoding`] if the signature is malformed.
+///   * [`Error::Unverified`] if the signature was not made by `peer_id`.
+pub fn verify(
+    repo: &git2::Repository,
+    oid: git2::Oid,
+    peer_id: &librad::PeerId,
+) -> Result<(), Error> {
+    let (signature_encoded_bytes, signed) = repo.extract_signature(&oid, Some(SIGNATURE_FIELD))?;
+    let signature = {
+        let signature_encoded = std::str::from_utf8(&signature_encoded_bytes)
+            .map_err(|err| Error::Encoding(err.to_string()))?;
+        let signature_bytes =
+            base64::decode(signature_encoded).map_err(|err| Error::Encoding(err.to_string()))?;
+        minicbor::decode::<link_crypto::Signature>(&signature_bytes)
+            .map_err(|err| Error::Encoding(err.to_string()))?
+    };
+
+    if signature.verify(&signed, peer_id.as_public_key()) {
+        Ok(())
+    } else {
+        Err(Error::Unverified)
+    }
+}
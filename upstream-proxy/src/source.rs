@@ -0,0 +1,3869 @@
+// Copyright © 2022 The Radicle Upstream Contributors
+//
+// This file is part of radicle-upstream, distributed under the GPLv3
+// with Radicle Linking Exception. For full terms see the included
+// LICENSE file.
+
+//! Source code browsing functionality that is not provided by [`radicle_source`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    convert::TryFrom as _,
+    num::NonZeroUsize,
+    path::Path,
+    str::FromStr as _,
+};
+
+use serde::{
+    ser::{SerializeStruct as _, Serializer},
+    Deserialize, Serialize,
+};
+
+use librad::git::types::{Reference, Single};
+use link_crypto::PeerId;
+use radicle_source::{
+    commit::Header,
+    object::TreeEntry,
+    surf::{
+        file_system::{self, DirectoryContents},
+        vcs::git::{self, Browser, RefScope, Rev},
+    },
+    Blob, Branch, Info, ObjectType, Revision, Tag, Tree,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::error;
+
+/// The default branch of a project together with the revisions that can be browsed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceConfig {
+    /// The default branch configured in the project metadata.
+    pub default_branch: String,
+    /// The local branches of the project.
+    pub branches: Vec<Branch>,
+    /// The local tags of the project.
+    pub tags: Vec<Tag>,
+}
+
+/// Collects the local branches and tags of the repository `browser` is pointed at into a
+/// [`SourceConfig`].
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the branches or tags could not be listed.
+pub fn source_config(
+    browser: &Browser<'_>,
+    default_branch: String,
+) -> Result<SourceConfig, radicle_source::Error> {
+    Ok(SourceConfig {
+        default_branch,
+        branches: radicle_source::branches(browser, RefScope::Local)?,
+        tags: radicle_source::tags(browser)?,
+    })
+}
+
+/// Orderings for a list of [`Tag`]s, see [`sort_tags`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TagSort {
+    /// Byte-wise order of the tag names.
+    Lexical,
+    /// Case-insensitive order of the tag names.
+    Name,
+    /// Tags that look like semantic versions, optionally prefixed with `v`, ordered by their
+    /// version numbers, followed by all other tags in lexical order.
+    Semver,
+}
+
+impl Default for TagSort {
+    fn default() -> Self {
+        Self::Lexical
+    }
+}
+
+/// A [`Branch`] or [`Tag`] together with the peer whose remote refs it was listed from.
+///
+/// Refs of the local peer serialize as the bare name, like [`Branch`] and [`Tag`] themselves,
+/// so existing consumers keep working. Remote refs serialize as
+/// `{ "name": <name>, "remote": <peer_id> }`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedRef<T> {
+    /// The name of the branch or tag.
+    pub name: T,
+    /// The peer the ref belongs to, `None` for refs of the local peer.
+    pub remote: Option<PeerId>,
+}
+
+impl<T> ScopedRef<T> {
+    /// Scopes every ref in `names` to `remote`.
+    pub fn all(names: Vec<T>, remote: Option<PeerId>) -> Vec<Self> {
+        names
+            .into_iter()
+            .map(|name| Self { name, remote })
+            .collect()
+    }
+}
+
+impl<T: Serialize> Serialize for ScopedRef<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.remote {
+            None => self.name.serialize(serializer),
+            Some(remote) => {
+                let mut state = serializer.serialize_struct("ScopedRef", 2)?;
+                state.serialize_field("name", &self.name)?;
+                state.serialize_field("remote", remote)?;
+                state.end()
+            },
+        }
+    }
+}
+
+/// Lists the tags of the local peer, or of `peer_id` if given, in lexical order.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the tags could not be listed.
+pub fn tags(
+    browser: &Browser<'_>,
+    peer_id: Option<PeerId>,
+) -> Result<Vec<Tag>, radicle_source::Error> {
+    let scope = peer_id.map_or(RefScope::Local, |peer_id| RefScope::Remote {
+        name: Some(peer_id.to_string()),
+    });
+    let mut tags = browser
+        .list_tags(scope)?
+        .into_iter()
+        .map(|tag| Tag::from(tag.name().to_string()))
+        .collect::<Vec<_>>();
+    tags.sort();
+
+    Ok(tags)
+}
+
+/// Sorts `tags` according to `sort`.
+pub fn sort_tags(tags: &mut [Tag], sort: TagSort) {
+    match sort {
+        TagSort::Lexical => tags.sort_by_cached_key(ToString::to_string),
+        TagSort::Name => tags.sort_by_cached_key(|tag| {
+            let name = tag.to_string();
+            (name.to_lowercase(), name)
+        }),
+        TagSort::Semver => tags.sort_by_cached_key(|tag| {
+            let name = tag.to_string();
+            let version = parse_version(&name);
+            // Versions come first as `false` orders before `true`.
+            (version.is_none(), version, name)
+        }),
+    }
+}
+
+/// A parsed semantic version as used by [`TagSort::Semver`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Version {
+    /// Major, minor and patch number.
+    core: (u64, u64, u64),
+    /// Whether the version has no pre-release suffix, which makes it order after its
+    /// pre-releases.
+    release: bool,
+    /// The dot-separated identifiers of the pre-release suffix, compared one by one. A shorter
+    /// suffix orders first if all of its identifiers are equal.
+    pre: Vec<PreRelease>,
+}
+
+/// An identifier of the pre-release suffix of a [`Version`], ordered as in §11 of the semantic
+/// versioning spec: numeric identifiers compare numerically and order before alphanumeric ones,
+/// which compare lexically.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum PreRelease {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+/// Parses `name` as `[v]<major>.<minor>.<patch>[-<pre>][+<build>]`.
+fn parse_version(name: &str) -> Option<Version> {
+    let name = name.strip_prefix('v').unwrap_or(name);
+    let name = name.split('+').next()?;
+    let (core, pre) = match name.split_once('-') {
+        Some((core, pre)) => (core, pre),
+        None => (name, ""),
+    };
+
+    let mut numbers = core.split('.').map(str::parse::<u64>);
+    let version = (
+        numbers.next()?.ok()?,
+        numbers.next()?.ok()?,
+        numbers.next()?.ok()?,
+    );
+    if numbers.next().is_some() {
+        return None;
+    }
+
+    let pre_release = if pre.is_empty() {
+        Vec::new()
+    } else {
+        pre.split('.')
+            .map(|identifier| {
+                identifier.parse::<u64>().map_or_else(
+                    |_| PreRelease::Alphanumeric(identifier.to_string()),
+                    PreRelease::Numeric,
+                )
+            })
+            .collect()
+    };
+
+    Some(Version {
+        core: version,
+        release: pre.is_empty(),
+        pre: pre_release,
+    })
+}
+
+/// High-level statistics about a repository, see [`stats_bounded`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    /// Number of commits.
+    pub commits: usize,
+    /// Number of local branches.
+    pub branches: usize,
+    /// Number of distinct commit authors.
+    pub contributors: usize,
+    /// Whether the walk of the history was cut short, in which case `commits` and
+    /// `contributors` are lower bounds.
+    pub approximate: bool,
+}
+
+impl From<radicle_source::surf::vcs::git::Stats> for Stats {
+    fn from(stats: radicle_source::surf::vcs::git::Stats) -> Self {
+        Self {
+            commits: stats.commits,
+            branches: stats.branches,
+            contributors: stats.contributors,
+            approximate: false,
+        }
+    }
+}
+
+/// Like [`Browser::get_stats`] for the history of `head`, but the walk stops after
+/// `max_commits` commits. If the history is longer the result is marked as approximate.
+///
+/// Only the commits reachable from `head` and their authors are counted. `branches` counts
+/// every local branch in the namespace of `reference`.
+///
+/// # Errors
+///
+/// Will return [`error::Error`] if the branches could not be listed or a commit could not be
+/// read.
+pub fn stats_bounded(
+    repo: &git2::Repository,
+    reference: &Reference<Single>,
+    head: git2::Oid,
+    max_commits: usize,
+) -> Result<Stats, error::Error> {
+    let branches = branch_count(repo, reference)?;
+
+    let mut revwalk = repo.revwalk().map_err(git_error)?;
+    revwalk.push(head).map_err(git_error)?;
+    let mut commits = 0;
+    let mut contributors = BTreeSet::new();
+    let mut approximate = false;
+    for oid in revwalk {
+        if commits == max_commits {
+            approximate = true;
+            break;
+        }
+        let commit = repo
+            .find_commit(oid.map_err(git_error)?)
+            .map_err(git_error)?;
+        let author = commit.author();
+        contributors.insert((author.name_bytes().to_vec(), author.email_bytes().to_vec()));
+        commits += 1;
+    }
+
+    Ok(Stats {
+        commits,
+        branches,
+        contributors: contributors.len(),
+        approximate,
+    })
+}
+
+/// The number of local branches in the namespace of `reference`.
+///
+/// # Errors
+///
+/// Will return [`error::Error`] if `reference` has no namespace or the branches could not be
+/// listed.
+fn branch_count(
+    repo: &git2::Repository,
+    reference: &Reference<Single>,
+) -> Result<usize, error::Error> {
+    let namespace = reference
+        .namespace
+        .as_ref()
+        .ok_or(crate::daemon::state::Error::MissingNamespace)?;
+    Ok(repo
+        .references_glob(&format!("refs/namespaces/{}/refs/heads/*", namespace))
+        .map_err(git_error)?
+        .count())
+}
+
+/// A [`Header`] together with the author time and the timezone offsets of both signatures,
+/// which [`Header`] leaves out when serialized.
+///
+/// It is serialized like [`Header`] with the additional fields. The message of the header is
+/// rebuilt from the summary and [`commit_description`], so the serialized description is the
+/// body of the commit message even if the summary spans several lines or was shortened. Headers
+/// shortened with [`CommitHeader::truncate`] are marked with `"truncated": true`.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitHeader {
+    /// The header as provided by [`radicle_source`].
+    #[serde(flatten)]
+    pub header: Header,
+    /// Offset of the committer's timezone from UTC in minutes.
+    pub committer_time_offset: i32,
+    /// The recorded time of the author signature in seconds since the unix epoch.
+    pub author_time: i64,
+    /// Offset of the author's timezone from UTC in minutes.
+    pub author_time_offset: i32,
+    /// Whether the summary or the description were shortened by [`CommitHeader::truncate`].
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl From<&git::Commit> for CommitHeader {
+    fn from(commit: &git::Commit) -> Self {
+        let mut header = Self {
+            header: Header::from(commit),
+            committer_time_offset: commit.committer.time.offset_minutes(),
+            author_time: commit.author.time.seconds(),
+            author_time_offset: commit.author.time.offset_minutes(),
+            truncated: false,
+        };
+        header.set_description(commit_description(&commit.message));
+        header
+    }
+}
+
+/// Returns the body of a commit `message`, which by git convention is separated from the
+/// summary by the first blank line. Unlike [`Header::description`] this doesn't rely on the
+/// summary being a prefix of the message, which it isn't if the summary spans several lines.
+///
+/// The body is returned verbatim apart from surrounding blank lines, so `\r\n` line endings
+/// are preserved. Returns an empty string if the message has no body.
+#[must_use]
+pub fn commit_description(message: &str) -> &str {
+    let mut offset = 0;
+    for line in message.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+
+    message[offset..]
+        .trim_start_matches(|c| c == '\r' || c == '\n')
+        .trim_end()
+}
+
+impl CommitHeader {
+    /// The body of the commit message, see [`commit_description`].
+    #[must_use]
+    pub fn description(&self) -> &str {
+        self.header.description()
+    }
+
+    /// Rebuilds the message of the header from its summary and `description`, so that
+    /// [`Header::description`] returns `description`.
+    fn set_description(&mut self, description: &str) {
+        self.header.message = format!("{}\n\n{}", self.header.summary, description);
+    }
+
+    /// Shortens the summary and the description to at most the given number of characters,
+    /// ending them with an ellipsis if they were cut. Sets [`CommitHeader::truncated`] if either
+    /// was shortened.
+    pub fn truncate(&mut self, summary_max_len: Option<usize>, description_max_len: Option<usize>) {
+        let summary =
+            summary_max_len.and_then(|max_len| truncate_text(&self.header.summary, max_len));
+        let description =
+            description_max_len.and_then(|max_len| truncate_text(self.description(), max_len));
+        if summary.is_none() && description.is_none() {
+            return;
+        }
+
+        let description = description.unwrap_or_else(|| self.description().to_string());
+        if let Some(summary) = summary {
+            self.header.summary = summary;
+        }
+        self.set_description(&description);
+        self.truncated = true;
+    }
+
+    /// Replaces the author and committer with their canonical identities from `mailmap`.
+    pub fn apply_mailmap(&mut self, mailmap: &git2::Mailmap) {
+        for person in [&mut self.header.author, &mut self.header.committer] {
+            let (name, email) = resolve_person(mailmap, &person.name, &person.email);
+            person.name = name;
+            person.email = email;
+        }
+    }
+}
+
+/// Cuts `text` to `max_len` characters including a trailing ellipsis. Returns `None` if `text`
+/// is not longer than `max_len`.
+fn truncate_text(text: &str, max_len: usize) -> Option<String> {
+    if text.chars().count() <= max_len {
+        return None;
+    }
+
+    let mut truncated = text
+        .chars()
+        .take(max_len.saturating_sub(1))
+        .collect::<String>();
+    truncated.push('…');
+    Some(truncated)
+}
+
+/// Returns the canonical name and email of a contributor with `name` and `email` from `mailmap`.
+/// Contributors that can't be represented as a [`git2::Signature`] are returned unchanged.
+fn resolve_person(mailmap: &git2::Mailmap, name: &str, email: &str) -> (String, String) {
+    let resolved = git2::Signature::new(name, email, &git2::Time::new(0, 0))
+        .and_then(|signature| mailmap.resolve_signature(&signature));
+    match resolved {
+        Ok(signature) => (
+            signature.name().unwrap_or(name).to_string(),
+            signature.email().unwrap_or(email).to_string(),
+        ),
+        Err(_) => (name.to_string(), email.to_string()),
+    }
+}
+
+/// Like [`mailmap`], but loads the `.mailmap` file at the root of the commit `head` in `repo`.
+///
+/// # Errors
+///
+/// Will return [`error::Error`] if the tree of `head` or the `.mailmap` could not be read.
+pub fn commit_mailmap(
+    repo: &git2::Repository,
+    head: git2::Oid,
+) -> Result<git2::Mailmap, error::Error> {
+    let tree = repo
+        .find_commit(head)
+        .and_then(|commit| commit.tree())
+        .map_err(git_error)?;
+    let contents = match tree.get_name(".mailmap") {
+        Some(entry) => {
+            let object = entry.to_object(repo).map_err(git_error)?;
+            object
+                .as_blob()
+                .and_then(|blob| std::str::from_utf8(blob.content()).ok())
+                .unwrap_or_default()
+                .to_string()
+        },
+        None => String::new(),
+    };
+
+    git2::Mailmap::from_buffer(&contents).map_err(git_error)
+}
+
+/// Loads the `.mailmap` file at the root of the revision the `browser` is pointed at, see
+/// `gitmailmap(5)`. Returns an empty mailmap if there is no such file.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the tree of the revision could not be read.
+pub fn mailmap(browser: &Browser<'_>) -> Result<git2::Mailmap, radicle_source::Error> {
+    let root = browser.get_directory()?;
+    let contents = root
+        .find_file(file_system::Path::from_str(".mailmap")?)
+        .and_then(|file| String::from_utf8(file.contents).ok())
+        .unwrap_or_default();
+
+    Ok(git2::Mailmap::from_buffer(&contents).map_err(git::error::Error::from)?)
+}
+
+/// The commit headers of a history together with the repository statistics, like
+/// [`radicle_source::commit::Commits`] but with [`CommitHeader`]s.
+#[derive(Serialize)]
+pub struct Commits {
+    /// The commit headers, latest first.
+    pub headers: Vec<CommitHeader>,
+    /// The statistics of the history.
+    pub stats: Stats,
+}
+
+/// A range of committer times in seconds since the epoch. Both bounds are inclusive and a
+/// missing bound doesn't restrict the range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeWindow {
+    /// The earliest committer time.
+    pub since: Option<i64>,
+    /// The latest committer time.
+    pub until: Option<i64>,
+}
+
+impl TimeWindow {
+    /// The commits of `history` that were committed within the window, in the order of
+    /// `history`.
+    ///
+    /// Committer times don't have to decrease along the history, e.g. after a rebase or with a
+    /// skewed clock, so every commit of `history` is checked. If `since` is after `until` no
+    /// commits are returned.
+    fn commits<'a>(
+        self,
+        history: impl Iterator<Item = &'a git::Commit>,
+    ) -> impl Iterator<Item = &'a git::Commit> {
+        history.filter(move |commit| self.contains(commit))
+    }
+
+    /// Whether `commit` was committed within the window.
+    fn contains(self, commit: &git::Commit) -> bool {
+        let time = commit.committer.time.seconds();
+        self.since.map_or(true, |since| time >= since)
+            && self.until.map_or(true, |until| time <= until)
+    }
+}
+
+/// Retrieves the history of `head` like [`radicle_source::commits`], restricted to the commits
+/// within `window`. The statistics cover the complete history of `head`, like
+/// [`stats_bounded`] without a limit.
+///
+/// The history is walked once and `cancel` is checked before every commit is read.
+///
+/// # Errors
+///
+///   * The history could not be read or the statistics could not be computed.
+///   * [`error::Error::Cancelled`] if `cancel` was cancelled during the walk.
+pub fn commits(
+    repo: &git2::Repository,
+    reference: &Reference<Single>,
+    head: git2::Oid,
+    window: TimeWindow,
+    cancel: &CancellationToken,
+) -> Result<Commits, error::Error> {
+    let mut headers = Vec::new();
+    let mut commits = 0;
+    let mut contributors = BTreeSet::new();
+    for commit in history(repo, head)? {
+        ensure_not_cancelled(cancel)?;
+        let commit = commit?;
+        commits += 1;
+        contributors.insert((commit.author.name.clone(), commit.author.email.clone()));
+        if window.contains(&commit) {
+            headers.push(CommitHeader::from(&commit));
+        }
+    }
+
+    Ok(Commits {
+        headers,
+        stats: Stats {
+            commits,
+            branches: branch_count(repo, reference)?,
+            contributors: contributors.len(),
+            approximate: false,
+        },
+    })
+}
+
+/// Fails with [`error::Error::Cancelled`] once `cancel` was cancelled.
+fn ensure_not_cancelled(cancel: &CancellationToken) -> Result<(), error::Error> {
+    if cancel.is_cancelled() {
+        Err(error::Error::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+/// The part of a commit that is matched by [`search_commits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchField {
+    /// The full commit message, including the summary.
+    Message,
+    /// The name and email of the commit author.
+    Author,
+}
+
+impl Default for SearchField {
+    fn default() -> Self {
+        Self::Message
+    }
+}
+
+/// Walks the history of `head` and returns the headers of at most `limit` commits whose
+/// `field` contains `query`. Matching is case-insensitive.
+///
+/// The walk stops as soon as `limit` commits matched.
+///
+/// # Errors
+///
+/// Will return [`error::Error`] if a commit of the history could not be read.
+pub fn search_commits(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    query: &str,
+    field: SearchField,
+    limit: usize,
+) -> Result<Vec<CommitHeader>, error::Error> {
+    let query = query.to_lowercase();
+    history(repo, head)?
+        .filter(|commit| {
+            commit.as_ref().map_or(true, |commit| {
+                let haystack = match field {
+                    SearchField::Message => commit.message.to_lowercase(),
+                    SearchField::Author => {
+                        format!("{} <{}>", commit.author.name, commit.author.email).to_lowercase()
+                    },
+                };
+                haystack.contains(&query)
+            })
+        })
+        .take(limit)
+        .map(|commit| commit.map(|commit| CommitHeader::from(&commit)))
+        .collect()
+}
+
+/// Returns the headers of the commits on page `page` of the history of `revision` within
+/// `window`, with `per_page` commits per page. Pages start at `0`.
+///
+/// Unlike [`commits`] this does not compute the repository statistics, which
+/// requires listing all branches and walking the complete history.
+///
+/// If `revision` is `None` the history the `browser` is currently pointed at is used.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the revision could not be resolved.
+pub fn commit_headers(
+    browser: &mut Browser<'_>,
+    revision: Option<Revision<PeerId>>,
+    window: TimeWindow,
+    page: usize,
+    per_page: usize,
+) -> Result<Vec<CommitHeader>, radicle_source::Error> {
+    if let Some(revision) = revision {
+        browser.rev(Rev::try_from(revision)?)?;
+    }
+
+    let headers = window
+        .commits(browser.get().iter())
+        .skip(page.saturating_mul(per_page))
+        .take(per_page)
+        .map(CommitHeader::from)
+        .collect();
+
+    Ok(headers)
+}
+
+/// A page of commit headers returned by [`commits_after`].
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitPage {
+    /// The commit headers, latest first.
+    pub headers: Vec<CommitHeader>,
+    /// The hash of the last commit on this page, to be passed as `after` to get the next page.
+    /// `None` if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// Returns the headers of at most `limit` commits of the history of `revision` that come after
+/// the commit `after`, or from the start of the history if `after` is `None`.
+///
+/// Unlike [`commit_headers`] the pages stay stable when new commits are added to the history
+/// between requests.
+///
+/// If `revision` is `None` the history the `browser` is currently pointed at is used.
+///
+/// # Errors
+///
+///   * The revision could not be resolved.
+///   * `after` is not part of the history, see [`error::Error::RevisionNotFound`].
+pub fn commits_after(
+    browser: &mut Browser<'_>,
+    revision: Option<Revision<PeerId>>,
+    after: Option<git2::Oid>,
+    limit: NonZeroUsize,
+) -> Result<CommitPage, error::Error> {
+    if let Some(revision) = revision {
+        browser
+            .rev(Rev::try_from(revision).map_err(radicle_source::Error::from)?)
+            .map_err(radicle_source::Error::from)?;
+    }
+
+    let history = browser.get();
+    let start = match after {
+        Some(after) => {
+            history
+                .iter()
+                .position(|commit| commit.id == after)
+                .ok_or_else(|| error::Error::RevisionNotFound(after.to_string()))?
+                + 1
+        },
+        None => 0,
+    };
+
+    let headers = history
+        .iter()
+        .skip(start)
+        .take(limit.get())
+        .map(CommitHeader::from)
+        .collect::<Vec<_>>();
+    let next_cursor = if start + headers.len() < history.len() {
+        headers.last().map(|header| header.header.sha1.to_string())
+    } else {
+        None
+    };
+
+    Ok(CommitPage {
+        headers,
+        next_cursor,
+    })
+}
+
+/// A commit in the graph returned by [`commit_graph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphNode {
+    /// The full commit hash.
+    pub sha1: String,
+    /// The full hashes of the parents in the order they are recorded in the commit. Merge
+    /// commits have more than one parent.
+    pub parents: Vec<String>,
+    /// The summary of the commit message.
+    pub summary: String,
+    /// The time of the committer signature in seconds since the epoch.
+    pub committer_time: i64,
+}
+
+/// Walks the history of `revision` and returns at most `limit` commits together with their
+/// parents.
+///
+/// If `revision` is `None` the history the `browser` is currently pointed at is walked.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the revision could not be resolved.
+pub fn commit_graph(
+    browser: &mut Browser<'_>,
+    revision: Option<Revision<PeerId>>,
+    limit: usize,
+) -> Result<Vec<GraphNode>, radicle_source::Error> {
+    if let Some(revision) = revision {
+        browser.rev(Rev::try_from(revision)?)?;
+    }
+
+    let nodes = browser
+        .get()
+        .iter()
+        .take(limit)
+        .map(|commit| GraphNode {
+            sha1: commit.id.to_string(),
+            parents: commit.parents.iter().map(ToString::to_string).collect(),
+            summary: commit.summary.clone(),
+            committer_time: commit.committer.time.seconds(),
+        })
+        .collect();
+
+    Ok(nodes)
+}
+
+/// A commit author together with their contributions, see [`contributors`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contributor {
+    /// The name and email of the author.
+    pub person: radicle_source::Person,
+    /// Number of commits authored.
+    pub commits: usize,
+    /// The hash of the oldest commit authored within the walked part of the history, see
+    /// [`contributors`].
+    pub first_commit: String,
+    /// The hash of the latest commit authored.
+    pub last_commit: String,
+}
+
+/// Walks at most `limit` commits of the history of `head` and aggregates them by author email.
+/// Contributors are ordered by their number of commits, most first, and then by name.
+///
+/// The walk stops after `limit` commits, so the counts and [`Contributor::first_commit`] only
+/// cover the latest `limit` commits rather than the complete history.
+///
+/// If `use_mailmap` is set, authors are resolved with the `.mailmap` of `head` first so that
+/// contributors with several identities are counted once.
+///
+/// # Errors
+///
+/// Will return [`error::Error`] if the history or the `.mailmap` could not be read.
+pub fn contributors(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    limit: usize,
+    use_mailmap: bool,
+) -> Result<Vec<Contributor>, error::Error> {
+    let mailmap = if use_mailmap {
+        Some(commit_mailmap(repo, head)?)
+    } else {
+        None
+    };
+
+    let mut contributors = std::collections::HashMap::<String, Contributor>::new();
+    // The history starts with the latest commit.
+    for commit in history(repo, head)?.take(limit) {
+        let commit = commit?;
+        let (name, email) = match &mailmap {
+            Some(mailmap) => resolve_person(mailmap, &commit.author.name, &commit.author.email),
+            None => (commit.author.name.clone(), commit.author.email.clone()),
+        };
+        let sha1 = commit.id.to_string();
+        contributors
+            .entry(email.to_lowercase())
+            .and_modify(|contributor| {
+                contributor.commits += 1;
+                contributor.first_commit = sha1.clone();
+            })
+            .or_insert_with(|| Contributor {
+                person: radicle_source::Person { name, email },
+                commits: 1,
+                first_commit: sha1.clone(),
+                last_commit: sha1.clone(),
+            });
+    }
+
+    let mut contributors = contributors.into_values().collect::<Vec<_>>();
+    contributors.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| a.person.name.cmp(&b.person.name))
+    });
+
+    Ok(contributors)
+}
+
+/// The tree at `prefix` in the commit `head`. An empty `prefix` is the root tree.
+///
+/// # Errors
+///
+///   * [`radicle_source::Error::PathNotFound`] if `prefix` does not exist.
+///   * [`error::Error::NotADirectory`] if `prefix` is a file.
+pub fn prefix_tree<'repo>(
+    repo: &'repo git2::Repository,
+    head: git2::Oid,
+    prefix: &str,
+) -> Result<git2::Tree<'repo>, error::Error> {
+    let root = repo
+        .find_commit(head)
+        .and_then(|commit| commit.tree())
+        .map_err(git_error)?;
+    if prefix.is_empty() {
+        return Ok(root);
+    }
+
+    let entry = match root.get_path(Path::new(prefix)) {
+        Ok(entry) => entry,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => {
+            let path = file_system::Path::from_str(prefix).map_err(radicle_source::Error::from)?;
+            return Err(radicle_source::Error::PathNotFound(path).into());
+        },
+        Err(err) => return Err(git_error(err)),
+    };
+    if entry.kind() != Some(git2::ObjectType::Tree) {
+        return Err(error::Error::NotADirectory(prefix.to_string()));
+    }
+
+    repo.find_tree(entry.id()).map_err(git_error)
+}
+
+/// Walks the tree `tree`, which is located at `prefix`, depth-first and calls `on_entry` for
+/// every entry as soon as it is visited, so that callers can stream large trees. Directories
+/// are visited before files and entries of the same kind are ordered by name. The walk stops
+/// early if `on_entry` returns `false`.
+///
+/// Use [`prefix_tree`] to look up the tree, so that a missing prefix is reported before the
+/// walk starts.
+///
+/// # Errors
+///
+/// Will return [`error::Error`] if a tree below `tree` could not be read.
+pub fn walk_tree<F>(
+    repo: &git2::Repository,
+    tree: git2::Oid,
+    prefix: &str,
+    mut on_entry: F,
+) -> Result<(), error::Error>
+where
+    F: FnMut(TreeEntry) -> bool,
+{
+    let tree = repo.find_tree(tree).map_err(git_error)?;
+    walk_git_tree(repo, &tree, prefix, &mut on_entry)?;
+    Ok(())
+}
+
+/// Visits the entries of `tree`, which is located at `path`, for [`walk_tree`]. Returns
+/// `false` if the walk was stopped. Submodules are skipped.
+fn walk_git_tree<F>(
+    repo: &git2::Repository,
+    tree: &git2::Tree<'_>,
+    path: &str,
+    on_entry: &mut F,
+) -> Result<bool, error::Error>
+where
+    F: FnMut(TreeEntry) -> bool,
+{
+    let mut entries = tree
+        .iter()
+        .filter_map(|entry| {
+            let object_type = match entry.kind()? {
+                git2::ObjectType::Tree => ObjectType::Tree,
+                git2::ObjectType::Blob => ObjectType::Blob,
+                _ => return None,
+            };
+            Some((object_type, entry.name()?.to_string(), entry.id()))
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(object_type, name, _)| {
+        (matches!(object_type, ObjectType::Blob), name.clone())
+    });
+
+    for (object_type, name, oid) in entries {
+        let entry_path = if path.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", path, name)
+        };
+        let is_tree = matches!(object_type, ObjectType::Tree);
+        let entry = TreeEntry {
+            info: Info {
+                name,
+                object_type,
+                last_commit: None,
+            },
+            path: entry_path.clone(),
+        };
+        if !on_entry(entry) {
+            return Ok(false);
+        }
+
+        if is_tree {
+            let sub_tree = repo.find_tree(oid).map_err(git_error)?;
+            if !walk_git_tree(repo, &sub_tree, &entry_path, on_entry)? {
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// The size of a tree, see [`tree_summary`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeSummary {
+    /// Number of files below the prefix.
+    pub file_count: u64,
+    /// Number of directories below the prefix, not counting the prefix itself.
+    pub dir_count: u64,
+    /// Size of all files below the prefix.
+    pub total_bytes: u64,
+}
+
+/// Counts the files and directories below `prefix` of the commit `head` and sums up the size of
+/// the files, walking the tree once. Submodules are skipped.
+///
+/// The sizes are read from the object headers, the contents of the files are never loaded.
+///
+/// # Errors
+///
+///   * [`radicle_source::Error::PathNotFound`] if `prefix` does not exist.
+///   * [`error::Error::NotADirectory`] if `prefix` is a file.
+///   * A tree or object header could not be read.
+pub fn tree_summary(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    prefix: &str,
+) -> Result<TreeSummary, error::Error> {
+    /// Adds the contents of `tree` to `summary`.
+    fn visit(
+        repo: &git2::Repository,
+        odb: &git2::Odb<'_>,
+        tree: &git2::Tree<'_>,
+        summary: &mut TreeSummary,
+    ) -> Result<(), error::Error> {
+        for entry in tree.iter() {
+            match entry.kind() {
+                Some(git2::ObjectType::Tree) => {
+                    summary.dir_count += 1;
+                    let sub_tree = repo.find_tree(entry.id()).map_err(git_error)?;
+                    visit(repo, odb, &sub_tree, summary)?;
+                },
+                Some(git2::ObjectType::Blob) => {
+                    let (size, _) = odb.read_header(entry.id()).map_err(git_error)?;
+                    summary.file_count += 1;
+                    summary.total_bytes += size as u64;
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    let tree = prefix_tree(repo, head, prefix.trim_matches('/'))?;
+    let odb = repo.odb().map_err(git_error)?;
+    let mut summary = TreeSummary::default();
+    visit(repo, &odb, &tree, &mut summary)?;
+
+    Ok(summary)
+}
+
+/// The name of `revision` as given by the user, i.e. the branch or tag name or the commit hash.
+pub fn revision_name<P>(revision: &Revision<P>) -> String {
+    match revision {
+        Revision::Tag { name } | Revision::Branch { name, .. } => name.clone(),
+        Revision::Sha { sha } => sha.0.to_string(),
+    }
+}
+
+/// Outcome of merging one revision into another, see [`merge_preview`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePreview {
+    /// The best common ancestor of both revisions.
+    pub merge_base: String,
+    /// Paths that can't be merged without conflicts.
+    pub conflicts: Vec<String>,
+    /// Whether `head` descends from `base`, so `base` can be fast-forwarded to `head`.
+    pub fast_forward: bool,
+}
+
+/// Previews merging the commit `head` into the commit `base` in memory, without touching any
+/// working copy or reference of `repo`.
+///
+/// # Errors
+///
+///   * [`error::Error::UnrelatedHistories`] if `base` and `head` have no common ancestor.
+///   * The commits could not be found or merged.
+pub fn merge_preview(
+    repo: &git2::Repository,
+    base: git2::Oid,
+    head: git2::Oid,
+) -> Result<MergePreview, error::Error> {
+    let merge_base = match repo.merge_base(base, head) {
+        Ok(merge_base) => merge_base,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => {
+            return Err(error::Error::UnrelatedHistories {
+                base: base.to_string(),
+                head: head.to_string(),
+            })
+        },
+        Err(err) => return Err(git_error(err)),
+    };
+
+    // If either commit contains the other the merge is trivial.
+    let conflicts = if merge_base == base || merge_base == head {
+        vec![]
+    } else {
+        let base = repo.find_commit(base).map_err(git_error)?;
+        let head = repo.find_commit(head).map_err(git_error)?;
+        let index = repo.merge_commits(&base, &head, None).map_err(git_error)?;
+        let mut conflicts = index
+            .conflicts()
+            .map_err(git_error)?
+            .filter_map(|conflict| {
+                let conflict = conflict.ok()?;
+                let entry = conflict.our.or(conflict.their).or(conflict.ancestor)?;
+                Some(String::from_utf8_lossy(&entry.path).into_owned())
+            })
+            .collect::<Vec<_>>();
+        conflicts.sort();
+        conflicts.dedup();
+        conflicts
+    };
+
+    Ok(MergePreview {
+        merge_base: merge_base.to_string(),
+        conflicts,
+        fast_forward: merge_base == base,
+    })
+}
+
+/// The type of objects to enumerate with [`list_objects`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectKind {
+    /// Commit objects.
+    Commit,
+    /// Tree objects, i.e. directories.
+    Tree,
+    /// Blob objects, i.e. file contents.
+    Blob,
+}
+
+/// Enumerates the oids of up to `limit` objects of type `kind` that are reachable from the commit
+/// `head`. Commits are listed in the order of a revwalk from `head`. Trees and blobs are listed
+/// in the order they are first found in the trees of those commits, each only once.
+///
+/// # Errors
+///
+/// The commit `head` or an object reachable from it could not be read.
+pub fn list_objects(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    kind: ObjectKind,
+    limit: usize,
+) -> Result<Vec<String>, error::Error> {
+    let mut revwalk = repo.revwalk().map_err(git_error)?;
+    revwalk.push(head).map_err(git_error)?;
+
+    let mut seen = HashSet::new();
+    let mut objects = Vec::new();
+    for oid in revwalk {
+        if objects.len() >= limit {
+            break;
+        }
+        let oid = oid.map_err(git_error)?;
+        if kind == ObjectKind::Commit {
+            objects.push(oid.to_string());
+            continue;
+        }
+
+        let tree = repo
+            .find_commit(oid)
+            .and_then(|commit| commit.tree())
+            .map_err(git_error)?;
+        if !seen.insert(tree.id()) {
+            continue;
+        }
+        if kind == ObjectKind::Tree {
+            objects.push(tree.id().to_string());
+        }
+        tree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+            // The contents of a tree we have seen before have been visited already.
+            if !seen.insert(entry.id()) {
+                return git2::TreeWalkResult::Skip;
+            }
+            let matches = match entry.kind() {
+                Some(git2::ObjectType::Tree) => kind == ObjectKind::Tree,
+                Some(git2::ObjectType::Blob) => kind == ObjectKind::Blob,
+                _ => false,
+            };
+            if matches && objects.len() < limit {
+                objects.push(entry.id().to_string());
+            }
+            git2::TreeWalkResult::Ok
+        })
+        .map_err(git_error)?;
+    }
+
+    Ok(objects)
+}
+
+/// Resolves `revision` to a commit in the namespace of `reference`. If `revision` is `None` the
+/// commit `reference` points to is returned. Branches of a remote peer are looked up among the
+/// remote refs of that peer.
+///
+/// Unlike pointing a [`Browser`] at the revision this only reads the reference, the history
+/// is not walked.
+///
+/// # Errors
+///
+///   * [`error::Error::RevisionNotFound`] if `revision` does not exist.
+///   * If `reference` has no namespace or could not be read.
+pub fn resolve_revision(
+    repo: &git2::Repository,
+    reference: &Reference<Single>,
+    revision: Option<Revision<PeerId>>,
+) -> Result<git2::Oid, error::Error> {
+    let namespace = reference
+        .namespace
+        .as_ref()
+        .ok_or(crate::daemon::state::Error::MissingNamespace)?;
+    let refname = match &revision {
+        None => reference.to_string(),
+        Some(Revision::Branch {
+            name,
+            peer_id: None,
+        }) => format!("refs/namespaces/{}/refs/heads/{}", namespace, name),
+        Some(Revision::Branch {
+            name,
+            peer_id: Some(peer_id),
+        }) => format!(
+            "refs/namespaces/{}/refs/remotes/{}/heads/{}",
+            namespace, peer_id, name
+        ),
+        Some(Revision::Tag { name }) => format!("refs/namespaces/{}/refs/tags/{}", namespace, name),
+        Some(Revision::Sha { sha }) => sha.0.to_string(),
+    };
+
+    repo.revparse_single(&format!("{}^{{commit}}", refname))
+        .map(|object| object.id())
+        .map_err(|err| match (&revision, err.code()) {
+            (Some(revision), git2::ErrorCode::NotFound | git2::ErrorCode::InvalidSpec) => {
+                error::Error::RevisionNotFound(revision_name(revision))
+            },
+            _ => git_error(err),
+        })
+}
+
+/// Walks the history of `head` in `repo`, latest first, in the order of [`Browser::get`].
+///
+/// Setting up a [`Browser`] collects the complete history up front. Here commits are only
+/// read as the iterator is advanced, so callers that stop early don't pay for the rest of the
+/// history.
+///
+/// # Errors
+///
+/// Will return [`error::Error`] if the walk could not be started at `head`. The iterator
+/// yields an error for every commit that could not be read.
+pub fn history(
+    repo: &git2::Repository,
+    head: git2::Oid,
+) -> Result<impl Iterator<Item = Result<git::Commit, error::Error>> + '_, error::Error> {
+    let mut revwalk = repo.revwalk().map_err(git_error)?;
+    revwalk.push(head).map_err(git_error)?;
+
+    Ok(revwalk.map(move |oid| {
+        let commit = repo
+            .find_commit(oid.map_err(git_error)?)
+            .map_err(git_error)?;
+        git::Commit::try_from(commit)
+            .map_err(|err| radicle_source::Error::from(git::error::Error::from(err)).into())
+    }))
+}
+
+/// Wraps a bare [`git2::Error`] the same way errors of [`radicle_source`] operations are.
+fn git_error(err: git2::Error) -> error::Error {
+    radicle_source::Error::from(git::error::Error::from(err)).into()
+}
+
+/// Turns `err` into [`error::Error::RevisionNotFound`] if it was caused by a branch, tag or
+/// commit that does not exist while resolving `revision`, e.g. with [`resolve_rev`]. Other errors
+/// are returned unchanged.
+///
+/// Only pass errors of resolving the revision. Browsing a resolved revision fails with the same
+/// git errors for missing paths and objects, use [`resolve_revision`] to resolve it first.
+pub fn revision_not_found(err: error::Error, revision: Option<String>) -> error::Error {
+    use radicle_source::surf::git::error::Error as GitError;
+
+    match (&err, revision) {
+        (
+            error::Error::Source(radicle_source::Error::Git(
+                GitError::NotBranch(_) | GitError::NotTag(_),
+            )),
+            Some(revision),
+        ) => error::Error::RevisionNotFound(revision),
+        (
+            error::Error::Source(radicle_source::Error::Git(GitError::Git(git_error))),
+            Some(revision),
+        ) if git_error.code() == git2::ErrorCode::NotFound => {
+            error::Error::RevisionNotFound(revision)
+        },
+        _ => err,
+    }
+}
+
+/// A [`radicle_source::Commit`] with a [`CommitHeader`], together with the headers of its
+/// parents.
+#[derive(Clone, Serialize)]
+pub struct CommitWithParents {
+    /// The commit header.
+    pub header: CommitHeader,
+    /// The change statistics for this commit.
+    pub stats: radicle_source::commit::Stats,
+    /// The changeset introduced by this commit.
+    pub diff: radicle_source::surf::diff::Diff,
+    /// The list of branches this commit belongs to.
+    pub branches: Vec<Branch>,
+    /// The headers of the parents in the order they are recorded in the commit. Empty for the
+    /// root commit.
+    pub parents: Vec<CommitHeader>,
+}
+
+/// Retrieves the commit `sha1` like [`radicle_source::commit`] and adds the headers of its
+/// parents.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the commit could not be found or the surf
+/// interaction fails.
+pub fn commit_with_parents(
+    browser: &mut Browser<'_>,
+    sha1: git2::Oid,
+) -> Result<CommitWithParents, radicle_source::Error> {
+    let radicle_source::Commit {
+        stats,
+        diff,
+        branches,
+        ..
+    } = radicle_source::commit(browser, sha1)?;
+
+    // The browser now points at the history of `sha1`, which contains all of its ancestors.
+    let history = browser.get();
+    let commit = history.first();
+    let parents = commit
+        .parents
+        .iter()
+        .filter_map(|parent| history.iter().find(|ancestor| ancestor.id == *parent))
+        .map(CommitHeader::from)
+        .collect();
+
+    Ok(CommitWithParents {
+        header: CommitHeader::from(commit),
+        stats,
+        diff,
+        branches,
+        parents,
+    })
+}
+
+/// Opens the repository at `repo_path`, which doesn't have to be a project, and calls `callback`
+/// with a [`Browser`] pointed at the repository's `HEAD`.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the repository could not be opened, has no
+/// branches or `callback` fails.
+pub fn using_local<T, F>(repo_path: &str, callback: F) -> Result<T, radicle_source::Error>
+where
+    F: FnOnce(&mut Browser<'_>) -> Result<T, radicle_source::Error>,
+{
+    let (branch, detached_head) = {
+        let repo = git2::Repository::open(repo_path).map_err(git::error::Error::from)?;
+        let head = repo.head().map_err(git::error::Error::from)?;
+        if head.is_branch() {
+            let name = head.shorthand().ok_or(radicle_source::Error::NoBranches)?;
+            (name.to_string(), None)
+        } else {
+            // A browser has to start out at a branch, so we start at any branch and move to
+            // the detached `HEAD` afterwards.
+            let first_branch = repo
+                .branches(Some(git2::BranchType::Local))
+                .map_err(git::error::Error::from)?
+                .filter_map(|branch| branch.ok()?.0.name().ok()?.map(String::from))
+                .min()
+                .ok_or(radicle_source::Error::NoBranches)?;
+            (first_branch, head.target())
+        }
+    };
+
+    let repo = git::Repository::new(repo_path)?;
+    let mut browser = Browser::new(&repo, git::Branch::local(&branch))?;
+    if let Some(oid) = detached_head {
+        browser.commit(oid)?;
+    }
+
+    callback(&mut browser)
+}
+
+/// A [`Blob`] that may have been returned without syntax highlighting because it is too large,
+/// see [`highlighted_blob`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightedBlob {
+    /// The blob itself.
+    #[serde(flatten)]
+    pub blob: Blob,
+    /// Whether highlighting was requested but skipped because the blob is larger than the limit.
+    pub truncated_highlight: bool,
+    /// Whether the blob is a git-LFS pointer rather than the content of the file.
+    pub lfs: bool,
+    /// The object the blob points to if it is a git-LFS pointer.
+    pub lfs_pointer: Option<LfsPointer>,
+    /// The lines the content was narrowed to, see [`blob_lines`].
+    pub lines: Option<LineRange>,
+}
+
+/// Lines of a blob, numbered from 1. Both ends are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineRange {
+    /// The first line.
+    pub start_line: usize,
+    /// The last line. Smaller than `start_line` if the range is empty.
+    pub end_line: usize,
+    /// The number of lines of the whole blob.
+    pub line_count: usize,
+}
+
+/// The object a git-LFS pointer file refers to, see [`parse_lfs_pointer`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LfsPointer {
+    /// The hash of the object including the hash algorithm, e.g. `sha256:4d7a…`.
+    pub oid: String,
+    /// The size of the object in bytes.
+    pub size: u64,
+}
+
+/// Git-LFS pointer files are never larger than this, so larger blobs aren't parsed.
+const LFS_POINTER_MAX_BYTES: usize = 1024;
+
+/// Parses `content` as a git-LFS pointer file. Returns `None` if it isn't one.
+///
+/// See <https://github.com/git-lfs/git-lfs/blob/main/docs/spec.md>.
+pub fn parse_lfs_pointer(content: &str) -> Option<LfsPointer> {
+    if content.len() > LFS_POINTER_MAX_BYTES || !content.starts_with("version https://git-lfs") {
+        return None;
+    }
+
+    let mut oid = None;
+    let mut size = None;
+    for line in content.lines().skip(1) {
+        match line.split_once(' ') {
+            Some(("oid", value)) => oid = Some(value.to_string()),
+            Some(("size", value)) => size = Some(value.parse().ok()?),
+            _ => {},
+        }
+    }
+
+    Some(LfsPointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+/// Retrieves the blob at `path` like [`radicle_source::blob::highlighting::blob`], but only
+/// highlights it with `theme` if its content is at most `max_highlight_bytes` long. Highlighted
+/// content is many times larger than the source, which adds up for large files.
+///
+/// Blobs that are git-LFS pointers are flagged and returned without highlighting, the object
+/// they point to is not fetched.
+///
+/// If `revision` is `None` the blob is looked up in the revision the `browser` is currently
+/// pointed at.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the revision could not be resolved or `path` does
+/// not exist.
+pub fn highlighted_blob(
+    browser: &mut Browser<'_>,
+    revision: Option<Revision<PeerId>>,
+    path: &str,
+    theme: Option<&str>,
+    max_highlight_bytes: usize,
+) -> Result<HighlightedBlob, radicle_source::Error> {
+    let blob = radicle_source::blob(browser, revision, path)?;
+    let lfs_pointer = match &blob.content {
+        radicle_source::BlobContent::Plain(content) => parse_lfs_pointer(content),
+        _ => None,
+    };
+    if lfs_pointer.is_some() {
+        return Ok(HighlightedBlob {
+            blob,
+            truncated_highlight: false,
+            lfs: true,
+            lfs_pointer,
+            lines: None,
+        });
+    }
+
+    let theme = match (theme, &blob.content) {
+        (Some(theme), radicle_source::BlobContent::Plain(content)) => {
+            if content.len() > max_highlight_bytes {
+                return Ok(HighlightedBlob {
+                    blob,
+                    truncated_highlight: true,
+                    lfs: false,
+                    lfs_pointer: None,
+                    lines: None,
+                });
+            }
+            theme
+        },
+        _ => {
+            return Ok(HighlightedBlob {
+                blob,
+                truncated_highlight: false,
+                lfs: false,
+                lfs_pointer: None,
+                lines: None,
+            })
+        },
+    };
+
+    // The browser already points at `revision`.
+    let blob =
+        radicle_source::blob::highlighting::blob::<PeerId>(browser, None, path, Some(theme))?;
+    Ok(HighlightedBlob {
+        blob,
+        truncated_highlight: false,
+        lfs: false,
+        lfs_pointer: None,
+        lines: None,
+    })
+}
+
+/// Narrows the content of a text `blob` to the lines from `start_line` to `end_line`, which
+/// default to the first and the last line. The range is clamped to the lines of the blob. The
+/// selected lines are highlighted like [`highlighted_blob`] does.
+///
+/// `blob` has to be retrieved without highlighting. Binary blobs are returned unchanged.
+#[must_use]
+pub fn blob_lines(
+    mut blob: HighlightedBlob,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    theme: Option<&str>,
+    max_highlight_bytes: usize,
+) -> HighlightedBlob {
+    let content = match &blob.blob.content {
+        radicle_source::BlobContent::Plain(content) => content,
+        _ => return blob,
+    };
+
+    let lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    let line_count = lines.len();
+    let start_line = start_line.unwrap_or(1).clamp(1, line_count.max(1));
+    let end_line = end_line
+        .unwrap_or(line_count)
+        .max(start_line)
+        .min(line_count);
+    let fragment = lines
+        .get(start_line - 1..end_line)
+        .unwrap_or_default()
+        .concat();
+
+    blob.lines = Some(LineRange {
+        start_line,
+        end_line,
+        line_count,
+    });
+    blob.blob.content = match theme {
+        Some(_) if blob.lfs => radicle_source::BlobContent::Plain(fragment),
+        Some(_) if fragment.len() > max_highlight_bytes => {
+            blob.truncated_highlight = true;
+            radicle_source::BlobContent::Plain(fragment)
+        },
+        Some(theme) => match radicle_source::syntax::highlight(&blob.blob.path, &fragment, theme) {
+            Some(html) => radicle_source::BlobContent::Html(html),
+            None => radicle_source::BlobContent::Plain(fragment),
+        },
+        None => radicle_source::BlobContent::Plain(fragment),
+    };
+
+    blob
+}
+
+/// File names that [`find_readme`] recognizes, compared case-insensitively, in order of
+/// preference.
+const README_NAMES: [&str; 2] = ["readme.md", "readme"];
+
+/// Looks for a README in the directory at `prefix` and retrieves it like [`highlighted_blob`].
+///
+/// If `revision` is `None` the revision the `browser` is currently pointed at is used.
+///
+/// Returns `None` if the directory has no README.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the revision could not be resolved or `prefix` does
+/// not exist.
+pub fn find_readme(
+    browser: &mut Browser<'_>,
+    revision: Option<Revision<PeerId>>,
+    prefix: Option<String>,
+    theme: Option<&str>,
+    max_highlight_bytes: usize,
+) -> Result<Option<HighlightedBlob>, radicle_source::Error> {
+    if let Some(revision) = revision {
+        browser.rev(Rev::try_from(revision)?)?;
+    }
+
+    let prefix = prefix.unwrap_or_default();
+    let prefix = prefix.trim_matches('/');
+    let root = browser.get_directory()?;
+    let directory = if prefix.is_empty() {
+        root
+    } else {
+        let path = file_system::Path::from_str(prefix)?;
+        root.find_directory(path.clone())
+            .ok_or(radicle_source::Error::PathNotFound(path))?
+    };
+
+    let files = directory
+        .iter()
+        .filter_map(|content| match content {
+            DirectoryContents::File { name, .. } => Some(name.to_string()),
+            DirectoryContents::Directory(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let readme = README_NAMES
+        .iter()
+        .find_map(|readme| files.iter().find(|name| name.eq_ignore_ascii_case(readme)));
+
+    match readme {
+        None => Ok(None),
+        Some(name) => {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            highlighted_blob(browser, None, &path, theme, max_highlight_bytes).map(Some)
+        },
+    }
+}
+
+/// Returns a hex digest that identifies the content of `blob`, independent of the revision and
+/// path it was retrieved from.
+///
+/// The digest is the git object id of the content, so for plain and binary blobs it matches the
+/// id of the blob in the repository. Highlighted blobs hash the generated HTML.
+#[must_use]
+pub fn blob_content_hash(blob: &Blob) -> String {
+    let bytes = match &blob.content {
+        radicle_source::BlobContent::Plain(content)
+        | radicle_source::BlobContent::Html(content) => content.as_bytes(),
+        radicle_source::BlobContent::Binary(bytes) => bytes.as_slice(),
+    };
+    git2::Oid::hash_object(git2::ObjectType::Blob, bytes)
+        .expect("hashing in-memory content does not fail")
+        .to_string()
+}
+
+/// Retrieves the blob at `path` of the repository at `repo_path` like [`radicle_source::blob`].
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the repository could not be opened, the revision
+/// could not be resolved or `path` does not exist.
+pub fn local_blob(
+    repo_path: &str,
+    revision: Option<Revision<PeerId>>,
+    path: &str,
+) -> Result<Blob, radicle_source::Error> {
+    using_local(repo_path, |browser| {
+        radicle_source::blob(browser, revision, path)
+    })
+}
+
+/// Retrieves the tree at `prefix` of the repository at `repo_path` like
+/// [`radicle_source::tree`].
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the repository could not be opened, the revision
+/// could not be resolved or `prefix` does not exist.
+pub fn local_tree(
+    repo_path: &str,
+    revision: Option<Revision<PeerId>>,
+    prefix: Option<String>,
+) -> Result<Tree, radicle_source::Error> {
+    using_local(repo_path, |browser| {
+        radicle_source::tree(browser, revision, prefix)
+    })
+}
+
+/// Computes the uncommitted changes in the working copy at `repo_path`, i.e. the difference
+/// between the tree of `HEAD` and the working directory including staged changes. Untracked files
+/// are left out.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the repository could not be opened, has no `HEAD`
+/// commit or the diff could not be computed.
+pub fn working_tree_diff(
+    repo_path: &str,
+) -> Result<radicle_source::surf::diff::Diff, radicle_source::Error> {
+    let repo = git2::Repository::open(repo_path).map_err(git::error::Error::from)?;
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_tree())
+        .map_err(git::error::Error::from)?;
+    let diff = repo
+        .diff_tree_to_workdir_with_index(Some(&head), None)
+        .map_err(git::error::Error::from)?;
+
+    Ok(radicle_source::surf::diff::Diff::try_from(diff).map_err(git::error::Error::from)?)
+}
+
+/// Retrieves the tree at `prefix` like [`radicle_source::tree`], but tells a `prefix` that is a
+/// file apart from one that doesn't exist.
+///
+/// # Errors
+///
+///   * [`error::Error::NotADirectory`] if `prefix` is a file.
+///   * The revision could not be resolved or `prefix` does not exist.
+pub fn tree(
+    browser: &mut Browser<'_>,
+    revision: Option<Revision<PeerId>>,
+    prefix: Option<String>,
+) -> Result<Tree, error::Error> {
+    match radicle_source::tree(browser, revision, prefix.clone()) {
+        Err(radicle_source::Error::PathNotFound(path)) => {
+            // The browser already points at `revision`.
+            let root = browser
+                .get_directory()
+                .map_err(radicle_source::Error::from)?;
+            if root.find_file(path.clone()).is_some() {
+                Err(error::Error::NotADirectory(prefix.unwrap_or_default()))
+            } else {
+                Err(radicle_source::Error::PathNotFound(path).into())
+            }
+        },
+        result => Ok(result?),
+    }
+}
+
+/// A submodule of a tree, i.e. an entry that points to a commit of another repository.
+/// [`radicle_source::tree`] leaves these entries out, see [`submodules`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submodule {
+    /// Name of the entry in its tree.
+    pub name: String,
+    /// Path of the entry from the root of the repository.
+    pub path: String,
+    /// The commit of the submodule repository the entry points to.
+    pub oid: git2::Oid,
+    /// The URL configured for the submodule in `.gitmodules`, if any.
+    pub url: Option<String>,
+}
+
+/// Serializes like a [`TreeEntry`] with the object type `SUBMODULE`.
+impl Serialize for Submodule {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SubmoduleInfo<'a> {
+            name: &'a str,
+            object_type: &'static str,
+            last_commit: Option<Header>,
+        }
+
+        #[derive(Serialize)]
+        struct Target<'a> {
+            oid: String,
+            url: Option<&'a str>,
+        }
+
+        let mut state = serializer.serialize_struct("Submodule", 3)?;
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field(
+            "info",
+            &SubmoduleInfo {
+                name: &self.name,
+                object_type: "SUBMODULE",
+                last_commit: None,
+            },
+        )?;
+        state.serialize_field(
+            "submodule",
+            &Target {
+                oid: self.oid.to_string(),
+                url: self.url.as_deref(),
+            },
+        )?;
+        state.end()
+    }
+}
+
+/// A [`Tree`] together with the submodules in it. The submodules are serialized as entries
+/// following the entries of the tree.
+pub struct TreeWithSubmodules {
+    /// The tree as listed by [`radicle_source::tree`].
+    pub tree: Tree,
+    /// The submodules in the tree, see [`submodules`].
+    pub submodules: Vec<Submodule>,
+}
+
+impl Serialize for TreeWithSubmodules {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        #[serde(untagged)]
+        enum Entry<'a> {
+            Tree(&'a TreeEntry),
+            Submodule(&'a Submodule),
+        }
+
+        let entries = self
+            .tree
+            .entries
+            .iter()
+            .map(Entry::Tree)
+            .chain(self.submodules.iter().map(Entry::Submodule))
+            .collect::<Vec<_>>();
+
+        let mut state = serializer.serialize_struct("Tree", 3)?;
+        state.serialize_field("path", &self.tree.path)?;
+        state.serialize_field("entries", &entries)?;
+        state.serialize_field("info", &self.tree.info)?;
+        state.end()
+    }
+}
+
+/// Lists the submodules in the directory `prefix` of the tree of `commit`, sorted by name. The
+/// URLs of the submodules are read from the `.gitmodules` file at the root of the tree.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the commit could not be found or the git
+/// interaction fails. A `prefix` that does not exist yields no submodules.
+pub fn submodules(
+    repo: &git2::Repository,
+    commit: git2::Oid,
+    prefix: Option<&str>,
+) -> Result<Vec<Submodule>, radicle_source::Error> {
+    let root = repo
+        .find_commit(commit)
+        .and_then(|commit| commit.tree())
+        .map_err(git::error::Error::from)?;
+    let prefix = prefix
+        .map(|prefix| prefix.trim_matches('/'))
+        .filter(|prefix| !prefix.is_empty());
+    let tree = match prefix {
+        None => root.clone(),
+        Some(prefix) => match root.get_path(Path::new(prefix)) {
+            Ok(entry) => entry
+                .to_object(repo)
+                .and_then(|object| object.peel_to_tree())
+                .map_err(git::error::Error::from)?,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(vec![]),
+            Err(err) => return Err(git::error::Error::from(err).into()),
+        },
+    };
+
+    let urls = root
+        .get_name(".gitmodules")
+        .and_then(|entry| entry.to_object(repo).ok()?.into_blob().ok())
+        .map(|blob| parse_gitmodules(&String::from_utf8_lossy(blob.content())))
+        .unwrap_or_default();
+
+    let mut submodules = tree
+        .iter()
+        .filter(|entry| entry.kind() == Some(git2::ObjectType::Commit))
+        .filter_map(|entry| {
+            let name = entry.name()?.to_string();
+            let path = prefix.map_or_else(|| name.clone(), |prefix| format!("{}/{}", prefix, name));
+            Some(Submodule {
+                url: urls.get(&path).cloned(),
+                oid: entry.id(),
+                name,
+                path,
+            })
+        })
+        .collect::<Vec<_>>();
+    submodules.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(submodules)
+}
+
+/// Parses the content of a `.gitmodules` file into a map from submodule paths to their URLs.
+/// Sections without a `path` or `url` are left out.
+fn parse_gitmodules(content: &str) -> HashMap<String, String> {
+    let mut urls = HashMap::new();
+    let mut section: (Option<String>, Option<String>) = (None, None);
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        } else if line.starts_with('[') {
+            if let (Some(path), Some(url)) = std::mem::take(&mut section) {
+                urls.insert(path, url);
+            }
+        } else if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim() {
+                "path" => section.0 = Some(value.trim_end_matches('/').to_string()),
+                "url" => section.1 = Some(value),
+                _ => {},
+            }
+        }
+    }
+    if let (Some(path), Some(url)) = section {
+        urls.insert(path, url);
+    }
+
+    urls
+}
+
+/// Resolves `rev` to the oid of a commit. `rev` may be a full or abbreviated commit hash, a
+/// branch or a tag.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if `rev` could not be resolved to a commit. If an
+/// abbreviated hash is ambiguous the underlying [`git2::Error`] has the code
+/// [`git2::ErrorCode::Ambiguous`].
+pub fn resolve_rev(browser: &Browser<'_>, rev: &str) -> Result<git2::Oid, radicle_source::Error> {
+    Ok(browser.oid(&format!("{}^{{commit}}", rev))?)
+}
+
+/// Name for the storage bucket caching [`languages`] by commit.
+const LANGUAGES_BUCKET_NAME: &str = "languages";
+
+/// Name for the storage bucket caching [`tree_summary`] by commit and prefix.
+const TREE_SUMMARIES_BUCKET_NAME: &str = "tree-summaries";
+
+/// Maximum number of entries kept in each of the buckets caching [`languages`] and
+/// [`tree_summary`].
+const MAX_CACHED_ENTRIES: usize = 1024;
+
+/// Directories whose contents are not written by the project itself and are left out of
+/// [`languages`].
+const VENDORED_DIRECTORIES: [&str; 3] = ["vendor", "node_modules", "third_party"];
+
+/// The share of a language in the files of a revision, see [`languages`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageStat {
+    /// Name of the language.
+    pub language: String,
+    /// Size of all files in the language.
+    pub bytes: u64,
+    /// Share of the language in the size of all classified files, rounded to one decimal.
+    pub percentage: f64,
+}
+
+/// The language of the file `name` based on its extension, if it is a known one.
+fn file_language(name: &str) -> Option<&'static str> {
+    match name {
+        "Makefile" => return Some("Makefile"),
+        "Dockerfile" => return Some("Dockerfile"),
+        _ => {},
+    }
+
+    let language = match Path::new(name).extension()?.to_str()? {
+        "rs" => "Rust",
+        "hs" | "lhs" => "Haskell",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "svelte" => "Svelte",
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" => "SCSS",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" => "C++",
+        "go" => "Go",
+        "py" => "Python",
+        "rb" => "Ruby",
+        "java" => "Java",
+        "kt" => "Kotlin",
+        "scala" => "Scala",
+        "swift" => "Swift",
+        "ex" | "exs" => "Elixir",
+        "erl" => "Erlang",
+        "clj" => "Clojure",
+        "ml" | "mli" => "OCaml",
+        "php" => "PHP",
+        "lua" => "Lua",
+        "zig" => "Zig",
+        "nix" => "Nix",
+        "sol" => "Solidity",
+        "sh" | "bash" => "Shell",
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Computes how much of the files of the commit `head` are written in each language. Files are
+/// classified by their extension, binary files and the contents of vendored directories are
+/// skipped. The result is ordered by size, largest first.
+///
+/// Only the blobs of classified files are read, to tell binary files apart. `cancel` is checked
+/// before every tree is read.
+///
+/// # Errors
+///
+///   * The tree of `head` could not be read.
+///   * [`error::Error::Cancelled`] if `cancel` was cancelled during the walk.
+pub fn languages(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    cancel: &CancellationToken,
+) -> Result<Vec<LanguageStat>, error::Error> {
+    /// Adds the size of all classified files below `tree` to `bytes`.
+    fn visit(
+        repo: &git2::Repository,
+        tree: &git2::Tree<'_>,
+        bytes: &mut BTreeMap<&'static str, u64>,
+        cancel: &CancellationToken,
+    ) -> Result<(), error::Error> {
+        ensure_not_cancelled(cancel)?;
+        for entry in tree.iter() {
+            let name = match entry.name() {
+                Some(name) => name,
+                None => continue,
+            };
+            match entry.kind() {
+                Some(git2::ObjectType::Tree) => {
+                    if !VENDORED_DIRECTORIES.contains(&name) {
+                        let sub_tree = repo.find_tree(entry.id()).map_err(git_error)?;
+                        visit(repo, &sub_tree, bytes, cancel)?;
+                    }
+                },
+                Some(git2::ObjectType::Blob) => {
+                    let language = match file_language(name) {
+                        Some(language) => language,
+                        None => continue,
+                    };
+                    let blob = repo.find_blob(entry.id()).map_err(git_error)?;
+                    let contents = blob.content();
+                    if contents.contains(&0) || std::str::from_utf8(contents).is_err() {
+                        continue;
+                    }
+                    *bytes.entry(language).or_default() += contents.len() as u64;
+                },
+                // Submodules are skipped.
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    let root = prefix_tree(repo, head, "")?;
+    let mut bytes = BTreeMap::new();
+    visit(repo, &root, &mut bytes, cancel)?;
+
+    let total = bytes.values().sum::<u64>();
+    let mut languages = bytes
+        .into_iter()
+        .map(|(language, bytes)| LanguageStat {
+            language: language.to_string(),
+            bytes,
+            percentage: (bytes as f64 * 1000.0 / total as f64).round() / 10.0,
+        })
+        .collect::<Vec<_>>();
+    languages.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    Ok(languages)
+}
+
+/// The [`languages`] of the commit `oid` stored with [`cache_languages`], if any.
+///
+/// # Errors
+///
+/// Errors if we cannot read data from the store.
+pub fn cached_languages(
+    store: &kv::Store,
+    oid: git2::Oid,
+) -> Result<Option<Vec<LanguageStat>>, error::Error> {
+    Ok(store
+        .bucket::<String, kv::Json<Vec<LanguageStat>>>(Some(LANGUAGES_BUCKET_NAME))?
+        .get(oid.to_string())?
+        .map(|json| json.0))
+}
+
+/// Stores the [`languages`] of the commit `oid`. Commits are immutable, so the entry never needs
+/// to be invalidated. It may be evicted once [`MAX_CACHED_ENTRIES`] commits are cached.
+///
+/// # Errors
+///
+/// Errors if we cannot write data to the store.
+pub fn cache_languages(
+    store: &kv::Store,
+    oid: git2::Oid,
+    languages: &[LanguageStat],
+) -> Result<(), error::Error> {
+    set_bounded(
+        &store.bucket::<String, kv::Json<Vec<LanguageStat>>>(Some(LANGUAGES_BUCKET_NAME))?,
+        oid.to_string(),
+        kv::Json(languages.to_vec()),
+    )
+}
+
+/// Stores `value` at `key` in `bucket`. Entries are evicted first while the bucket holds
+/// [`MAX_CACHED_ENTRIES`] or more. The keys start with a commit hash, so the evicted entries
+/// are effectively random.
+///
+/// # Errors
+///
+/// Errors if we cannot read or write data of the store.
+fn set_bounded<V>(
+    bucket: &kv::Bucket<'_, String, V>,
+    key: String,
+    value: V,
+) -> Result<(), error::Error>
+where
+    V: kv::Value,
+{
+    while bucket.len() >= MAX_CACHED_ENTRIES {
+        let evicted = match bucket.iter().next() {
+            Some(item) => item?.key::<String>()?,
+            None => break,
+        };
+        bucket.remove(evicted)?;
+    }
+    bucket.set(key, value)?;
+    Ok(())
+}
+
+/// The key of the [`tree_summary`] of `prefix` in the commit `oid` in its storage bucket.
+fn tree_summary_key(oid: git2::Oid, prefix: Option<&str>) -> String {
+    format!("{}:{}", oid, prefix.unwrap_or_default().trim_matches('/'))
+}
+
+/// The [`tree_summary`] of `prefix` in the commit `oid` stored with [`cache_tree_summary`], if
+/// any.
+///
+/// # Errors
+///
+/// Errors if we cannot read data from the store.
+pub fn cached_tree_summary(
+    store: &kv::Store,
+    oid: git2::Oid,
+    prefix: Option<&str>,
+) -> Result<Option<TreeSummary>, error::Error> {
+    Ok(store
+        .bucket::<String, kv::Json<TreeSummary>>(Some(TREE_SUMMARIES_BUCKET_NAME))?
+        .get(tree_summary_key(oid, prefix))?
+        .map(|json| json.0))
+}
+
+/// Stores the [`tree_summary`] of `prefix` in the commit `oid`. Like [`cache_languages`] the
+/// entry never needs to be invalidated.
+///
+/// # Errors
+///
+/// Errors if we cannot write data to the store.
+pub fn cache_tree_summary(
+    store: &kv::Store,
+    oid: git2::Oid,
+    prefix: Option<&str>,
+    summary: TreeSummary,
+) -> Result<(), error::Error> {
+    set_bounded(
+        &store.bucket::<String, kv::Json<TreeSummary>>(Some(TREE_SUMMARIES_BUCKET_NAME))?,
+        tree_summary_key(oid, prefix),
+        kv::Json(summary),
+    )
+}
+
+/// Whether the local branch `name` exists in the repository `browser` operates on.
+///
+/// The branch is looked up directly instead of listing every branch.
+///
+/// # Errors
+///
+/// Will return [`radicle_source::Error`] if the lookup failed for any other reason than the
+/// branch not being present.
+pub fn branch_exists(browser: &Browser<'_>, name: &str) -> Result<bool, radicle_source::Error> {
+    use radicle_source::surf::git::error::Error as GitError;
+
+    let reference = format!("refs/heads/{}", name);
+    if !git2::Reference::is_valid_name(&reference) {
+        return Ok(false);
+    }
+
+    match browser.oid(&reference) {
+        Ok(_) => Ok(true),
+        Err(GitError::Git(err)) if err.code() == git2::ErrorCode::NotFound => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use radicle_source::BlobContent;
+
+    /// Creates a repository with a `master` branch containing one commit for every
+    /// `(author, message)` pair. The first pair is the root commit.
+    fn repository(commits: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let tree_oid = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_oid).unwrap();
+
+        let mut parent: Option<git2::Oid> = None;
+        for (author, message) in commits {
+            let signature =
+                git2::Signature::now(author, &format!("{}@example.com", author)).unwrap();
+            let parents = parent
+                .map(|oid| repo.find_commit(oid).unwrap())
+                .into_iter()
+                .collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            parent = Some(
+                repo.commit(
+                    Some("refs/heads/master"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .unwrap(),
+            );
+        }
+
+        dir
+    }
+
+    /// Copies the local branches of `repo` into the namespace of a project, like they are stored
+    /// in the monorepo, and returns the reference to its `master` branch.
+    fn namespaced(repo: &git2::Repository) -> Reference<Single> {
+        use librad::{
+            git::{types::Namespace, Urn},
+            git_ext::RefLike,
+        };
+
+        let namespace = Namespace::from(Urn::new(radicle_git_ext::Oid::from(git2::Oid::zero())));
+        for branch in repo.branches(Some(git2::BranchType::Local)).unwrap() {
+            let (branch, _) = branch.unwrap();
+            repo.reference(
+                &format!(
+                    "refs/namespaces/{}/refs/heads/{}",
+                    namespace,
+                    branch.name().unwrap().unwrap()
+                ),
+                branch.get().target().unwrap(),
+                true,
+                "",
+            )
+            .unwrap();
+        }
+
+        Reference::head(namespace, None, RefLike::try_from("master").unwrap())
+    }
+
+    /// The [`commits`] of the `master` branch of the repository at `path`, see [`namespaced`].
+    fn master_commits(
+        path: &std::path::Path,
+        window: TimeWindow,
+        cancel: &CancellationToken,
+    ) -> Result<Commits, error::Error> {
+        let repo = git2::Repository::open(path).unwrap();
+        let reference = namespaced(&repo);
+        let head = resolve_revision(&repo, &reference, None).unwrap();
+        commits(&repo, &reference, head, window, cancel)
+    }
+
+    fn summaries(headers: &[CommitHeader]) -> Vec<&str> {
+        headers
+            .iter()
+            .map(|header| header.header.summary.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn search_commits_by_message() {
+        let dir = repository(&[
+            ("alice", "Initial commit"),
+            ("bob", "Extend the docs"),
+            ("alice", "Fix typo in docs"),
+        ]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+
+        let headers = search_commits(&repo, head, "THE DOCS", SearchField::Message, 10).unwrap();
+        assert_eq!(summaries(&headers), vec!["Extend the docs"]);
+
+        let headers = search_commits(&repo, head, "docs", SearchField::Message, 1).unwrap();
+        assert_eq!(summaries(&headers), vec!["Fix typo in docs"]);
+    }
+
+    #[test]
+    fn commits_within_time_window() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let repo = git2::Repository::init(dir.path()).unwrap();
+            let tree = repo
+                .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+                .unwrap();
+            let mut parent = None;
+            // The last commit is older than the one before it.
+            for (time, message) in [
+                (100, "First"),
+                (200, "Second"),
+                (300, "Third"),
+                (150, "Fourth"),
+            ] {
+                let signature =
+                    git2::Signature::new("alice", "alice@example.com", &git2::Time::new(time, 0))
+                        .unwrap();
+                let parents = parent
+                    .map(|oid| repo.find_commit(oid).unwrap())
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                let parents = parents.iter().collect::<Vec<_>>();
+                parent = Some(
+                    repo.commit(
+                        Some("refs/heads/master"),
+                        &signature,
+                        &signature,
+                        message,
+                        &tree,
+                        &parents,
+                    )
+                    .unwrap(),
+                );
+            }
+        }
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let mut window_summaries = |since, until| {
+            let window = TimeWindow { since, until };
+            let headers = master_commits(dir.path(), window, &CancellationToken::new())
+                .unwrap()
+                .headers;
+            assert_eq!(
+                summaries(&headers),
+                summaries(&commit_headers(&mut browser, None, window, 0, 10).unwrap())
+            );
+            summaries(&headers)
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            window_summaries(None, None),
+            ["Fourth", "Third", "Second", "First"]
+        );
+        assert_eq!(window_summaries(Some(200), None), ["Third", "Second"]);
+        assert_eq!(
+            window_summaries(None, Some(200)),
+            ["Fourth", "Second", "First"]
+        );
+        assert_eq!(window_summaries(Some(150), Some(250)), ["Fourth", "Second"]);
+        assert_eq!(window_summaries(Some(400), None), Vec::<String>::new());
+        assert_eq!(window_summaries(Some(300), Some(100)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn commit_headers_pages() {
+        let dir = repository(&[
+            ("alice", "Initial commit"),
+            ("bob", "Extend the docs"),
+            ("alice", "Fix typo in docs"),
+        ]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let headers = commit_headers(&mut browser, None, TimeWindow::default(), 0, 2).unwrap();
+        assert_eq!(
+            summaries(&headers),
+            vec!["Fix typo in docs", "Extend the docs"]
+        );
+        let headers = commit_headers(&mut browser, None, TimeWindow::default(), 1, 2).unwrap();
+        assert_eq!(summaries(&headers), vec!["Initial commit"]);
+        assert!(
+            commit_headers(&mut browser, None, TimeWindow::default(), 2, 2)
+                .unwrap()
+                .is_empty()
+        );
+
+        let headers_only = serde_json::to_value(
+            commit_headers(&mut browser, None, TimeWindow::default(), 0, 10).unwrap(),
+        )
+        .unwrap();
+        let full = serde_json::to_value(
+            master_commits(dir.path(), TimeWindow::default(), &CancellationToken::new()).unwrap(),
+        )
+        .unwrap();
+        assert!(headers_only
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|header| header.get("diff").is_none() && header.get("stats").is_none()));
+        assert!(headers_only.to_string().len() < full.to_string().len());
+    }
+
+    #[test]
+    fn mailmap_resolve() {
+        let mailmap = git2::Mailmap::from_buffer(
+            "# Contributors\n\
+             Alice Liddell <alice@example.com>\n\
+             <bob@example.com> <bob@old.example.com>\n\
+             Alice Liddell <alice@example.com> <alice@old.example.com> # moved\n\
+             Carol <carol@example.com> cc <carol@old.example.com>\n\
+             not an entry\n",
+        )
+        .unwrap();
+
+        let resolve = |name: &str, email: &str| {
+            let (name, email) = resolve_person(&mailmap, name, email);
+            format!("{} <{}>", name, email)
+        };
+        assert_eq!(
+            resolve("alice", "alice@example.com"),
+            "Alice Liddell <alice@example.com>"
+        );
+        assert_eq!(
+            resolve("alice", "alice@old.example.com"),
+            "Alice Liddell <alice@example.com>"
+        );
+        assert_eq!(
+            resolve("Bob", "bob@old.example.com"),
+            "Bob <bob@example.com>"
+        );
+        assert_eq!(
+            resolve("cc", "carol@old.example.com"),
+            "Carol <carol@example.com>"
+        );
+        assert_eq!(
+            resolve("someone", "carol@old.example.com"),
+            "someone <carol@old.example.com>"
+        );
+        assert_eq!(
+            resolve("dave", "dave@example.com"),
+            "dave <dave@example.com>"
+        );
+    }
+
+    #[test]
+    fn truncate_commit_header() {
+        let dir = repository(&[
+            ("alice", "Short"),
+            (
+                "alice",
+                "A rather long summary\n\nA description spanning\nseveral lines",
+            ),
+        ]);
+        let mut headers =
+            master_commits(dir.path(), TimeWindow::default(), &CancellationToken::new())
+                .unwrap()
+                .headers;
+
+        for header in &mut headers {
+            header.truncate(Some(10), Some(14));
+        }
+
+        let json = serde_json::to_value(&headers[0]).unwrap();
+        assert_eq!(json["summary"], "A rather …");
+        assert_eq!(json["description"], "A description…");
+        assert_eq!(json["truncated"], true);
+
+        let json = serde_json::to_value(&headers[1]).unwrap();
+        assert_eq!(json["summary"], "Short");
+        assert_eq!(json["description"], "");
+        assert_eq!(json.get("truncated"), None);
+    }
+
+    #[test]
+    fn commits_with_mailmap() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let repo = git2::Repository::init(dir.path()).unwrap();
+            let mailmap = repo
+                .blob(b"Alice Liddell <alice@example.com> <alice@old.example.com>\n")
+                .unwrap();
+            let mut builder = repo.treebuilder(None).unwrap();
+            builder.insert(".mailmap", mailmap, 0o100_644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+
+            let old = git2::Signature::now("alice", "alice@old.example.com").unwrap();
+            let root = repo
+                .commit(
+                    Some("refs/heads/master"),
+                    &old,
+                    &old,
+                    "Initial commit",
+                    &tree,
+                    &[],
+                )
+                .unwrap();
+            let root = repo.find_commit(root).unwrap();
+            let new = git2::Signature::now("Alice Liddell", "alice@example.com").unwrap();
+            repo.commit(
+                Some("refs/heads/master"),
+                &new,
+                &new,
+                "Extend the docs",
+                &tree,
+                &[&root],
+            )
+            .unwrap();
+        }
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let mut headers =
+            master_commits(dir.path(), TimeWindow::default(), &CancellationToken::new())
+                .unwrap()
+                .headers;
+        let authors = |headers: &[CommitHeader]| {
+            headers
+                .iter()
+                .map(|header| header.header.author.email.clone())
+                .collect::<BTreeSet<_>>()
+        };
+        assert_eq!(authors(&headers).len(), 2);
+
+        let mailmap = mailmap(&browser).unwrap();
+        for header in &mut headers {
+            header.apply_mailmap(&mailmap);
+        }
+        assert_eq!(
+            authors(&headers).into_iter().collect::<Vec<_>>(),
+            vec!["alice@example.com"]
+        );
+        assert!(headers
+            .iter()
+            .all(|header| header.header.author.name == "Alice Liddell"));
+    }
+
+    #[test]
+    fn contributors_by_commit_count() {
+        let dir = repository(&[
+            ("alice", "Initial commit"),
+            ("bob", "Extend the docs"),
+            ("alice", "Fix typo in docs"),
+            ("carol", "Add license"),
+            ("alice", "Release"),
+        ]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+        let history = history(&repo, head)
+            .unwrap()
+            .map(|commit| commit.unwrap().id.to_string())
+            .collect::<Vec<_>>();
+
+        let all = contributors(&repo, head, 100, true).unwrap();
+        assert_eq!(
+            all.iter()
+                .map(|contributor| (contributor.person.name.as_str(), contributor.commits))
+                .collect::<Vec<_>>(),
+            vec![("alice", 3), ("bob", 1), ("carol", 1)]
+        );
+        assert_eq!(all[0].last_commit, history[0]);
+        assert_eq!(all[0].first_commit, history[4]);
+
+        // Only the two latest commits are walked, so alice's first commit is her latest one.
+        let latest = contributors(&repo, head, 2, false).unwrap();
+        assert_eq!(
+            latest
+                .iter()
+                .map(|contributor| (contributor.person.name.as_str(), contributor.commits))
+                .collect::<Vec<_>>(),
+            vec![("alice", 1), ("carol", 1)]
+        );
+        assert_eq!(latest[0].first_commit, history[0]);
+    }
+
+    #[test]
+    fn commits_after_pages_through_history() {
+        let messages = (0..7).map(|i| format!("Commit {}", i)).collect::<Vec<_>>();
+        let commits = messages
+            .iter()
+            .map(|message| ("alice", message.as_str()))
+            .collect::<Vec<_>>();
+        let dir = repository(&commits);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let limit = NonZeroUsize::new(3).unwrap();
+        let mut seen = vec![];
+        let mut after = None;
+        loop {
+            let page = commits_after(&mut browser, None, after, limit).unwrap();
+            assert!(page.headers.len() <= 3);
+            seen.extend(
+                page.headers
+                    .iter()
+                    .map(|header| header.header.summary.clone()),
+            );
+            match page.next_cursor {
+                Some(cursor) => after = Some(git2::Oid::from_str(&cursor).unwrap()),
+                None => break,
+            }
+        }
+
+        let mut expected = messages;
+        expected.reverse();
+        assert_eq!(seen, expected);
+
+        let missing = git2::Oid::zero();
+        assert!(matches!(
+            commits_after(&mut browser, None, Some(missing), limit),
+            Err(error::Error::RevisionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn commit_header_time_offsets() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let repo = git2::Repository::init(dir.path()).unwrap();
+            let tree_oid = repo.treebuilder(None).unwrap().write().unwrap();
+            let tree = repo.find_tree(tree_oid).unwrap();
+            let author = git2::Signature::new(
+                "alice",
+                "alice@example.com",
+                &git2::Time::new(1_600_000_000, -180),
+            )
+            .unwrap();
+            let committer = git2::Signature::new(
+                "bob",
+                "bob@example.com",
+                &git2::Time::new(1_600_003_600, 330),
+            )
+            .unwrap();
+            repo.commit(
+                Some("refs/heads/master"),
+                &author,
+                &committer,
+                "Initial commit",
+                &tree,
+                &[],
+            )
+            .unwrap();
+        }
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let headers = commit_headers(&mut browser, None, TimeWindow::default(), 0, 1).unwrap();
+        let header = serde_json::to_value(&headers[0]).unwrap();
+        assert_eq!(header["committerTime"], 1_600_003_600);
+        assert_eq!(header["committerTimeOffset"], 330);
+        assert_eq!(header["authorTime"], 1_600_000_000);
+        assert_eq!(header["authorTimeOffset"], -180);
+    }
+
+    #[test]
+    fn commit_description_splits_on_blank_line() {
+        assert_eq!(commit_description("Initial commit"), "");
+        assert_eq!(commit_description("Initial commit\n"), "");
+        assert_eq!(
+            commit_description("Fix\n\nFixes the docs.\n"),
+            "Fixes the docs."
+        );
+        assert_eq!(
+            commit_description("Summary that\nspans two lines\n\nThe body\n"),
+            "The body"
+        );
+        assert_eq!(
+            commit_description(
+                "Extend the docs\r\n\r\nFirst paragraph\r\n  indented line\r\n\r\nSecond \
+                 paragraph\r\n"
+            ),
+            "First paragraph\r\n  indented line\r\n\r\nSecond paragraph"
+        );
+    }
+
+    #[test]
+    fn commit_header_description() {
+        let dir = repository(&[(
+            "alice",
+            "Extend the docs\r\n\r\nAdd a section.\r\n\r\nAnd another one.\r\n",
+        )]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let headers = commit_headers(&mut browser, None, TimeWindow::default(), 0, 1).unwrap();
+        let header = serde_json::to_value(&headers[0]).unwrap();
+        assert_eq!(
+            header["description"],
+            "Add a section.\r\n\r\nAnd another one."
+        );
+        assert_eq!(header["sha1"], headers[0].header.sha1.to_string());
+    }
+
+    #[test]
+    fn source_config_lists_branches_and_tags() {
+        let dir = repository(&[("alice", "Initial commit"), ("bob", "Extend the docs")]);
+        {
+            let repo = git2::Repository::open(dir.path()).unwrap();
+            let head = repo
+                .find_reference("refs/heads/master")
+                .unwrap()
+                .peel_to_commit()
+                .unwrap();
+            repo.branch("dev", &head, false).unwrap();
+            repo.tag_lightweight("v0.1.0", head.as_object(), false)
+                .unwrap();
+        }
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let config = source_config(&browser, "master".to_string()).unwrap();
+        assert_eq!(config.default_branch, "master");
+        assert_eq!(
+            config
+                .branches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["dev", "master"]
+        );
+        assert_eq!(
+            config
+                .tags
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["v0.1.0"]
+        );
+    }
+
+    /// Commits of the repository created by [`merge_repository`].
+    struct MergeCommits {
+        /// The root commit.
+        base: git2::Oid,
+        /// The first parent of `merge`, a child of `base`.
+        head: git2::Oid,
+        /// The second parent of `merge`, a child of `base`.
+        side: git2::Oid,
+        /// The merge commit `master` points to.
+        merge: git2::Oid,
+    }
+
+    /// Creates a repository where `master` points to a merge of two children of the root commit.
+    fn merge_repository() -> (tempfile::TempDir, MergeCommits) {
+        let dir = repository(&[("alice", "Initial commit"), ("bob", "Extend the docs")]);
+        let commits = {
+            let repo = git2::Repository::open(dir.path()).unwrap();
+            let head = repo
+                .find_reference("refs/heads/master")
+                .unwrap()
+                .peel_to_commit()
+                .unwrap();
+            let base = head.parent(0).unwrap();
+            let signature = git2::Signature::now("carol", "carol@example.com").unwrap();
+            let tree = head.tree().unwrap();
+            let side = repo
+                .commit(None, &signature, &signature, "Side change", &tree, &[&base])
+                .unwrap();
+            let side = repo.find_commit(side).unwrap();
+            let merge = repo
+                .commit(
+                    Some("refs/heads/master"),
+                    &signature,
+                    &signature,
+                    "Merge side change",
+                    &tree,
+                    &[&head, &side],
+                )
+                .unwrap();
+            MergeCommits {
+                base: base.id(),
+                head: head.id(),
+                side: side.id(),
+                merge,
+            }
+        };
+
+        (dir, commits)
+    }
+
+    #[test]
+    fn commit_graph_merge_parents() {
+        let (dir, MergeCommits { base, merge, .. }) = merge_repository();
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let nodes = commit_graph(&mut browser, None, 10).unwrap();
+        assert_eq!(nodes.len(), 4);
+
+        let merge_node = nodes
+            .iter()
+            .find(|node| node.sha1 == merge.to_string())
+            .unwrap();
+        assert_eq!(merge_node.summary, "Merge side change");
+        assert_eq!(merge_node.parents.len(), 2);
+
+        let root = nodes
+            .iter()
+            .find(|node| node.sha1 == base.to_string())
+            .unwrap();
+        assert!(root.parents.is_empty());
+
+        assert_eq!(commit_graph(&mut browser, None, 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn commit_with_parents_merge() {
+        let (
+            dir,
+            MergeCommits {
+                base,
+                head,
+                side,
+                merge,
+            },
+        ) = merge_repository();
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let commit = commit_with_parents(&mut browser, merge).unwrap();
+        assert_eq!(commit.header.header.sha1, merge);
+        assert_eq!(
+            commit
+                .parents
+                .iter()
+                .map(|parent| (parent.header.sha1, parent.header.summary.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(head, "Extend the docs"), (side, "Side change")]
+        );
+
+        let root = commit_with_parents(&mut browser, base).unwrap();
+        assert!(root.parents.is_empty());
+    }
+
+    /// Creates a repository with a single commit on `master` whose tree contains the given
+    /// files. Paths are relative to the repository root and separated by `/`.
+    fn repository_with_files(files: &[&str]) -> tempfile::TempDir {
+        fn write_tree(repo: &git2::Repository, files: &[Vec<&str>]) -> git2::Oid {
+            let mut builder = repo.treebuilder(None).unwrap();
+            let mut directories = std::collections::BTreeMap::<&str, Vec<Vec<&str>>>::new();
+            for file in files {
+                match file.as_slice() {
+                    [name] => {
+                        let blob = repo.blob(name.as_bytes()).unwrap();
+                        builder.insert(name, blob, 0o100_644).unwrap();
+                    },
+                    [directory, rest @ ..] => directories
+                        .entry(directory)
+                        .or_default()
+                        .push(rest.to_vec()),
+                    [] => unreachable!(),
+                }
+            }
+            for (directory, files) in directories {
+                let tree = write_tree(repo, &files);
+                builder.insert(directory, tree, 0o040_000).unwrap();
+            }
+            builder.write().unwrap()
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let files = files
+            .iter()
+            .map(|path| path.split('/').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let tree = repo.find_tree(write_tree(&repo, &files)).unwrap();
+        let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+        repo.commit(
+            Some("refs/heads/master"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn walk_tree_visits_every_entry() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs", "src/bin/main.rs", "a.txt"]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+
+        let root = prefix_tree(&repo, head, "").unwrap();
+        let mut lines = Vec::new();
+        walk_tree(&repo, root.id(), "", |entry| {
+            lines.push(serde_json::to_string(&entry).unwrap());
+            true
+        })
+        .unwrap();
+
+        let paths = lines
+            .iter()
+            .map(|line| {
+                let value = serde_json::from_str::<serde_json::Value>(line).unwrap();
+                value["path"].as_str().unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "src",
+                "src/bin",
+                "src/bin/main.rs",
+                "src/lib.rs",
+                "README.md",
+                "a.txt"
+            ]
+        );
+
+        let src = prefix_tree(&repo, head, "src").unwrap();
+        let mut paths = Vec::new();
+        walk_tree(&repo, src.id(), "src", |entry| {
+            paths.push(entry.path);
+            paths.len() < 2
+        })
+        .unwrap();
+        assert_eq!(paths, vec!["src/bin", "src/bin/main.rs"]);
+    }
+
+    #[test]
+    fn prefix_tree_rejects_missing_and_file_prefixes() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs"]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+
+        assert!(matches!(
+            prefix_tree(&repo, head, "missing"),
+            Err(error::Error::Source(radicle_source::Error::PathNotFound(_)))
+        ));
+        assert!(matches!(
+            prefix_tree(&repo, head, "README.md"),
+            Err(error::Error::NotADirectory(path)) if path == "README.md"
+        ));
+    }
+
+    #[test]
+    fn tree_summary_counts_entries() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs", "src/bin/main.rs", "a.txt"]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+
+        // The contents of every file is its name, so the sizes are the lengths of the names.
+        assert_eq!(
+            tree_summary(&repo, head, "").unwrap(),
+            TreeSummary {
+                file_count: 4,
+                dir_count: 2,
+                total_bytes: 27,
+            }
+        );
+        assert_eq!(
+            tree_summary(&repo, head, "/src/").unwrap(),
+            TreeSummary {
+                file_count: 2,
+                dir_count: 1,
+                total_bytes: 13,
+            }
+        );
+        assert!(matches!(
+            tree_summary(&repo, head, "missing"),
+            Err(error::Error::Source(radicle_source::Error::PathNotFound(_)))
+        ));
+        assert!(matches!(
+            tree_summary(&repo, head, "a.txt"),
+            Err(error::Error::NotADirectory(_))
+        ));
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+        let summary = tree_summary(&repo, head, "src").unwrap();
+        assert_eq!(
+            cached_tree_summary(&store, head, Some("src")).unwrap(),
+            None
+        );
+        cache_tree_summary(&store, head, Some("src"), summary).unwrap();
+        assert_eq!(
+            cached_tree_summary(&store, head, Some("/src/")).unwrap(),
+            Some(summary)
+        );
+        assert_eq!(cached_tree_summary(&store, head, None).unwrap(), None);
+    }
+
+    #[test]
+    fn stats_bounded_marks_approximate() {
+        let dir = repository(&[
+            ("alice", "Initial commit"),
+            ("bob", "Extend the docs"),
+            ("alice", "Fix typo in docs"),
+        ]);
+        let git2_repo = git2::Repository::open(dir.path()).unwrap();
+        let reference = namespaced(&git2_repo);
+        let head = resolve_revision(&git2_repo, &reference, None).unwrap();
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        assert_eq!(
+            stats_bounded(&git2_repo, &reference, head, 10).unwrap(),
+            Stats {
+                commits: 3,
+                branches: 1,
+                contributors: 2,
+                approximate: false,
+            }
+        );
+        assert_eq!(
+            stats_bounded(&git2_repo, &reference, head, 3).unwrap(),
+            Stats::from(browser.get_stats().unwrap())
+        );
+        assert_eq!(
+            stats_bounded(&git2_repo, &reference, head, 1).unwrap(),
+            Stats {
+                commits: 1,
+                branches: 1,
+                contributors: 1,
+                approximate: true,
+            }
+        );
+    }
+
+    #[test]
+    fn stats_bounded_only_count_reachable_commits() {
+        let dir = repository(&[("alice", "Initial commit"), ("bob", "Extend the docs")]);
+        {
+            let repo = git2::Repository::open(dir.path()).unwrap();
+            let head = repo
+                .find_reference("refs/heads/master")
+                .unwrap()
+                .peel_to_commit()
+                .unwrap();
+            let signature = git2::Signature::now("carol", "carol@example.com").unwrap();
+            repo.commit(
+                Some("refs/heads/dev"),
+                &signature,
+                &signature,
+                "Start the dev branch",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+        }
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let reference = namespaced(&repo);
+        let branch = |name: &str| {
+            resolve_revision(
+                &repo,
+                &reference,
+                Some(Revision::Branch {
+                    name: name.to_string(),
+                    peer_id: None,
+                }),
+            )
+        };
+
+        assert_eq!(
+            stats_bounded(&repo, &reference, branch("master").unwrap(), usize::MAX).unwrap(),
+            Stats {
+                commits: 2,
+                branches: 2,
+                contributors: 2,
+                approximate: false,
+            }
+        );
+        assert_eq!(
+            stats_bounded(&repo, &reference, branch("dev").unwrap(), usize::MAX).unwrap(),
+            Stats {
+                commits: 3,
+                branches: 2,
+                contributors: 3,
+                approximate: false,
+            }
+        );
+        assert!(matches!(
+            branch("missing"),
+            Err(error::Error::RevisionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_revision_not_found() {
+        let dir = repository_with_files(&["README.md"]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let revisions: [Revision<PeerId>; 3] = [
+            Revision::Branch {
+                name: "nope".to_string(),
+                peer_id: None,
+            },
+            Revision::Tag {
+                name: "nope".to_string(),
+            },
+            Revision::Sha {
+                sha: radicle_source::Oid(
+                    git2::Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap(),
+                ),
+            },
+        ];
+        for revision in revisions {
+            let name = revision_name(&revision);
+            let err = radicle_source::tree(&mut browser, Some(revision), None)
+                .map(|_| ())
+                .unwrap_err();
+            assert!(
+                matches!(
+                    revision_not_found(err.into(), Some(name.clone())),
+                    error::Error::RevisionNotFound(revision) if revision == name
+                ),
+                "expected `{}` to be reported as not found",
+                name
+            );
+        }
+
+        let err = radicle_source::tree(&mut browser, None, Some("missing".to_string()))
+            .map(|_| ())
+            .unwrap_err();
+        assert!(matches!(
+            revision_not_found(err.into(), Some("master".to_string())),
+            error::Error::Source(radicle_source::Error::PathNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn working_tree_diff_shows_modifications() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs"]);
+        let repo_path = dir.path().to_str().unwrap();
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+
+        let diff = working_tree_diff(repo_path).unwrap();
+        assert!(diff.modified.is_empty() && diff.created.is_empty() && diff.deleted.is_empty());
+
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn changed() {}\n").unwrap();
+        std::fs::remove_file(dir.path().join("README.md")).unwrap();
+        std::fs::write(dir.path().join("untracked.txt"), "new").unwrap();
+
+        let diff = working_tree_diff(repo_path).unwrap();
+        assert_eq!(
+            diff.modified
+                .iter()
+                .map(|file| file.path.to_string())
+                .collect::<Vec<_>>(),
+            vec![file_system::Path::from_str("src/lib.rs")
+                .unwrap()
+                .to_string()]
+        );
+        assert_eq!(diff.deleted.len(), 1);
+        assert!(diff.created.is_empty());
+    }
+
+    #[test]
+    fn tree_of_file_is_not_a_directory() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs"]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        assert_eq!(
+            tree(&mut browser, None, Some("src".to_string()))
+                .unwrap()
+                .entries
+                .len(),
+            1
+        );
+        assert!(matches!(
+            tree(&mut browser, None, Some("src/lib.rs".to_string())),
+            Err(error::Error::NotADirectory(path)) if path == "src/lib.rs"
+        ));
+        assert!(matches!(
+            tree(&mut browser, None, Some("missing".to_string())),
+            Err(error::Error::Source(radicle_source::Error::PathNotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn local_tree_and_blob() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs"]);
+        let repo_path = dir.path().to_str().unwrap();
+
+        let tree = local_tree(repo_path, None, None).unwrap();
+        assert_eq!(
+            tree.entries
+                .iter()
+                .map(|entry| entry.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["src", "README.md"]
+        );
+
+        let tree = local_tree(repo_path, None, Some("src".to_string())).unwrap();
+        assert_eq!(tree.entries.len(), 1);
+
+        let blob = local_blob(repo_path, None, "src/lib.rs").unwrap();
+        assert!(blob.content == BlobContent::Plain("lib.rs".to_string()));
+
+        let branch = Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        assert!(local_blob(repo_path, Some(branch), "README.md").is_ok());
+        assert!(local_blob(repo_path, None, "missing").is_err());
+    }
+
+    #[test]
+    fn blob_content_hash_is_stable() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs"]);
+        let repo_path = dir.path().to_str().unwrap();
+
+        let first = local_blob(repo_path, None, "src/lib.rs").unwrap();
+        let second = local_blob(repo_path, None, "src/lib.rs").unwrap();
+        assert_eq!(blob_content_hash(&first), blob_content_hash(&second));
+
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let oid = repo.revparse_single("master:src/lib.rs").unwrap().id();
+        assert_eq!(blob_content_hash(&first), oid.to_string());
+
+        let readme = local_blob(repo_path, None, "README.md").unwrap();
+        assert_ne!(blob_content_hash(&first), blob_content_hash(&readme));
+
+        let binary = |bytes: Vec<u8>| Blob {
+            content: BlobContent::Binary(bytes),
+            info: Info {
+                name: "image.png".to_string(),
+                object_type: ObjectType::Blob,
+                last_commit: None,
+            },
+            path: "image.png".to_string(),
+        };
+        let bytes = vec![0x89, 0x50, 0x4e, 0x47, 0x00, 0xff];
+        let oid = git2::Oid::hash_object(git2::ObjectType::Blob, &bytes).unwrap();
+        assert_eq!(blob_content_hash(&binary(bytes.clone())), oid.to_string());
+        assert_eq!(
+            blob_content_hash(&binary(bytes.clone())),
+            blob_content_hash(&binary(bytes))
+        );
+    }
+
+    #[test]
+    fn highlighted_blob_respects_limit() {
+        let dir = repository_with_files(&["src/lib.rs"]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+        let theme = Some("base16-ocean.dark");
+
+        // The content of the blob is "lib.rs".
+        let blob = highlighted_blob(&mut browser, None, "src/lib.rs", theme, 3).unwrap();
+        assert!(blob.truncated_highlight);
+        assert!(blob.blob.content == BlobContent::Plain("lib.rs".to_string()));
+        let json = serde_json::to_value(&blob).unwrap();
+        assert_eq!(json["truncatedHighlight"], true);
+        assert_eq!(json["path"], "src/lib.rs");
+
+        let blob = highlighted_blob(&mut browser, None, "src/lib.rs", theme, 1024).unwrap();
+        assert!(!blob.truncated_highlight);
+        assert!(blob.blob.is_html());
+
+        let blob = highlighted_blob(&mut browser, None, "src/lib.rs", None, 3).unwrap();
+        assert!(!blob.truncated_highlight);
+        assert!(blob.blob.content == BlobContent::Plain("lib.rs".to_string()));
+    }
+
+    #[test]
+    fn highlighted_blob_flags_lfs_pointers() {
+        const POINTER: &str = "version https://git-lfs.github.com/spec/v1
+oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+size 12345
+";
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        let pointer = repo.blob(POINTER.as_bytes()).unwrap();
+        builder.insert("logo.png", pointer, 0o100_644).unwrap();
+        let readme = repo.blob(b"version https://git-lfs is used").unwrap();
+        builder.insert("README.md", readme, 0o100_644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+        repo.commit(
+            Some("refs/heads/master"),
+            &signature,
+            &signature,
+            "Add logo",
+            &tree,
+            &[],
+        )
+        .unwrap();
+
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+        let theme = Some("base16-ocean.dark");
+
+        let blob = highlighted_blob(&mut browser, None, "logo.png", theme, 1024).unwrap();
+        assert!(blob.lfs);
+        assert_eq!(
+            blob.lfs_pointer,
+            Some(LfsPointer {
+                oid: "sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393"
+                    .to_string(),
+                size: 12345,
+            })
+        );
+        assert!(blob.blob.content == BlobContent::Plain(POINTER.to_string()));
+        let json = serde_json::to_value(&blob).unwrap();
+        assert_eq!(json["lfs"], true);
+        assert_eq!(json["lfsPointer"]["size"], 12345);
+
+        let blob = highlighted_blob(&mut browser, None, "README.md", theme, 1024).unwrap();
+        assert!(!blob.lfs);
+        assert_eq!(blob.lfs_pointer, None);
+        assert!(blob.blob.is_html());
+    }
+
+    #[test]
+    fn blob_lines_clamps_range() {
+        let blob = || HighlightedBlob {
+            blob: Blob {
+                content: BlobContent::Plain("→\n↑\n←\n↓\n↔\n".to_string()),
+                info: Info {
+                    name: "arrows.txt".to_string(),
+                    object_type: ObjectType::Blob,
+                    last_commit: None,
+                },
+                path: "text/arrows.txt".to_string(),
+            },
+            truncated_highlight: false,
+            lfs: false,
+            lfs_pointer: None,
+            lines: None,
+        };
+
+        let lines = blob_lines(blob(), Some(2), Some(4), None, 1024);
+        assert!(lines.blob.content == BlobContent::Plain("↑\n←\n↓\n".to_string()));
+        assert_eq!(
+            lines.lines,
+            Some(LineRange {
+                start_line: 2,
+                end_line: 4,
+                line_count: 5,
+            })
+        );
+        let json = serde_json::to_value(&lines).unwrap();
+        assert_eq!(json["lines"]["lineCount"], 5);
+
+        let lines = blob_lines(blob(), Some(4), Some(100), None, 1024);
+        assert!(lines.blob.content == BlobContent::Plain("↓\n↔\n".to_string()));
+        assert_eq!(lines.lines.map(|lines| lines.end_line), Some(5));
+
+        let lines = blob_lines(blob(), Some(9), Some(2), None, 1024);
+        assert!(lines.blob.content == BlobContent::Plain("↔\n".to_string()));
+        assert_eq!(lines.lines.map(|lines| lines.start_line), Some(5));
+
+        let lines = blob_lines(blob(), None, Some(1), Some("base16-ocean.dark"), 1);
+        assert!(lines.truncated_highlight);
+        assert!(lines.blob.content == BlobContent::Plain("→\n".to_string()));
+
+        let binary = HighlightedBlob {
+            blob: Blob {
+                content: BlobContent::Binary(vec![0xff]),
+                ..blob().blob
+            },
+            ..blob()
+        };
+        let lines = blob_lines(binary, Some(2), Some(4), None, 1024);
+        assert!(lines.blob.content == BlobContent::Binary(vec![0xff]));
+        assert_eq!(lines.lines, None);
+    }
+
+    #[test]
+    fn find_readme_in_directory() {
+        let dir =
+            repository_with_files(&["README.md", "docs/guide.md", "docs/readme", "src/lib.rs"]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let readme = find_readme(&mut browser, None, None, None, 1024)
+            .unwrap()
+            .unwrap();
+        assert_eq!(readme.blob.path, "README.md");
+        assert!(readme.blob.content == BlobContent::Plain("README.md".to_string()));
+
+        let readme = find_readme(
+            &mut browser,
+            None,
+            Some("docs".to_string()),
+            Some("base16-ocean.dark"),
+            1024,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(readme.blob.path, "docs/readme");
+        assert!(readme.blob.is_html());
+
+        assert!(
+            find_readme(&mut browser, None, Some("src".to_string()), None, 1024)
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            find_readme(&mut browser, None, Some("missing".to_string()), None, 1024),
+            Err(radicle_source::Error::PathNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn submodule_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let target = git2::Oid::hash_object(git2::ObjectType::Commit, b"submodule").unwrap();
+
+        let mut vendor = repo.treebuilder(None).unwrap();
+        vendor.insert("lib", target, 0o160_000).unwrap();
+        let readme = repo.blob(b"Vendored dependencies").unwrap();
+        vendor.insert("README", readme, 0o100_644).unwrap();
+        let vendor = vendor.write().unwrap();
+        let gitmodules = repo
+            .blob(
+                b"[submodule \"lib\"]\n\tpath = vendor/lib\n\turl = https://example.com/lib.git\n",
+            )
+            .unwrap();
+        let mut root = repo.treebuilder(None).unwrap();
+        root.insert(".gitmodules", gitmodules, 0o100_644).unwrap();
+        root.insert("vendor", vendor, 0o040_000).unwrap();
+        root.insert("other", target, 0o160_000).unwrap();
+        let tree = repo.find_tree(root.write().unwrap()).unwrap();
+        let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+        let commit = repo
+            .commit(
+                Some("refs/heads/master"),
+                &signature,
+                &signature,
+                "Add submodules",
+                &tree,
+                &[],
+            )
+            .unwrap();
+
+        let submodules = submodules(&repo, commit, Some("vendor")).unwrap();
+        assert_eq!(
+            submodules,
+            vec![Submodule {
+                name: "lib".to_string(),
+                path: "vendor/lib".to_string(),
+                oid: target,
+                url: Some("https://example.com/lib.git".to_string()),
+            }]
+        );
+        assert!(super::submodules(&repo, commit, Some("missing"))
+            .unwrap()
+            .is_empty());
+
+        let surf_repo = git::Repository::new(dir.path()).unwrap();
+        let mut browser = git::Browser::new(&surf_repo, git::Branch::local("master")).unwrap();
+        let tree = TreeWithSubmodules {
+            tree: radicle_source::tree::<PeerId>(&mut browser, None, Some("vendor".to_string()))
+                .unwrap(),
+            submodules,
+        };
+        let json = serde_json::to_value(&tree).unwrap();
+        assert_eq!(json["entries"][0]["path"], "vendor/README");
+        assert_eq!(
+            json["entries"][1],
+            serde_json::json!({
+                "path": "vendor/lib",
+                "info": { "name": "lib", "objectType": "SUBMODULE", "lastCommit": null },
+                "submodule": { "oid": target.to_string(), "url": "https://example.com/lib.git" },
+            })
+        );
+
+        let root = super::submodules(&repo, commit, None).unwrap();
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0].path, "other");
+        assert_eq!(root[0].url, None);
+    }
+
+    #[test]
+    fn parse_gitmodules_sections() {
+        let urls = parse_gitmodules(
+            "# path = ignored\n[submodule \"a\"]\n  path = a/\n  url = \"git@example.com:a.git\"\n[submodule \"b\"]\n  path = b\n",
+        );
+        assert_eq!(urls.len(), 1);
+        assert_eq!(
+            urls.get("a").map(String::as_str),
+            Some("git@example.com:a.git")
+        );
+    }
+
+    #[test]
+    fn branch_exists_lookup() {
+        let dir = repository(&[("alice", "Initial commit")]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        assert!(branch_exists(&browser, "master").unwrap());
+        assert!(!branch_exists(&browser, "does-not-exist").unwrap());
+        assert!(!branch_exists(&browser, "master^").unwrap());
+    }
+
+    #[test]
+    fn scoped_refs_report_remote() {
+        let peer_id = PeerId::from(link_crypto::SecretKey::new());
+        let dir = repository(&[("alice", "Initial commit")]);
+        {
+            let repo = git2::Repository::open(dir.path()).unwrap();
+            let head = repo
+                .find_reference("refs/heads/master")
+                .unwrap()
+                .peel_to_commit()
+                .unwrap()
+                .id();
+            repo.reference("refs/tags/v0.1.0", head, false, "local tag")
+                .unwrap();
+            repo.reference(
+                &format!("refs/remotes/{}/heads/feature", peer_id),
+                head,
+                false,
+                "remote branch",
+            )
+            .unwrap();
+            repo.reference(
+                &format!("refs/remotes/{}/tags/v0.2.0", peer_id),
+                head,
+                false,
+                "remote tag",
+            )
+            .unwrap();
+        }
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+
+        let local = ScopedRef::all(
+            radicle_source::branches(&browser, RefScope::Local).unwrap(),
+            None,
+        );
+        assert_eq!(
+            local,
+            vec![ScopedRef {
+                name: Branch::from("master".to_string()),
+                remote: None,
+            }]
+        );
+        assert_eq!(
+            serde_json::to_value(&local).unwrap(),
+            serde_json::json!(["master"])
+        );
+
+        let remote = ScopedRef::all(
+            radicle_source::branches(&browser, RefScope::from(Some(peer_id))).unwrap(),
+            Some(peer_id),
+        );
+        assert_eq!(
+            remote,
+            vec![ScopedRef {
+                name: Branch::from("feature".to_string()),
+                remote: Some(peer_id),
+            }]
+        );
+        assert_eq!(
+            serde_json::to_value(&remote).unwrap(),
+            serde_json::json!([{ "name": "feature", "remote": peer_id.to_string() }])
+        );
+
+        assert_eq!(
+            tags(&browser, None).unwrap(),
+            vec![Tag::from("v0.1.0".to_string())]
+        );
+        assert_eq!(
+            tags(&browser, Some(peer_id)).unwrap(),
+            vec![Tag::from("v0.2.0".to_string())]
+        );
+    }
+
+    #[test]
+    fn sort_tags_semver() {
+        let tags = || -> Vec<Tag> {
+            ["v0.9.0", "v0.10.0", "v0.2.0"]
+                .iter()
+                .map(|name| Tag::from(name.to_string()))
+                .collect()
+        };
+        let names = |tags: &[Tag]| tags.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        let mut semver = tags();
+        sort_tags(&mut semver, TagSort::Semver);
+        assert_eq!(names(&semver), vec!["v0.2.0", "v0.9.0", "v0.10.0"]);
+
+        let mut lexical = tags();
+        sort_tags(&mut lexical, TagSort::Lexical);
+        assert_eq!(names(&lexical), vec!["v0.10.0", "v0.2.0", "v0.9.0"]);
+
+        let mut mixed = ["release", "v1.0.0", "v1.0.0-rc.1", "0.1.0"]
+            .iter()
+            .map(|name| Tag::from(name.to_string()))
+            .collect::<Vec<_>>();
+        sort_tags(&mut mixed, TagSort::Semver);
+        assert_eq!(
+            names(&mixed),
+            vec!["0.1.0", "v1.0.0-rc.1", "v1.0.0", "release"]
+        );
+
+        // The precedence example of the semantic versioning spec, plus a numeric identifier
+        // that orders differently when compared lexically.
+        let expected = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0-rc.2",
+            "1.0.0-rc.10",
+            "1.0.0",
+        ];
+        let mut pre_releases = expected
+            .iter()
+            .rev()
+            .map(|name| Tag::from(name.to_string()))
+            .collect::<Vec<_>>();
+        sort_tags(&mut pre_releases, TagSort::Semver);
+        assert_eq!(names(&pre_releases), expected);
+    }
+
+    #[test]
+    fn list_objects_respects_limit() {
+        let dir = repository(&[
+            ("alice", "Initial commit"),
+            ("bob", "Extend the docs"),
+            ("alice", "Fix typo in docs"),
+        ]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+        let mut revwalk = repo.revwalk().unwrap();
+        revwalk.push(head).unwrap();
+        let commits = revwalk
+            .map(|oid| oid.unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            list_objects(&repo, head, ObjectKind::Commit, 10).unwrap(),
+            commits
+        );
+        assert_eq!(
+            list_objects(&repo, head, ObjectKind::Commit, 2).unwrap(),
+            commits[..2]
+        );
+
+        let dir = repository_with_files(&["README.md", "src/lib.rs", "src/bin/main.rs"]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+        let blobs = list_objects(&repo, head, ObjectKind::Blob, 10).unwrap();
+        assert_eq!(blobs.len(), 3);
+        assert!(blobs.contains(&repo.blob(b"lib.rs").unwrap().to_string()));
+        assert_eq!(
+            list_objects(&repo, head, ObjectKind::Tree, 10)
+                .unwrap()
+                .len(),
+            3
+        );
+        assert_eq!(
+            list_objects(&repo, head, ObjectKind::Tree, 1)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn merge_preview_fast_forward() {
+        let (dir, commits) = merge_repository();
+        let repo = git2::Repository::open(dir.path()).unwrap();
+
+        let preview = merge_preview(&repo, commits.base, commits.head).unwrap();
+        assert_eq!(
+            preview,
+            MergePreview {
+                merge_base: commits.base.to_string(),
+                conflicts: vec![],
+                fast_forward: true,
+            }
+        );
+
+        let preview = merge_preview(&repo, commits.head, commits.side).unwrap();
+        assert_eq!(preview.merge_base, commits.base.to_string());
+        assert!(preview.conflicts.is_empty());
+        assert!(!preview.fast_forward);
+    }
+
+    #[test]
+    fn merge_preview_conflicts() {
+        let dir = repository_with_files(&["README.md", "src/lib.rs"]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let root = repo
+            .find_reference("refs/heads/master")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap();
+        let signature = git2::Signature::now("alice", "alice@example.com").unwrap();
+        let change_readme = |content: &str, parents: &[&git2::Commit<'_>]| {
+            let mut builder = repo.treebuilder(Some(&root.tree().unwrap())).unwrap();
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            builder.insert("README.md", blob, 0o100_644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            repo.commit(None, &signature, &signature, content, &tree, parents)
+                .unwrap()
+        };
+
+        let ours = change_readme("ours", &[&root]);
+        let theirs = change_readme("theirs", &[&root]);
+        let preview = merge_preview(&repo, ours, theirs).unwrap();
+        assert_eq!(preview.merge_base, root.id().to_string());
+        assert_eq!(preview.conflicts, vec!["README.md"]);
+        assert!(!preview.fast_forward);
+
+        let unrelated = change_readme("unrelated", &[]);
+        assert!(matches!(
+            merge_preview(&repo, ours, unrelated),
+            Err(error::Error::UnrelatedHistories { .. })
+        ));
+    }
+
+    #[test]
+    fn languages_by_extension() {
+        let dir = repository_with_files(&[
+            "src/main.rs",
+            "src/lib.rs",
+            "ui/App.svelte",
+            "ui/index.ts",
+            "vendor/dep.rs",
+            "node_modules/pkg/index.js",
+            "README.md",
+        ]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+
+        // The contents of every file is its name, so the sizes are the lengths of the names.
+        assert_eq!(
+            languages(&repo, head, &CancellationToken::new()).unwrap(),
+            vec![
+                LanguageStat {
+                    language: "Rust".to_string(),
+                    bytes: 13,
+                    percentage: 41.9,
+                },
+                LanguageStat {
+                    language: "Svelte".to_string(),
+                    bytes: 10,
+                    percentage: 32.3,
+                },
+                LanguageStat {
+                    language: "TypeScript".to_string(),
+                    bytes: 8,
+                    percentage: 25.8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn cancelled_walks_give_up() {
+        let dir = repository(&[("alice", "Initial commit"), ("bob", "Extend the docs")]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+        let cancel = CancellationToken::new();
+
+        let commits = master_commits(dir.path(), TimeWindow::default(), &cancel).unwrap();
+        assert_eq!(commits.headers.len(), 2);
+        assert_eq!(commits.stats.commits, 2);
+        assert_eq!(commits.stats.contributors, 2);
+
+        cancel.cancel();
+        assert!(matches!(
+            master_commits(dir.path(), TimeWindow::default(), &cancel),
+            Err(error::Error::Cancelled)
+        ));
+        assert!(matches!(
+            languages(&repo, head, &cancel),
+            Err(error::Error::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn cached_languages_by_oid() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = kv::Store::new(kv::Config::new(temp_dir.path().join("store"))).unwrap();
+        let oid = git2::Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        let stats = vec![LanguageStat {
+            language: "Rust".to_string(),
+            bytes: 42,
+            percentage: 100.0,
+        }];
+
+        assert_eq!(cached_languages(&store, oid).unwrap(), None);
+        cache_languages(&store, oid, &stats).unwrap();
+        assert_eq!(cached_languages(&store, oid).unwrap(), Some(stats.clone()));
+
+        let oids = (0..=MAX_CACHED_ENTRIES)
+            .map(|i| git2::Oid::from_str(&format!("{:040x}", i)).unwrap())
+            .collect::<Vec<_>>();
+        for oid in &oids {
+            cache_languages(&store, *oid, &stats).unwrap();
+        }
+        let cached = oids
+            .iter()
+            .chain(std::iter::once(&oid))
+            .filter(|oid| cached_languages(&store, **oid).unwrap().is_some())
+            .count();
+        assert_eq!(cached, MAX_CACHED_ENTRIES);
+        assert!(cached_languages(&store, oids[MAX_CACHED_ENTRIES])
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn resolve_rev_short_hash() {
+        let dir = repository(&[("alice", "Initial commit"), ("bob", "Extend the docs")]);
+        let repo = git::Repository::new(dir.path()).unwrap();
+        let browser = git::Browser::new(&repo, git::Branch::local("master")).unwrap();
+        let head = browser.get().first().id;
+
+        let short = head.to_string()[..7].to_string();
+        assert_eq!(resolve_rev(&browser, &short).unwrap(), head);
+        assert_eq!(resolve_rev(&browser, "master").unwrap(), head);
+        assert!(resolve_rev(&browser, "does-not-exist").is_err());
+    }
+
+    #[test]
+    fn search_commits_by_author() {
+        let dir = repository(&[
+            ("alice", "Initial commit"),
+            ("bob", "Extend the docs"),
+            ("alice", "Fix typo in docs"),
+        ]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+
+        let headers =
+            search_commits(&repo, head, "bob@example.com", SearchField::Author, 10).unwrap();
+        assert_eq!(summaries(&headers), vec!["Extend the docs"]);
+    }
+
+    #[test]
+    fn search_commits_stops_at_limit() {
+        let dir = repository(&[
+            ("alice", "Initial commit"),
+            ("bob", "Extend the docs"),
+            ("alice", "Fix typo in docs"),
+        ]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+
+        // Removing the root commit breaks the history below the first match, which is only
+        // noticed if the walk goes on after `limit` matches.
+        let root = repo.revparse_single("master~2").unwrap().id();
+        std::fs::remove_file(dir.path().join(".git/objects").join(format!(
+            "{}/{}",
+            &root.to_string()[..2],
+            &root.to_string()[2..]
+        )))
+        .unwrap();
+
+        let headers = search_commits(&repo, head, "docs", SearchField::Message, 1).unwrap();
+        assert_eq!(summaries(&headers), vec!["Fix typo in docs"]);
+        assert!(search_commits(&repo, head, "docs", SearchField::Message, 10).is_err());
+    }
+
+    #[test]
+    fn resolve_revision_in_namespace() {
+        let dir = repository(&[("alice", "Initial commit"), ("bob", "Extend the docs")]);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let head = repo.refname_to_id("refs/heads/master").unwrap();
+        let reference = namespaced(&repo);
+
+        assert_eq!(resolve_revision(&repo, &reference, None).unwrap(), head);
+        assert_eq!(
+            resolve_revision(
+                &repo,
+                &reference,
+                Some(Revision::Branch {
+                    name: "master".to_string(),
+                    peer_id: None,
+                })
+            )
+            .unwrap(),
+            head
+        );
+        assert!(matches!(
+            resolve_revision(
+                &repo,
+                &reference,
+                Some(Revision::Tag {
+                    name: "v1.0.0".to_string(),
+                })
+            ),
+            Err(error::Error::RevisionNotFound(name)) if name == "v1.0.0"
+        ));
+    }
+}